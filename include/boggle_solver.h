@@ -0,0 +1,43 @@
+/* C interface to boggle_solver, built with `cargo build --features ffi`.
+ *
+ * Strings are NUL-terminated UTF-8. A function returning a pointer returns NULL on
+ * failure; boggle_last_error() then describes what went wrong. Every non-NULL
+ * dictionary and result must be released with its _free function. */
+
+#ifndef BOGGLE_SOLVER_H
+#define BOGGLE_SOLVER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Dictionary BoggleDictionary;
+typedef struct FfiResult BoggleResult;
+
+/* Message from the last failed call on this thread, or NULL. Valid until the next
+ * failing call on the same thread. */
+const char *boggle_last_error(void);
+
+/* Loads a newline-separated word list. */
+BoggleDictionary *boggle_dict_load(const char *path);
+void boggle_dict_free(BoggleDictionary *dict);
+
+/* Solves a rows x cols board given as its tiles in row order ("srpseuimeahwwdzr");
+ * q is the Qu tile. The dictionary is only read, so threads may share one. */
+BoggleResult *boggle_solve(const BoggleDictionary *dict, const char *board, size_t rows, size_t cols);
+void boggle_result_free(BoggleResult *result);
+
+size_t boggle_result_count(const BoggleResult *result);
+uint32_t boggle_result_total_score(const BoggleResult *result);
+/* Words are ordered longest first; the string is owned by the result. NULL past the end. */
+const char *boggle_result_word(const BoggleResult *result, size_t index);
+uint32_t boggle_result_word_score(const BoggleResult *result, size_t index);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BOGGLE_SOLVER_H */
@@ -0,0 +1,483 @@
+use crate::dawg::Dawg;
+use crate::trie::{WordIndex, WordNode};
+
+// Index 0 is the root, which is nobody's child or sibling, so 0 doubles as "none"
+const NONE: u32 = 0;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    letter: char,
+    is_word: bool,
+    // Words in this node's subtree, its own included
+    words: u32,
+    first_child: u32,
+    next_sibling: u32,
+    // Bit n is set when there is a child for the ASCII capital 'A' + n. Most lookups
+    // in a search miss, and this answers those without touching the children.
+    ascii: u32,
+}
+
+// The bit in `Node::ascii` for `ch`, or None for anything outside A-Z
+fn ascii_bit(ch: char) -> Option<u32> {
+    ch.is_ascii_uppercase().then(|| 1 << (ch as u32 - 'A' as u32))
+}
+
+/// A trie kept in one contiguous `Vec`. Each node links to its first child and its next
+/// sibling by index, with siblings in letter order, so walking it touches one
+/// allocation and dropping it is a single free instead of one per node.
+#[derive(Debug, Clone)]
+pub struct ArenaTrie {
+    nodes: Vec<Node>,
+    // Nodes unlinked by `remove`; they stay in the arena but are no longer reachable
+    unlinked: usize,
+}
+
+/// A node of an `ArenaTrie`: the arena and a position in it.
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaNode<'a> {
+    trie: &'a ArenaTrie,
+    index: u32,
+}
+
+impl Default for ArenaTrie {
+    fn default() -> Self {
+        ArenaTrie::new()
+    }
+}
+
+impl ArenaTrie {
+    pub fn new() -> Self {
+        let root = Node { letter: '\0', is_word: false, words: 0, first_child: NONE, next_sibling: NONE, ascii: 0 };
+        ArenaTrie { nodes: vec![root], unlinked: 0 }
+    }
+
+    /// Adds `word`, returning whether it was not already present.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut path = vec![0];
+        for ch in word.chars() {
+            path.push(self.child_or_insert(*path.last().unwrap_or(&0), ch));
+        }
+        let last = *path.last().unwrap_or(&0) as usize;
+        if std::mem::replace(&mut self.nodes[last].is_word, true) {
+            return false;
+        }
+        for node in path {
+            self.nodes[node as usize].words += 1;
+        }
+        true
+    }
+
+    // The child of `parent` for `ch`, linked in at its place in letter order if new
+    fn child_or_insert(&mut self, parent: u32, ch: char) -> u32 {
+        let (prev, next) = self.place(parent, ch);
+        if next != NONE && self.nodes[next as usize].letter == ch {
+            return next;
+        }
+
+        let index = u32::try_from(self.nodes.len()).expect("trie has more than u32::MAX nodes");
+        self.nodes.push(Node { letter: ch, is_word: false, words: 0, first_child: NONE, next_sibling: next, ascii: 0 });
+        self.link(parent, prev, index);
+        index
+    }
+
+    // The children of `parent` just before `ch` and at or after it, NONE if there are none
+    fn place(&self, parent: u32, ch: char) -> (u32, u32) {
+        let mut prev = NONE;
+        let mut next = self.nodes[parent as usize].first_child;
+        while next != NONE && self.nodes[next as usize].letter < ch {
+            prev = next;
+            next = self.nodes[next as usize].next_sibling;
+        }
+        (prev, next)
+    }
+
+    // Makes `index` a child of `parent`, after sibling `prev` (NONE for the first child)
+    fn link(&mut self, parent: u32, prev: u32, index: u32) {
+        self.nodes[parent as usize].ascii |= ascii_bit(self.nodes[index as usize].letter).unwrap_or(0);
+        match prev {
+            NONE => self.nodes[parent as usize].first_child = index,
+            prev => self.nodes[prev as usize].next_sibling = index,
+        }
+    }
+
+    /// Adds every word of `other`. When `other` holds only words starting with a letter
+    /// this trie has none for, as the subtries of a parallel build do, its nodes are
+    /// copied across in one go; otherwise they are merged in node by node.
+    pub fn graft(&mut self, other: &ArenaTrie) {
+        let top = other.nodes[0].first_child;
+        let letter = other.nodes[top as usize].letter;
+        let single = top != NONE && other.nodes[top as usize].next_sibling == NONE && other.unlinked == 0;
+        if !single || self.root().child(letter).is_some() {
+            self.merge(0, other.root());
+            self.count_words(0);
+            return;
+        }
+
+        // Node i of `other` becomes node i + base here, the root aside
+        let base = u32::try_from(self.nodes.len() - 1).expect("trie has more than u32::MAX nodes");
+        let moved = |i: u32| if i == NONE { NONE } else { i + base };
+        self.nodes.extend(other.nodes[1..].iter().map(|node| Node {
+            first_child: moved(node.first_child),
+            next_sibling: moved(node.next_sibling),
+            ..*node
+        }));
+        let (prev, next) = self.place(0, letter);
+        self.nodes[moved(top) as usize].next_sibling = next;
+        self.link(0, prev, moved(top));
+        self.nodes[0].words += other.nodes[0].words;
+    }
+
+    fn merge(&mut self, parent: u32, other: ArenaNode) {
+        for (ch, child) in other.children() {
+            let index = self.child_or_insert(parent, ch);
+            self.nodes[index as usize].is_word |= child.is_word();
+            self.merge(index, child);
+        }
+    }
+
+    // Sets `words` on `node` and everything below it from the `is_word` flags
+    fn count_words(&mut self, node: u32) -> u32 {
+        let children: Vec<u32> = self.at(node).child_indices().collect();
+        let below: u32 = children.into_iter().map(|child| self.count_words(child)).sum();
+        let here = &mut self.nodes[node as usize];
+        here.words = below + u32::from(here.is_word);
+        here.words
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut node = self.root();
+        for ch in word.chars() {
+            match node.child(ch) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        node.is_word()
+    }
+
+    /// Removes `word`, unlinking nodes that no longer lead to any word. Returns whether
+    /// it was present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let mut path = vec![0u32];
+        for ch in word.chars() {
+            match self.at(*path.last().unwrap_or(&0)).child(ch) {
+                Some(next) => path.push(next.index),
+                None => return false,
+            }
+        }
+        let last = *path.last().unwrap_or(&0) as usize;
+        if !std::mem::replace(&mut self.nodes[last].is_word, false) {
+            return false;
+        }
+        for &node in &path {
+            self.nodes[node as usize].words -= 1;
+        }
+
+        // Walk back up, dropping each node left with no word and no children
+        while let [.., parent, node] = path[..] {
+            let dead = self.nodes[node as usize];
+            if dead.is_word || dead.first_child != NONE {
+                break;
+            }
+            if self.nodes[parent as usize].first_child == node {
+                self.nodes[parent as usize].first_child = dead.next_sibling;
+            } else {
+                let mut sibling = self.nodes[parent as usize].first_child;
+                while self.nodes[sibling as usize].next_sibling != node {
+                    sibling = self.nodes[sibling as usize].next_sibling;
+                }
+                self.nodes[sibling as usize].next_sibling = dead.next_sibling;
+            }
+            self.nodes[parent as usize].ascii &= !ascii_bit(dead.letter).unwrap_or(0);
+            self.unlinked += 1;
+            path.pop();
+        }
+        true
+    }
+
+    /// Number of reachable nodes, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() - self.unlinked
+    }
+
+    /// Bytes the arena has allocated, unlinked nodes and spare capacity included.
+    pub fn heap_bytes(&self) -> usize {
+        self.nodes.capacity() * std::mem::size_of::<Node>()
+    }
+
+    pub fn root(&self) -> ArenaNode<'_> {
+        self.at(0)
+    }
+
+    fn at(&self, index: u32) -> ArenaNode<'_> {
+        ArenaNode { trie: self, index }
+    }
+
+    /// Builds the minimized automaton with identical suffix subtrees merged.
+    pub fn minimize(&self) -> Dawg {
+        Dawg::from_trie(self.root())
+    }
+}
+
+impl WordIndex for ArenaTrie {
+    type Node<'a> = ArenaNode<'a>;
+
+    fn root(&self) -> ArenaNode<'_> {
+        ArenaTrie::root(self)
+    }
+}
+
+impl<'a> ArenaNode<'a> {
+    fn node(self) -> &'a Node {
+        &self.trie.nodes[self.index as usize]
+    }
+
+    // The children in letter order, as arena indices
+    fn child_indices(self) -> impl Iterator<Item = u32> + 'a {
+        let nodes = &self.trie.nodes;
+        std::iter::successors(Some(self.node().first_child), move |&i| Some(nodes[i as usize].next_sibling))
+            .take_while(|&i| i != NONE)
+    }
+}
+
+impl<'a> WordNode for ArenaNode<'a> {
+    fn child(self, ch: char) -> Option<Self> {
+        let nodes = &self.trie.nodes;
+        let here = self.node();
+        if let Some(bit) = ascii_bit(ch)
+            && here.ascii & bit == 0
+        {
+            return None;
+        }
+        let mut index = here.first_child;
+        while index != NONE {
+            let node = &nodes[index as usize];
+            // Siblings are sorted, so the scan can stop at the first letter not before `ch`
+            if node.letter >= ch {
+                return (node.letter == ch).then_some(ArenaNode { trie: self.trie, index });
+            }
+            index = node.next_sibling;
+        }
+        None
+    }
+
+    fn is_word(self) -> bool {
+        self.node().is_word
+    }
+
+    fn children(self) -> Vec<(char, Self)> {
+        self.child_indices().map(|index| (self.trie.nodes[index as usize].letter, self.trie.at(index))).collect()
+    }
+
+    fn subtree(self) -> Option<(usize, u32)> {
+        Some((self.index as usize, self.node().words))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::locale::Locale;
+    use crate::rng::Rng;
+    use crate::solver::BoggleSolver;
+    use crate::trie::TrieNode;
+
+    // Capitals that take the presence mask and letters that walk the siblings instead
+    const LETTERS: [char; 9] = ['A', 'B', 'E', 'R', 'S', 'T', 'É', 'ß', 'Ω'];
+
+    fn random_words(rng: &mut Rng, count: usize) -> Vec<String> {
+        (0..count).map(|_| (0..1 + rng.below(6)).map(|_| LETTERS[rng.below(LETTERS.len())]).collect()).collect()
+    }
+
+    // Every word below both nodes, with the same letters in the same order, or a panic
+    // naming the first prefix where they differ
+    fn assert_same<A: WordNode, B: WordNode>(a: A, b: B, prefix: &mut String) {
+        assert_eq!(a.is_word(), b.is_word(), "is_word at {:?}", prefix);
+        let (a, b) = (a.children(), b.children());
+        let a_letters: Vec<char> = a.iter().map(|&(ch, _)| ch).collect();
+        let b_letters: Vec<char> = b.iter().map(|&(ch, _)| ch).collect();
+        assert_eq!(a_letters, b_letters, "children of {:?}", prefix);
+        for (&(ch, a), &(_, b)) in a.iter().zip(&b) {
+            prefix.push(ch);
+            assert_same(a, b, prefix);
+            prefix.pop();
+        }
+    }
+
+    // The words counted under each arena node match its subtree
+    fn words_below(node: ArenaNode) -> u32 {
+        let below = node.children().into_iter().map(|(_, child)| words_below(child)).sum::<u32>();
+        let words = below + u32::from(node.is_word());
+        assert_eq!(node.subtree().map(|(_, words)| words), Some(words));
+        words
+    }
+
+    #[test]
+    fn arena_matches_the_hashmap_trie() {
+        let mut rng = Rng::new(43);
+        for _ in 0..20 {
+            let (mut arena, mut map) = (ArenaTrie::new(), TrieNode::default());
+            let words = random_words(&mut rng, 400);
+            for word in &words {
+                assert_eq!(arena.insert(word), map.insert(word), "insert {:?}", word);
+            }
+            assert_same(arena.root(), &map, &mut String::new());
+            assert_eq!(arena.node_count(), map.node_count());
+            words_below(arena.root());
+            for probe in words.iter().cloned().chain(random_words(&mut rng, 400)) {
+                assert_eq!(arena.contains(&probe), map.contains(&probe), "contains {:?}", probe);
+            }
+            assert!(!arena.contains("") && !map.contains(""));
+
+            for word in words.iter().chain(&random_words(&mut rng, 100)).step_by(2) {
+                assert_eq!(arena.remove(word), map.remove(word), "remove {:?}", word);
+            }
+            assert_same(arena.root(), &map, &mut String::new());
+            assert_eq!(arena.node_count(), map.node_count());
+            words_below(arena.root());
+        }
+    }
+
+    #[test]
+    fn grafted_tries_match_one_built_whole() {
+        let mut rng = Rng::new(143);
+        for _ in 0..20 {
+            let words = random_words(&mut rng, 300);
+            let mut map = TrieNode::default();
+            words.iter().for_each(|word| _ = map.insert(word));
+
+            // By first letter, as a parallel build splits them, then in halves that overlap
+            let mut by_letter = ArenaTrie::new();
+            for letter in LETTERS {
+                let mut part = ArenaTrie::new();
+                words.iter().filter(|word| word.starts_with(letter)).for_each(|word| _ = part.insert(word));
+                if part.node_count() > 1 {
+                    by_letter.graft(&part);
+                }
+            }
+            let (mut halves, mut second) = (ArenaTrie::new(), ArenaTrie::new());
+            words[..200].iter().for_each(|word| _ = halves.insert(word));
+            words[100..].iter().for_each(|word| _ = second.insert(word));
+            halves.graft(&second);
+
+            for arena in [by_letter, halves] {
+                assert_same(arena.root(), &map, &mut String::new());
+                assert_eq!(arena.node_count(), map.node_count());
+                words_below(arena.root());
+            }
+        }
+    }
+
+    // Every node of both, visited together, answers each letter the same way
+    fn assert_same_lookups<A: WordNode, B: WordNode>(a: A, b: B, prefix: &mut String) {
+        let probes = ('A'..='Z').chain('a'..='z').chain(LETTERS).chain(['@', '[', 'Ä', 'é', '\0']);
+        for ch in probes {
+            assert_eq!(a.child(ch).is_some(), b.child(ch).is_some(), "{:?} after {:?}", ch, prefix);
+        }
+        for ((ch, a), (_, b)) in a.children().into_iter().zip(b.children()) {
+            prefix.push(ch);
+            assert_same_lookups(a, b, prefix);
+            prefix.pop();
+        }
+    }
+
+    // The words a plain search of `tiles` finds below `root`, with `?` standing for any
+    // letter and no cell used twice
+    fn board_words<N: WordNode>(root: N, tiles: &[Vec<String>]) -> Vec<String> {
+        fn visit<N: WordNode>(
+            node: N,
+            (r, c): (usize, usize),
+            tiles: &[Vec<String>],
+            word: &mut String,
+            visited: &mut Vec<Vec<bool>>,
+            found: &mut Vec<String>,
+        ) {
+            if visited[r][c] {
+                return;
+            }
+            let tile = &tiles[r][c];
+            let nexts: Vec<(String, N)> = if tile == "?" {
+                node.children().into_iter().map(|(ch, next)| (ch.to_string(), next)).collect()
+            } else {
+                let next = tile.chars().try_fold(node, |node, ch| node.child(ch));
+                next.map(|next| (tile.clone(), next)).into_iter().collect()
+            };
+            visited[r][c] = true;
+            for (letters, next) in nexts {
+                word.push_str(&letters);
+                if next.is_word() {
+                    found.push(word.clone());
+                }
+                for (dr, dc) in [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)] {
+                    let (r2, c2) = (r as i32 + dr, c as i32 + dc);
+                    if (0..tiles.len() as i32).contains(&r2) && (0..tiles[0].len() as i32).contains(&c2) {
+                        visit(next, (r2 as usize, c2 as usize), tiles, word, visited, found);
+                    }
+                }
+                word.truncate(word.len() - letters.len());
+            }
+            visited[r][c] = false;
+        }
+
+        let mut found = Vec::new();
+        let mut visited = vec![vec![false; tiles[0].len()]; tiles.len()];
+        for r in 0..tiles.len() {
+            for c in 0..tiles[0].len() {
+                visit(root, (r, c), tiles, &mut String::new(), &mut visited, &mut found);
+            }
+        }
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    #[test]
+    fn child_lookups_agree_for_every_letter() {
+        let mut rng = Rng::new(44);
+        for _ in 0..20 {
+            let (mut arena, mut map) = (ArenaTrie::new(), TrieNode::default());
+            for word in random_words(&mut rng, 300) {
+                arena.insert(&word);
+                map.insert(&word);
+            }
+            assert_same_lookups(arena.root(), &map, &mut String::new());
+        }
+    }
+
+    #[test]
+    fn solves_agree_with_the_hashmap_trie() {
+        let mut rng = Rng::new(144);
+        for round in 0..40 {
+            // Uppercased as the dictionary and board do it, so ß is the two letters SS
+            let words: Vec<String> = random_words(&mut rng, 500)
+                .iter()
+                .map(|word| Locale::Default.upper(word))
+                .filter(|word| word.chars().count() >= 3)
+                .collect();
+            let (mut arena, mut map) = (ArenaTrie::new(), TrieNode::default());
+            for word in &words {
+                arena.insert(word);
+                map.insert(word);
+            }
+            let size = 3 + rng.below(2);
+            let mut tiles: Vec<Vec<String>> = (0..size)
+                .map(|_| (0..size).map(|_| LETTERS[rng.below(LETTERS.len())].to_string()).collect())
+                .collect();
+            for tile in tiles.iter_mut().flatten() {
+                *tile = Locale::Default.upper(tile);
+            }
+            if rng.below(2) == 0 {
+                tiles[rng.below(size)][rng.below(size)] = "?".to_string();
+            }
+
+            let expected = board_words(&map, &tiles);
+            assert_eq!(board_words(arena.root(), &tiles), expected, "round {}: {:?}", round, tiles);
+            // The solver, walking the arena the dictionary builds, finds the same
+            let solver = BoggleSolver::from_words(Board::new(tiles.clone()), &words).unwrap();
+            let mut solved = solver.solve_all();
+            solved.sort();
+            assert_eq!(solved, expected, "round {}: {:?}", round, tiles);
+        }
+    }
+}
@@ -0,0 +1,906 @@
+use std::io;
+use std::path::Path;
+
+use crate::error::BoggleError;
+use crate::json::Json;
+use crate::locale::Locale;
+
+/// The tile of a blank cell that can stand for any one letter.
+pub const WILDCARD: &str = "?";
+
+/// How the cells of a board touch each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Rows and columns: a cell touches up to eight others.
+    #[default]
+    Square,
+    /// A hexagon of hexagonal cells, each touching up to six others. A hexagon of radius
+    /// R is typed as 2R + 1 rows of R + 1, R + 2, ... 2R + 1, ... R + 1 tiles, and held
+    /// in axial coordinates: cell (q, r), both counted from -R to R with the centre at
+    /// (0, 0), is row r + R and column q + R of a square grid whose two corners outside
+    /// the hexagon are blocked.
+    Hex,
+}
+
+impl std::str::FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "square" => Ok(Topology::Square),
+            "hex" => Ok(Topology::Hex),
+            _ => Err(format!("unknown topology '{}' (choose square or hex)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    tiles: Vec<Vec<String>>, // Each tile can be multiple chars (e.g., "QU"); "" is a blocked cell, "?" a wildcard
+    topology: Topology,
+    // Layers of a 3D board, stacked one under another in `tiles`
+    depth: usize,
+}
+
+// A turn or flip of a board: which tile of the original, given its rows and columns,
+// lands on a cell
+type Symmetry = fn(usize, usize, usize, usize) -> (usize, usize);
+
+impl Board {
+    pub fn new(tiles: Vec<Vec<String>>) -> Self {
+        Board { tiles, topology: Topology::Square, depth: 1 }
+    }
+
+    /// A hex board from its rows as typed, R + 1 up to 2R + 1 and back down to R + 1
+    /// tiles long, laid out in the axial grid described at `Topology::Hex`.
+    pub fn hex(rows: Vec<Vec<String>>) -> Result<Self, BoggleError> {
+        if rows.len().is_multiple_of(2) {
+            return Err(BoggleError::HexRowCount { rows: rows.len() });
+        }
+        let radius = rows.len() / 2;
+        let mut tiles = Vec::new();
+        for (i, row) in rows.into_iter().enumerate() {
+            let expected = rows_len(radius) - i.abs_diff(radius);
+            if row.len() != expected {
+                return Err(BoggleError::HexRowLength { row: i + 1, expected, found: row.len() });
+            }
+            // The rows above the middle start further right, those below end further left
+            let mut grid = vec![String::new(); radius.saturating_sub(i)];
+            grid.extend(row);
+            grid.resize(rows_len(radius), String::new());
+            tiles.push(grid);
+        }
+        let board = Board { tiles, topology: Topology::Hex, depth: 1 };
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// A 3D board of square `layers`, each the same size. A cell touches the cells
+    /// around it in its own layer and in the layers just above and below, up to 26 in
+    /// all. The layers are held one under another, so layer `z`, row `r` is row
+    /// `z * layer_rows() + r` of `tiles()`.
+    pub fn stacked(layers: Vec<Board>) -> Result<Self, BoggleError> {
+        let Some(first) = layers.first() else {
+            return Err(BoggleError::EmptyBoard);
+        };
+        let size = (first.rows(), first.cols());
+        for (i, layer) in layers.iter().enumerate() {
+            if (layer.rows(), layer.cols()) != size {
+                return Err(BoggleError::RaggedLayers {
+                    layer: i + 1,
+                    expected: size,
+                    found: (layer.rows(), layer.cols()),
+                });
+            }
+        }
+        let depth = layers.len();
+        let tiles = layers.into_iter().flat_map(|layer| layer.tiles).collect();
+        let board = Board { tiles, topology: Topology::Square, depth };
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// Builds a board from row strings such as `["srps", "euim", "eahw", "wdzr"]`.
+    pub fn from_rows<S: AsRef<str>>(rows: &[S]) -> Self {
+        Board::from_rows_with(rows, &ParseOptions::default())
+    }
+
+    pub fn from_rows_with<S: AsRef<str>>(rows: &[S], opts: &ParseOptions) -> Self {
+        Board::new(rows.iter().map(|row| parse_row(row.as_ref(), opts)).collect())
+    }
+
+    /// Parses input rows such as `["s r p s", "euim", ...]`.
+    /// Spaces between letters are ignored, `?` or `*` is a wildcard cell and `#` or `-` a
+    /// blocked one, anywhere on the board; otherwise every row must be alphabetic and have
+    /// the same number of tiles, or for a hex board (`opts.topology`) the lengths that make
+    /// a hexagon.
+    pub fn parse_rows<S: AsRef<str>>(rows: &[S], opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let mut tiles: Vec<Vec<String>> = Vec::new();
+        for (i, text) in rows.iter().enumerate() {
+            let row = i + 1;
+            check_row(row, text.as_ref())?;
+            let letters: String = text.as_ref().chars().filter(|ch| !ch.is_whitespace()).collect();
+            let row_tiles = parse_row(&letters, opts);
+            if opts.topology == Topology::Square
+                && let Some(first) = tiles.first()
+                && row_tiles.len() != first.len()
+            {
+                return Err(BoggleError::RaggedBoard { row, expected: first.len(), found: row_tiles.len() });
+            }
+            tiles.push(row_tiles);
+        }
+        if opts.topology == Topology::Hex {
+            return Board::hex(tiles);
+        }
+        let board = Board::new(tiles);
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// Parses a board however it was typed: `SRPS EUIM EAHW WDZR`, `srps,euim,eahw,wdzr`,
+    /// a grid of lines with spaces between the letters, or any of these in lowercase with
+    /// stray whitespace. Several non-blank lines are the rows, as `parse_rows` reads them,
+    /// and a single line is a whole board that `from_line` splits into rows. With
+    /// `opts.cols`, each row must have that many tiles. A character that is not part of
+    /// any tile is reported with its row and column.
+    pub fn parse(input: &str, opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let rows: Vec<&str> = input.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        let board = match rows[..] {
+            [] => return Err(BoggleError::EmptyBoard),
+            [line] => return Board::from_line(line, opts),
+            _ => Board::parse_rows(&rows, opts)?,
+        };
+        check_cols(&board, opts)?;
+        Ok(board)
+    }
+
+    /// Parses a board typed as command-line arguments or the lines of a board file, one
+    /// row each, or one argument holding the whole board; see `parse`.
+    pub fn from_args<S: AsRef<str>>(rows: &[S], opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let rows: Vec<&str> = rows.iter().map(AsRef::as_ref).collect();
+        Board::parse(&rows.join("\n"), opts)
+    }
+
+    /// Parses a board file: one row per line, blank lines and comments ignored. A `#`
+    /// starts a comment at the start of a line or after a space, and is a blocked cell
+    /// anywhere else, so a row starting with a block is written with `-`. With
+    /// `opts.layered`, blank lines instead separate the layers of a 3D board.
+    pub fn from_text(text: &str, opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let lines = text.lines().map(|line| strip_comment(line).trim());
+        if opts.layered {
+            let mut layers: Vec<Vec<&str>> = vec![Vec::new()];
+            for line in lines {
+                match layers.last_mut() {
+                    Some(layer) if !line.is_empty() => layer.push(line),
+                    Some(layer) if !layer.is_empty() => layers.push(Vec::new()),
+                    _ => {}
+                }
+            }
+            layers.retain(|layer| !layer.is_empty());
+            let layers = layers.iter().map(|rows| Board::parse_rows(rows, opts)).collect::<Result<_, _>>()?;
+            return Board::stacked(layers);
+        }
+        let rows: Vec<&str> = lines.filter(|line| !line.is_empty()).collect();
+        Board::from_args(&rows, opts)
+    }
+
+    /// Parses a whole board written on one line, either as rows separated by `/` or `,`
+    /// (`srps/euim/eahw/wdzr`) or as a square run of tiles (`srpseuimeahwwdzr`, spaces
+    /// anywhere). A run is cut into rows of `opts.cols` tiles when that is set, and a hex
+    /// board's run into the rows of the hexagon it fills. A run that is not square but is
+    /// made of space-separated words of the same number of tiles, as in `abc def`, has
+    /// those words as its rows.
+    pub fn from_line(line: &str, opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let line = line.trim();
+        if line.contains(['/', ',']) {
+            let rows: Vec<&str> = line.split(['/', ',']).collect();
+            let board = Board::parse_rows(&rows, opts)?;
+            check_cols(&board, opts)?;
+            return Ok(board);
+        }
+
+        let flat = ParseOptions { topology: Topology::Square, ..opts.clone() };
+        let board = Board::parse_rows(&[line], &flat)?;
+        let tiles = &board.tiles[0];
+        if opts.topology == Topology::Hex {
+            let radius = (0..=tiles.len()).find(|&r| 3 * r * (r + 1) + 1 >= tiles.len()).unwrap_or(0);
+            if 3 * radius * (radius + 1) + 1 != tiles.len() {
+                return Err(BoggleError::NotHexagon { tiles: tiles.len() });
+            }
+            let mut rest = &tiles[..];
+            let mut rows = Vec::new();
+            for i in 0..rows_len(radius) {
+                let (row, tail) = rest.split_at(rows_len(radius) - i.abs_diff(radius));
+                rows.push(row.to_vec());
+                rest = tail;
+            }
+            return Board::hex(rows);
+        }
+        if let Some(cols) = opts.cols {
+            if cols == 0 || !tiles.len().is_multiple_of(cols) {
+                return Err(BoggleError::NotRows { tiles: tiles.len(), cols });
+            }
+            return Ok(Board::new(tiles.chunks(cols).map(|row| row.to_vec()).collect()));
+        }
+        let side = (1..=tiles.len()).find(|n| n * n >= tiles.len()).unwrap_or(0);
+        if side * side != tiles.len() {
+            // A word must be whole tiles, not half of a spaced-out "(l l)" group
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let rows: Vec<Vec<String>> = words.iter().map(|word| parse_row(word, opts)).collect();
+            if rows.len() > 1
+                && rows[0].len() > 1
+                && rows.iter().all(|row| row.len() == rows[0].len())
+                && words.iter().all(|word| check_row(1, word).is_ok())
+            {
+                return Ok(Board::new(rows));
+            }
+            return Err(BoggleError::NotSquare { tiles: tiles.len() });
+        }
+        Ok(Board::new(tiles.chunks(side).map(|row| row.to_vec()).collect()))
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P, opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| BoggleError::BoardIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        Board::from_text(&text, opts)
+    }
+
+    /// Reads a board from JSON of the form `{"rows": ["SRPS", "EUIM", ...]}`, each row
+    /// parsed and checked like a typed one, so a bad letter or a short row is reported
+    /// with its position. `"topology": "hex"` reads the rows as a hex board, and a
+    /// `"layers"` array of such row arrays in place of `"rows"` makes a 3D board.
+    pub fn from_json(text: &str, opts: &ParseOptions) -> Result<Self, BoggleError> {
+        let json = Json::parse(text).map_err(BoggleError::InvalidJson)?;
+        let mut opts = opts.clone();
+        if let Some(topology) = json.get("topology").and_then(Json::as_str) {
+            opts.topology = topology.parse().map_err(BoggleError::InvalidJson)?;
+        }
+        if let Some(layers) = json.get("layers").and_then(Json::as_array) {
+            let layers = layers
+                .iter()
+                .enumerate()
+                .map(|(i, layer)| {
+                    let rows = layer
+                        .as_array()
+                        .ok_or_else(|| BoggleError::InvalidJson(format!("layer {} is not an array", i + 1)))?;
+                    Board::parse_rows(&json_rows(rows)?, &opts)
+                })
+                .collect::<Result<_, _>>()?;
+            return Board::stacked(layers);
+        }
+        let rows = json
+            .get("rows")
+            .and_then(Json::as_array)
+            .ok_or_else(|| BoggleError::InvalidJson("expected an object with a \"rows\" array".to_string()))?;
+        Board::parse_rows(&json_rows(rows)?, &opts)
+    }
+
+    /// The board as `{"rows": [...]}`, which `from_json` with default options reads back
+    /// into an equal board. Multi-letter tiles other than Qu are written as `(..)` groups
+    /// and blocked cells as `()`. A hex board is written as the rows of its hexagon,
+    /// with `"topology": "hex"`, and a 3D board as `{"layers": [[...], ...]}`.
+    pub fn to_json(&self) -> String {
+        let row_text = |row: &[String]| -> String {
+            row.iter()
+                .map(|tile| match tile.as_str() {
+                    WILDCARD | "QU" => tile.clone(),
+                    // A lone Q would otherwise read back as the Qu tile
+                    t if t.chars().count() == 1 && t != "Q" => tile.clone(),
+                    t => format!("({})", t),
+                })
+                .collect()
+        };
+        if self.topology == Topology::Hex {
+            let rows = self.hex_rows().map(|row| Json::from(row_text(row))).collect();
+            return Json::object(vec![("rows", Json::Array(rows)), ("topology", Json::from("hex"))]).to_string();
+        }
+        if self.depth > 1 {
+            let layers = self
+                .tiles
+                .chunks(self.layer_rows())
+                .map(|layer| Json::Array(layer.iter().map(|row| Json::from(row_text(row))).collect()))
+                .collect();
+            return Json::object(vec![("layers", Json::Array(layers))]).to_string();
+        }
+        let rows = self.tiles.iter().map(|row| Json::from(row_text(row))).collect();
+        Json::object(vec![("rows", Json::Array(rows))]).to_string()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.tiles.first().map_or(0, |row| row.len())
+    }
+
+    pub fn tile(&self, r: usize, c: usize) -> &str {
+        &self.tiles[r][c]
+    }
+
+    /// Whether the cell is a blank that stands for any one letter.
+    pub fn is_wildcard(&self, r: usize, c: usize) -> bool {
+        self.tiles[r][c] == WILDCARD
+    }
+
+    /// Whether the cell is a solid block that no word may pass through.
+    pub fn is_blocked(&self, r: usize, c: usize) -> bool {
+        self.tiles[r][c].is_empty()
+    }
+
+    /// Replaces the tile in cell (`row`, `col`), counted from 0: uppercase letters, `?`
+    /// for a wildcard or `""` for a block.
+    pub fn set_tile(&mut self, row: usize, col: usize, tile: String) -> Result<(), BoggleError> {
+        if row >= self.rows() || col >= self.cols() {
+            return Err(BoggleError::CellOffBoard { row, col, rows: self.rows(), cols: self.cols() });
+        }
+        self.tiles[row][col] = tile;
+        Ok(())
+    }
+
+    pub fn tiles(&self) -> &[Vec<String>] {
+        &self.tiles
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Number of layers: 1 unless the board was `stacked`.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Rows in each layer, which is every row of a board with one layer.
+    pub fn layer_rows(&self) -> usize {
+        self.rows() / self.depth
+    }
+
+    /// The layer, row and column of cell (`row`, `col`), all counted from 0.
+    pub fn layer_cell(&self, row: usize, col: usize) -> (usize, usize, usize) {
+        let layer_rows = self.layer_rows().max(1);
+        (row / layer_rows, row % layer_rows, col)
+    }
+
+    /// The cells of each row a hex board was typed with, without the blocked corners
+    /// around the hexagon; every row of a square board.
+    pub fn hex_rows(&self) -> impl Iterator<Item = &[String]> {
+        let radius = self.rows() / 2;
+        self.tiles.iter().enumerate().map(move |(i, row)| match self.topology {
+            Topology::Square => &row[..],
+            Topology::Hex => &row[radius.saturating_sub(i)..rows_len(radius) - i.saturating_sub(radius)],
+        })
+    }
+
+    /// The axial coordinates (q, r) of cell (`row`, `col`) of a hex board, or the cell
+    /// itself on a square one.
+    pub fn axial(&self, row: usize, col: usize) -> (i64, i64) {
+        match self.topology {
+            Topology::Square => (row as i64, col as i64),
+            Topology::Hex => {
+                let radius = (self.rows() / 2) as i64;
+                (col as i64 - radius, row as i64 - radius)
+            }
+        }
+    }
+
+    /// Total number of letters on the board, counting both letters of a Qu tile.
+    pub fn letter_count(&self) -> usize {
+        self.tiles.iter().flatten().map(|t| t.chars().count()).sum()
+    }
+
+    /// Number of cells that are not blocked, which is the most a path can cover.
+    pub fn open_cells(&self) -> usize {
+        self.tiles.iter().flatten().filter(|t| !t.is_empty()).count()
+    }
+
+    /// The board's representative among the boards it can be turned or flipped into,
+    /// which all hold the same words: the one whose tiles, row by row, sort first. A
+    /// square board has 8 such forms (4 rotations, each mirrored), any other rectangle
+    /// only itself and its half turn. Hex and 3D boards are returned as they are.
+    pub fn canonical_form(&self) -> Board {
+        self.canonical_cells().0
+    }
+
+    /// `canonical_form()`, with where each of its cells comes from: its cell (r, c) is
+    /// cell `from[r][c]` of this board. A path on either board maps onto the other this
+    /// way, since turning and flipping keep neighbours neighbours.
+    pub fn canonical_cells(&self) -> (Board, Vec<Vec<(usize, usize)>>) {
+        let (rows, cols) = (self.rows(), self.cols());
+        let identity = || (0..rows).map(|r| (0..cols).map(|c| (r, c)).collect()).collect();
+        let turns: &[Symmetry] = match (self.topology, self.depth) {
+            (Topology::Square, 1) if rows == cols => &[
+                |r, c, _, _| (r, c),
+                |r, c, n, _| (c, n - 1 - r),
+                |r, c, n, _| (n - 1 - r, n - 1 - c),
+                |r, c, n, _| (n - 1 - c, r),
+                |r, c, _, m| (r, m - 1 - c),
+                |r, c, n, _| (n - 1 - r, c),
+                |r, c, _, _| (c, r),
+                |r, c, n, _| (n - 1 - c, n - 1 - r),
+            ],
+            (Topology::Square, 1) => &[|r, c, _, _| (r, c), |r, c, n, m| (n - 1 - r, m - 1 - c)],
+            _ => return (self.clone(), identity()),
+        };
+        // Tile (r, c) of each form is the one `turn` moves there
+        turns
+            .iter()
+            .map(|turn| {
+                let from: Vec<Vec<(usize, usize)>> =
+                    (0..rows).map(|r| (0..cols).map(|c| turn(r, c, rows, cols)).collect()).collect();
+                let tiles =
+                    from.iter().map(|row| row.iter().map(|&(r, c)| self.tiles[r][c].clone()).collect()).collect();
+                (Board { tiles, ..self.clone() }, from)
+            })
+            .min_by(|(a, _), (b, _)| a.tiles.cmp(&b.tiles))
+            .unwrap_or_else(|| (self.clone(), identity()))
+    }
+
+    /// A hash of `canonical_form()`, the same for every turn and flip of the board and
+    /// from one run or build to the next (64-bit FNV-1a). Boards that differ may still
+    /// share one, if rarely.
+    pub fn fingerprint(&self) -> u64 {
+        let canonical = self.canonical_form();
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut add = |byte: u8| hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        add(canonical.topology as u8);
+        add(canonical.depth as u8);
+        // Tiles never hold these bytes, so they mark where tiles and rows end
+        for row in &canonical.tiles {
+            for tile in row {
+                tile.bytes().for_each(&mut add);
+                add(0);
+            }
+            add(1);
+        }
+        hash
+    }
+
+    /// Checks that the board is non-empty and every row has the same number of tiles.
+    pub fn validate(&self) -> Result<(), BoggleError> {
+        if self.rows() == 0 || self.cols() == 0 {
+            return Err(BoggleError::EmptyBoard);
+        }
+        for (i, row) in self.tiles.iter().enumerate() {
+            if row.len() != self.cols() {
+                return Err(BoggleError::RaggedBoard { row: i + 1, expected: self.cols(), found: row.len() });
+            }
+        }
+        Ok(())
+    }
+}
+
+// The strings of a JSON array of rows, with the position of any that is not one
+fn json_rows(rows: &[Json]) -> Result<Vec<&str>, BoggleError> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| row.as_str().ok_or_else(|| BoggleError::InvalidJson(format!("row {} is not a string", i + 1))))
+        .collect()
+}
+
+// Rejects rows typed out whole whose length is not the asked-for `opts.cols`, naming
+// the first such row
+fn check_cols(board: &Board, opts: &ParseOptions) -> Result<(), BoggleError> {
+    let Some(cols) = opts.cols else { return Ok(()) };
+    match board.tiles.iter().position(|row| row.len() != cols) {
+        Some(i) => Err(BoggleError::ColumnCount { row: i + 1, expected: cols, found: board.tiles[i].len() }),
+        None => Ok(()),
+    }
+}
+
+// The rows, and the longest row, of a hexagon of radius `radius`
+fn rows_len(radius: usize) -> usize {
+    2 * radius + 1
+}
+
+/// A line of a board file without its comment, if it has one: from a `#` at its start
+/// or after whitespace to the end. Any other `#` is a blocked cell.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut last = ' ';
+    for (i, ch) in line.char_indices() {
+        if ch == '#' && last.is_whitespace() {
+            return &line[..i];
+        }
+        last = ch;
+    }
+    line
+}
+
+// Rejects anything but letters, wildcards, blocks and balanced tile groups; `col` counts
+// the characters of the row as typed
+fn check_row(row: usize, text: &str) -> Result<(), BoggleError> {
+    let mut group_start = None;
+    for (i, ch) in text.chars().enumerate() {
+        let col = i + 1;
+        match ch {
+            '(' if group_start.is_none() => group_start = Some(col),
+            ')' if group_start.is_some() => group_start = None,
+            '(' | ')' => return Err(BoggleError::UnbalancedGroup { row, col }),
+            '?' | '*' if group_start.is_some() => return Err(BoggleError::WildcardInGroup { row, col }),
+            '?' | '*' | '#' | '-' if group_start.is_none() => {}
+            ch if ch.is_whitespace() || ch.is_alphabetic() => {}
+            ch => return Err(BoggleError::InvalidCharacter { row, col, ch }),
+        }
+    }
+    match group_start {
+        Some(col) => Err(BoggleError::UnbalancedGroup { row, col }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Treat `q` (or `qu`) as the two-letter Qu tile, as on a real Boggle cube.
+    pub qu_tile: bool,
+    /// Multi-letter tiles (uppercase, e.g. "LL", "CH") that a row's letters are grouped
+    /// into wherever they appear, longest first.
+    pub digraphs: Vec<String>,
+    /// Whether the rows make a square grid or a hexagon.
+    pub topology: Topology,
+    /// Blank lines in a board file separate the layers of a 3D board.
+    pub layered: bool,
+    /// How many tiles wide a board written as one run of tiles is; when unset the run
+    /// must make a square.
+    pub cols: Option<usize>,
+    /// How letters are uppercased; the dictionary must be loaded with the same one.
+    pub locale: Locale,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            qu_tile: true,
+            digraphs: Vec::new(),
+            topology: Topology::Square,
+            layered: false,
+            cols: None,
+            locale: Locale::Default,
+        }
+    }
+}
+
+/// Splits a row string into tiles: "Q" or "QU" is one Qu tile, a parenthesized group like
+/// "(ll)" or a declared digraph is one tile, `#` or `-` (or the empty group "()") is a
+/// blocked cell, and other letters are individual tiles.
+///
+/// Letters are uppercased one at a time by `opts.locale`, so a letter whose uppercase
+/// form is several characters (German ß becomes "SS") stays a single cell holding the
+/// expansion. That matches how the same letter is spelled in a dictionary uppercased
+/// by the same locale.
+pub fn parse_row(row: &str, opts: &ParseOptions) -> Vec<String> {
+    let letters: Vec<String> = row.chars().map(|ch| opts.locale.upper_char(ch)).collect();
+    let mut digraphs: Vec<Vec<String>> =
+        opts.digraphs.iter().map(|d| d.chars().map(|ch| opts.locale.upper_char(ch)).collect()).collect();
+    digraphs.sort_by_key(|d: &Vec<String>| std::cmp::Reverse(d.len()));
+
+    let mut tiles: Vec<String> = Vec::new();
+    let mut j = 0;
+    while j < letters.len() {
+        if letters[j] == "(" {
+            let end = letters[j..].iter().position(|l| l == ")").map_or(letters.len(), |i| j + i);
+            tiles.push(letters[j + 1..end].concat());
+            j = end + 1;
+        } else if let Some(digraph) = digraphs.iter().find(|d| !d.is_empty() && letters[j..].starts_with(d)) {
+            tiles.push(digraph.concat());
+            j += digraph.len();
+        } else if letters[j] == "*" {
+            tiles.push(WILDCARD.to_string());
+            j += 1;
+        } else if letters[j] == "#" || letters[j] == "-" {
+            tiles.push(String::new());
+            j += 1;
+        } else if opts.qu_tile && letters[j] == "Q" {
+            tiles.push("QU".to_string());
+            // A typed "qu" and a lone "q" both mean the Qu tile
+            j += if letters.get(j + 1).is_some_and(|next| next == "U") { 2 } else { 1 };
+        } else {
+            tiles.push(letters[j].clone());
+            j += 1;
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSIC: [&str; 4] = ["SRPS", "EUIM", "EAHW", "WDZR"];
+
+    fn rows(board: &Board) -> Vec<String> {
+        board.tiles().iter().map(|row| row.iter().map(|tile| format!("[{}]", tile)).collect()).collect()
+    }
+
+    fn classic() -> Board {
+        Board::from_rows(&CLASSIC)
+    }
+
+    fn with_cols(cols: usize) -> ParseOptions {
+        ParseOptions { cols: Some(cols), ..ParseOptions::default() }
+    }
+
+    fn hex() -> ParseOptions {
+        ParseOptions { topology: Topology::Hex, ..ParseOptions::default() }
+    }
+
+    #[test]
+    fn parses_each_row_separator() {
+        let opts = ParseOptions::default();
+        for input in [
+            "SRPS EUIM EAHW WDZR",
+            "srps/euim/eahw/wdzr",
+            "srps,euim,eahw,wdzr",
+            "srps\neuim\neahw\nwdzr",
+            "srps\r\neuim\r\neahw\r\nwdzr\r\n",
+            "s r p s\ne u i m\ne a h w\nw d z r",
+            "s r p s e u i m e a h w w d z r",
+            "srpseuimeahwwdzr",
+            "  srps / euim/eahw ,wdzr  ",
+            "\n\n  srps\n\n  euim \neahw\nwdzr\n\n",
+        ] {
+            assert_eq!(Board::parse(input, &opts).unwrap(), classic(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn parses_rows_given_one_per_argument() {
+        let opts = ParseOptions::default();
+        assert_eq!(Board::from_args(&CLASSIC, &opts).unwrap(), classic());
+        assert_eq!(Board::from_args(&["s r p s", "e u i m", "e a h w", "w d z r"], &opts).unwrap(), classic());
+        assert_eq!(Board::from_args(&["srpseuimeahwwdzr"], &opts).unwrap(), classic());
+        assert_eq!(Board::from_args(&["srps euim eahw wdzr"], &opts).unwrap(), classic());
+    }
+
+    #[test]
+    fn cuts_a_run_into_rows() {
+        let board = Board::parse("abcdef", &with_cols(3)).unwrap();
+        assert_eq!(rows(&board), ["[A][B][C]", "[D][E][F]"]);
+        let board = Board::parse("abcdef", &with_cols(2)).unwrap();
+        assert_eq!(rows(&board), ["[A][B]", "[C][D]", "[E][F]"]);
+        let board = Board::parse("abc/def", &with_cols(3)).unwrap();
+        assert_eq!(rows(&board), ["[A][B][C]", "[D][E][F]"]);
+    }
+
+    #[test]
+    fn reads_space_separated_words_as_rows_when_the_run_is_not_square() {
+        let opts = ParseOptions::default();
+        assert_eq!(rows(&Board::parse("abc def", &opts).unwrap()), ["[A][B][C]", "[D][E][F]"]);
+        assert_eq!(rows(&Board::parse("ab cd ef", &opts).unwrap()), ["[A][B]", "[C][D]", "[E][F]"]);
+        // A square run stays a square, whatever its spacing
+        assert_eq!(rows(&Board::parse("ab cd", &opts).unwrap()), ["[A][B]", "[C][D]"]);
+        assert_eq!(rows(&Board::parse("a bcd", &opts).unwrap()), ["[A][B]", "[C][D]"]);
+    }
+
+    #[test]
+    fn parses_multi_letter_wildcard_and_blocked_tiles() {
+        let opts = ParseOptions::default();
+        assert_eq!(rows(&Board::parse("qa/bc", &opts).unwrap()), ["[QU][A]", "[B][C]"]);
+        assert_eq!(rows(&Board::parse("qua/bc", &opts).unwrap()), ["[QU][A]", "[B][C]"]);
+        assert_eq!(rows(&Board::parse("QUA BC", &opts).unwrap()), ["[QU][A]", "[B][C]"]);
+        assert_eq!(rows(&Board::parse("(ll)a/bc", &opts).unwrap()), ["[LL][A]", "[B][C]"]);
+        assert_eq!(rows(&Board::parse("()a/bc", &opts).unwrap()), ["[][A]", "[B][C]"]);
+
+        let board = Board::parse("?a/b#", &opts).unwrap();
+        assert_eq!(rows(&board), ["[?][A]", "[B][]"]);
+        assert!(board.is_wildcard(0, 0));
+        assert!(board.is_blocked(1, 1));
+        assert_eq!(Board::parse("*a/b-", &opts).unwrap(), board);
+
+        let literal = ParseOptions { qu_tile: false, ..ParseOptions::default() };
+        assert_eq!(rows(&Board::parse("qu/ab", &literal).unwrap()), ["[Q][U]", "[A][B]"]);
+        assert_eq!(rows(&Board::parse("(qu)a/bc", &literal).unwrap()), ["[QU][A]", "[B][C]"]);
+
+        let digraphs = ParseOptions { digraphs: vec!["LL".to_string(), "CH".to_string()], ..ParseOptions::default() };
+        assert_eq!(rows(&Board::parse("lla/chb", &digraphs).unwrap()), ["[LL][A]", "[CH][B]"]);
+    }
+
+    #[test]
+    fn parses_hex_boards() {
+        let expected = ["[][A][B]", "[C][D][E]", "[F][G][]"];
+        for input in ["abcdefg", "ab/cde/fg", "ab\ncde\nfg", "a b c d e f g"] {
+            let board = Board::parse(input, &hex()).unwrap();
+            assert_eq!(board.topology(), Topology::Hex, "{:?}", input);
+            assert_eq!(rows(&board), expected, "{:?}", input);
+        }
+        assert_eq!(Board::parse(&"a".repeat(19), &hex()).unwrap().rows(), 5);
+    }
+
+    #[test]
+    fn parses_layered_board_files() {
+        let opts = ParseOptions { layered: true, ..ParseOptions::default() };
+        let board = Board::from_text("# two layers\nab\ncd\n\nef\ngh # the top\n", &opts).unwrap();
+        assert_eq!(board.depth(), 2);
+        assert_eq!(board.layer_rows(), 2);
+        assert_eq!(rows(&board), ["[A][B]", "[C][D]", "[E][F]", "[G][H]"]);
+        assert_eq!(board.layer_cell(3, 1), (1, 1, 1));
+    }
+
+    #[test]
+    fn strips_comments_from_board_files() {
+        let opts = ParseOptions::default();
+        let board = Board::from_text("# the classic\nsrps  # first row\neuim\n\neahw\nwdzr\n", &opts);
+        assert_eq!(board.unwrap(), classic());
+        assert_eq!(rows(&Board::from_text("-a #\nb#\n", &opts).unwrap()), ["[][A]", "[B][]"]);
+    }
+
+    #[test]
+    fn uppercases_by_locale() {
+        let turkish = ParseOptions { locale: Locale::Turkish, ..ParseOptions::default() };
+        assert_eq!(rows(&Board::parse("iı/ab", &turkish).unwrap()), ["[İ][I]", "[A][B]"]);
+        let german = ParseOptions { locale: Locale::German, ..ParseOptions::default() };
+        assert_eq!(rows(&Board::parse("ßa/bc", &german).unwrap()), ["[SS][A]", "[B][C]"]);
+    }
+
+    fn special() -> Board {
+        let rows = [["QU", "Q", "TH"], ["", "?", "E"], ["A", "B", "C"]];
+        Board::new(rows.iter().map(|row| row.iter().map(|tile| tile.to_string()).collect()).collect())
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let opts = ParseOptions::default();
+        let layered = ParseOptions { layered: true, ..ParseOptions::default() };
+        let hexagon = Board::parse("abcdefg", &hex()).unwrap();
+        let stacked = Board::from_text("ab\ncd\n\nef\ngh\n", &layered).unwrap();
+        assert_eq!(classic().to_json(), r#"{"rows":["SRPS","EUIM","EAHW","WDZR"]}"#);
+        assert_eq!(special().to_json(), r#"{"rows":["QU(Q)(TH)","()?E","ABC"]}"#);
+        assert_eq!(hexagon.to_json(), r#"{"rows":["AB","CDE","FG"],"topology":"hex"}"#);
+        assert_eq!(stacked.to_json(), r#"{"layers":[["AB","CD"],["EF","GH"]]}"#);
+        for board in [classic(), special(), hexagon, stacked] {
+            assert_eq!(Board::from_json(&board.to_json(), &opts).unwrap(), board, "{}", board.to_json());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_json_boards() {
+        let opts = ParseOptions::default();
+        let err = Board::from_json(r#"{"rows": ["ab", "cde"]}"#, &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::RaggedBoard { row: 2, expected: 2, found: 3 }), "{:?}", err);
+        let err = Board::from_json(r#"{"rows": ["ab", "c1"]}"#, &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::InvalidCharacter { row: 2, col: 2, ch: '1' }), "{:?}", err);
+        let err = Board::from_json(r#"{"rows": ["ab", 12]}"#, &opts).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: row 2 is not a string");
+        let err = Board::from_json(r#"{"layers": [["ab", "cd"], "efgh"]}"#, &opts).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: layer 2 is not an array");
+        let err = Board::from_json(r#"{"cells": []}"#, &opts).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: expected an object with a \"rows\" array");
+        let err = Board::from_json(r#"{"rows": ["abc"], "topology": "torus"}"#, &opts).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: unknown topology 'torus' (choose square or hex)");
+    }
+
+    #[test]
+    fn rejects_empty_boards() {
+        let opts = ParseOptions::default();
+        for input in ["", "   ", "\n\n", " \n \t\n"] {
+            let err = Board::parse(input, &opts).unwrap_err();
+            assert!(matches!(err, BoggleError::EmptyBoard), "{:?}: {:?}", input, err);
+        }
+        let err = Board::from_text("# only a comment\n", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::EmptyBoard), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_characters_that_are_not_tiles_at_their_position() {
+        let opts = ParseOptions::default();
+        for (input, row, col, ch) in [
+            ("abc1", 1, 4, '1'),
+            ("ab/c!", 2, 2, '!'),
+            ("ab,c.", 2, 2, '.'),
+            (" ab/ c;", 2, 3, ';'),
+            ("ab\ncd\ne_", 3, 2, '_'),
+            ("srps euim eahw wdz1", 1, 19, '1'),
+            ("ab|cd", 1, 3, '|'),
+        ] {
+            let err = Board::parse(input, &opts).unwrap_err();
+            let BoggleError::InvalidCharacter { row: r, col: c, ch: found } = err else {
+                panic!("{:?}: {:?}", input, err)
+            };
+            assert_eq!((r, c, found), (row, col, ch), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_tile_groups() {
+        let opts = ParseOptions::default();
+        let err = Board::parse("(ab/cd", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::UnbalancedGroup { row: 1, col: 1 }), "{:?}", err);
+        let err = Board::parse("ab/c)d", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::UnbalancedGroup { row: 2, col: 2 }), "{:?}", err);
+        let err = Board::parse("((a)b/cd", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::UnbalancedGroup { row: 1, col: 2 }), "{:?}", err);
+        let err = Board::parse("(a?)b/cd", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::WildcardInGroup { row: 1, col: 3 }), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_boards_of_the_wrong_shape() {
+        let opts = ParseOptions::default();
+        let err = Board::parse("abc", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::NotSquare { tiles: 3 }), "{:?}", err);
+        let err = Board::parse("ab cde", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::NotSquare { tiles: 5 }), "{:?}", err);
+        let err = Board::parse("a b c", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::NotSquare { tiles: 3 }), "{:?}", err);
+        let err = Board::parse("ab/cde", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::RaggedBoard { row: 2, expected: 2, found: 3 }), "{:?}", err);
+        let err = Board::parse("ab\ncd\ne", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::RaggedBoard { row: 3, expected: 2, found: 1 }), "{:?}", err);
+        let err = Board::parse("ab/cd/", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::RaggedBoard { row: 3, expected: 2, found: 0 }), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_rows_that_do_not_match_the_columns_asked_for() {
+        let err = Board::parse("abcdef", &with_cols(4)).unwrap_err();
+        assert!(matches!(err, BoggleError::NotRows { tiles: 6, cols: 4 }), "{:?}", err);
+        let err = Board::parse("abcd", &with_cols(0)).unwrap_err();
+        assert!(matches!(err, BoggleError::NotRows { tiles: 4, cols: 0 }), "{:?}", err);
+        let err = Board::parse("ab/cd", &with_cols(3)).unwrap_err();
+        assert!(matches!(err, BoggleError::ColumnCount { row: 1, expected: 3, found: 2 }), "{:?}", err);
+        let err = Board::from_args(&["abc", "def"], &with_cols(2)).unwrap_err();
+        assert!(matches!(err, BoggleError::ColumnCount { row: 1, expected: 2, found: 3 }), "{:?}", err);
+    }
+
+    #[test]
+    fn names_the_row_of_the_wrong_width() {
+        let board = Board::new(vec![vec!["A".to_string(); 3], vec!["B".to_string(); 2]]);
+        let err = check_cols(&board, &with_cols(3)).unwrap_err();
+        assert!(matches!(err, BoggleError::ColumnCount { row: 2, expected: 3, found: 2 }), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_hex_boards_that_are_not_hexagons() {
+        let err = Board::parse("abcd", &hex()).unwrap_err();
+        assert!(matches!(err, BoggleError::NotHexagon { tiles: 4 }), "{:?}", err);
+        let err = Board::parse("ab/cd", &hex()).unwrap_err();
+        assert!(matches!(err, BoggleError::HexRowCount { rows: 2 }), "{:?}", err);
+        let err = Board::parse("ab/cd/ef", &hex()).unwrap_err();
+        assert!(matches!(err, BoggleError::HexRowLength { row: 2, expected: 3, found: 2 }), "{:?}", err);
+    }
+
+    #[test]
+    fn rejects_layers_of_different_sizes() {
+        let opts = ParseOptions { layered: true, ..ParseOptions::default() };
+        let err = Board::from_text("ab\ncd\n\nefg\nhij\n", &opts).unwrap_err();
+        assert!(matches!(err, BoggleError::RaggedLayers { layer: 2, expected: (2, 2), found: (2, 3) }), "{:?}", err);
+    }
+
+    #[test]
+    fn every_turn_and_flip_shares_one_canonical_form() {
+        // Turned a quarter at a time, then mirrored, flipped top to bottom and across
+        // both diagonals
+        let variants = [
+            ["abc", "def", "ghi"],
+            ["gda", "heb", "ifc"],
+            ["ihg", "fed", "cba"],
+            ["cfi", "beh", "adg"],
+            ["cba", "fed", "ihg"],
+            ["ghi", "def", "abc"],
+            ["adg", "beh", "cfi"],
+            ["ifc", "heb", "gda"],
+        ];
+        let fingerprint = Board::from_rows(&variants[0]).fingerprint();
+        for variant in variants {
+            let board = Board::from_rows(&variant);
+            assert_eq!(rows(&board.canonical_form()), rows(&Board::from_rows(&variants[0])), "{:?}", variant);
+            assert_eq!(board.fingerprint(), fingerprint, "{:?}", variant);
+        }
+        // Swapping two tiles is no symmetry
+        assert_ne!(Board::from_rows(&["acb", "def", "ghi"]).fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn a_non_square_board_only_turns_halfway() {
+        // Half a turn keeps a 2 x 3 board's shape; a mirror image is another board
+        let board = Board::from_rows(&["fed", "cba"]);
+        assert_eq!(rows(&board.canonical_form()), ["[A][B][C]", "[D][E][F]"]);
+        assert_eq!(board.fingerprint(), Board::from_rows(&["abc", "def"]).fingerprint());
+        let mirrored = Board::from_rows(&["cba", "fed"]);
+        assert_eq!(rows(&mirrored.canonical_form()), ["[C][B][A]", "[F][E][D]"]);
+        assert_ne!(mirrored.fingerprint(), board.fingerprint());
+
+        let (_, from) = board.canonical_cells();
+        assert_eq!(from, [[(1, 2), (1, 1), (1, 0)], [(0, 2), (0, 1), (0, 0)]]);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::board::Board;
+use crate::error::BoggleError;
+use crate::score::score;
+use crate::solver::FoundWord;
+
+/// What a cell of a bonus layout does to the score of a word passing through it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Bonus {
+    #[default]
+    None,
+    DoubleLetter,
+    TripleLetter,
+    DoubleWord,
+    TripleWord,
+    /// A letter multiplier other than 2 or 3, from an `L<n>` cell.
+    Letter(u32),
+    /// A word multiplier other than 2 or 3, from a `W<n>` cell.
+    Word(u32),
+}
+
+// The largest multiplier an `L<n>` or `W<n>` cell may have, which keeps a word crossing
+// every cell of a big board well inside a u32
+const MAX_MULTIPLIER: u32 = 10;
+
+impl Bonus {
+    /// What the cell's letter values are multiplied by.
+    pub fn letter_multiplier(self) -> u32 {
+        match self {
+            Bonus::DoubleLetter => 2,
+            Bonus::TripleLetter => 3,
+            Bonus::Letter(n) => n,
+            _ => 1,
+        }
+    }
+
+    /// What the whole word is multiplied by, once per visit to the cell.
+    pub fn word_multiplier(self) -> u32 {
+        match self {
+            Bonus::DoubleWord => 2,
+            Bonus::TripleWord => 3,
+            Bonus::Word(n) => n,
+            _ => 1,
+        }
+    }
+}
+
+impl FromStr for Bonus {
+    type Err = String;
+
+    /// Reads `.`, `DL`, `TL`, `DW` or `TW`, or a multiplier spelled out as `L<n>` or
+    /// `W<n>` for n from 1 to 10, in any case. `L2` is `DoubleLetter`, `W3` `TripleWord`
+    /// and so on, and a multiplier of 1 is no bonus.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let upper = s.to_uppercase();
+        match upper.as_str() {
+            "." => return Ok(Bonus::None),
+            "DL" => return Ok(Bonus::DoubleLetter),
+            "TL" => return Ok(Bonus::TripleLetter),
+            "DW" => return Ok(Bonus::DoubleWord),
+            "TW" => return Ok(Bonus::TripleWord),
+            _ => {}
+        }
+        let unknown = || format!("unknown bonus '{}' (use ., DL, TL, DW, TW, or L<n> or W<n> for n up to 10)", s);
+        let (kind, n) = upper.split_at_checked(1).ok_or_else(unknown)?;
+        let n: u32 = n.parse().ok().filter(|n| (1..=MAX_MULTIPLIER).contains(n)).ok_or_else(unknown)?;
+        match (kind, n) {
+            ("L" | "W", 1) => Ok(Bonus::None),
+            ("L", 2) => Ok(Bonus::DoubleLetter),
+            ("L", 3) => Ok(Bonus::TripleLetter),
+            ("L", n) => Ok(Bonus::Letter(n)),
+            ("W", 2) => Ok(Bonus::DoubleWord),
+            ("W", 3) => Ok(Bonus::TripleWord),
+            ("W", n) => Ok(Bonus::Word(n)),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+impl fmt::Display for Bonus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Bonus::None => ".",
+            Bonus::DoubleLetter => "DL",
+            Bonus::TripleLetter => "TL",
+            Bonus::DoubleWord => "DW",
+            Bonus::TripleWord => "TW",
+            Bonus::Letter(n) => return write!(f, "L{}", n),
+            Bonus::Word(n) => return write!(f, "W{}", n),
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// The bonus on every cell of a board, for the Boggle With Friends style of scoring that
+/// `BoggleSolver::set_bonus` turns on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BonusLayout {
+    cells: Vec<Vec<Bonus>>,
+}
+
+impl BonusLayout {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| BoggleError::BonusIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        BonusLayout::parse(&text).map_err(|e| match e {
+            BoggleError::InvalidBonus(msg) => BoggleError::InvalidBonus(format!("{}: {}", path.display(), msg)),
+            e => e,
+        })
+    }
+
+    /// Reads one row of the grid per line, cells separated by spaces: `.` for a plain
+    /// cell, or `DL`, `TL`, `DW` or `TW`, or any letter or word multiplier as `L<n>` or
+    /// `W<n>` (see `Bonus::from_str`). As in a board file, `#` starts a
+    /// comment and blank lines are skipped; every row must have the same number of cells.
+    pub fn parse(text: &str) -> Result<Self, BoggleError> {
+        let mut cells: Vec<Vec<Bonus>> = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let row = line
+                .split_whitespace()
+                .enumerate()
+                .map(|(col, symbol)| {
+                    symbol.parse().map_err(|e| {
+                        BoggleError::InvalidBonus(format!("row {}, column {}: {}", cells.len() + 1, col + 1, e))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = cells.first()
+                && first.len() != row.len()
+            {
+                return Err(BoggleError::InvalidBonus(format!(
+                    "row {} has {} cells, expected {} like the first row",
+                    cells.len() + 1,
+                    row.len(),
+                    first.len()
+                )));
+            }
+            cells.push(row);
+        }
+        if cells.is_empty() {
+            return Err(BoggleError::InvalidBonus("the layout is empty".to_string()));
+        }
+        Ok(BonusLayout { cells })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Bonus {
+        self.cells[row][col]
+    }
+
+    // Each cell's tile value times its letter multiplier, and its word multiplier: all
+    // the search needs to score a path as it goes
+    pub(crate) fn cell_scores(&self, board: &Board) -> Vec<Vec<(u32, u32)>> {
+        (0..board.rows())
+            .map(|r| {
+                (0..board.cols())
+                    .map(|c| {
+                        let value: u32 = board.tile(r, c).chars().map(letter_value).sum();
+                        let bonus = self.get(r, c);
+                        (value * bonus.letter_multiplier(), bonus.word_multiplier())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The Scrabble value of a letter: 1 for the common ones up to 10 for Q and Z. Letters
+/// outside A-Z are worth 1, and anything else, such as a wildcard, nothing.
+pub fn letter_value(ch: char) -> u32 {
+    match ch.to_ascii_uppercase() {
+        'A' | 'E' | 'I' | 'L' | 'N' | 'O' | 'R' | 'S' | 'T' | 'U' => 1,
+        'D' | 'G' => 2,
+        'B' | 'C' | 'M' | 'P' => 3,
+        'F' | 'H' | 'V' | 'W' | 'Y' => 4,
+        'K' => 5,
+        'J' | 'X' => 8,
+        'Q' | 'Z' => 10,
+        ch if ch.is_alphabetic() => 1,
+        _ => 0,
+    }
+}
+
+/// How a word's score adds up along its path on a board with bonuses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// Each cell of the path in order: its letters, their value, and the cell's letter
+    /// multiplier. A wildcard shows the letter it stood for and is worth nothing.
+    pub letters: Vec<(String, u32, u32)>,
+    /// The word multipliers of the cells passed, multiplied together.
+    pub word_multiplier: u32,
+    /// Added after the multipliers: the word's plain Boggle score.
+    pub length_bonus: u32,
+}
+
+impl ScoreBreakdown {
+    /// The letter values times their multipliers, summed, times the word multiplier,
+    /// plus the length bonus.
+    pub fn total(&self) -> u32 {
+        let letters: u32 = self.letters.iter().map(|(_, value, multiplier)| value * multiplier).sum();
+        letters * self.word_multiplier + self.length_bonus
+    }
+}
+
+// "P 3x2 + R 1 + I 1 = 8, x2 = 16, +2 for length = 18"
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letters: Vec<String> = self
+            .letters
+            .iter()
+            .map(|(tile, value, multiplier)| match multiplier {
+                1 => format!("{} {}", tile, value),
+                _ => format!("{} {}x{}", tile, value, multiplier),
+            })
+            .collect();
+        let sum: u32 = self.letters.iter().map(|(_, value, multiplier)| value * multiplier).sum();
+        write!(f, "{} = {}", letters.join(" + "), sum)?;
+        if self.word_multiplier > 1 {
+            write!(f, ", x{} = {}", self.word_multiplier, sum * self.word_multiplier)?;
+        }
+        write!(f, ", +{} for length = {}", self.length_bonus, self.total())
+    }
+}
+
+/// What `found` scores with bonus scoring on a board with no bonus cells: its letter
+/// values, wildcards worth nothing, plus its plain Boggle score. Comparing this with
+/// `bonus_score` shows what the layout added.
+pub fn base_score(found: &FoundWord) -> u32 {
+    let letters: u32 = found.word.chars().map(letter_value).sum();
+    let blanks: u32 = found.wildcards.iter().map(|&(_, letter)| letter_value(letter)).sum();
+    letters - blanks + score(&found.word)
+}
+
+/// The score of `found` along its recorded path on `board` with `layout`.
+pub fn score_breakdown(board: &Board, layout: &BonusLayout, found: &FoundWord) -> ScoreBreakdown {
+    let mut word_multiplier = 1;
+    // Wildcards are listed in path order, so each wildcard cell takes the next one
+    let mut blanks = found.wildcards.iter();
+    let letters = found
+        .path
+        .iter()
+        .map(|&(r, c)| {
+            let bonus = layout.get(r, c);
+            word_multiplier *= bonus.word_multiplier();
+            if board.is_wildcard(r, c)
+                && let Some(&(_, letter)) = blanks.next()
+            {
+                return (letter.to_string(), 0, bonus.letter_multiplier());
+            }
+            let tile = board.tile(r, c);
+            (tile.to_string(), tile.chars().map(letter_value).sum(), bonus.letter_multiplier())
+        })
+        .collect();
+    ScoreBreakdown { letters, word_multiplier, length_bonus: score(&found.word) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{BoggleSolver, SolveResult};
+
+    const WORDS: [&str; 7] = ["cat", "ace", "cater", "quest", "squat", "rote", "set"];
+
+    // The "C A T / Qu E R / S T O" board solved with `layout`
+    fn solve(layout: &str) -> (BoggleSolver<'static>, SolveResult) {
+        let mut solver = BoggleSolver::from_words(Board::from_rows(&["cat", "qer", "sto"]), WORDS).unwrap();
+        solver.set_bonus(Some(BonusLayout::parse(layout).unwrap())).unwrap();
+        let result = solver.solve();
+        (solver, result)
+    }
+
+    fn scores(result: &SolveResult) -> Vec<(&str, u32)> {
+        let mut scores: Vec<(&str, u32)> =
+            result.words.iter().map(|f| (f.word.as_str(), f.bonus_score.unwrap())).collect();
+        scores.sort();
+        scores
+    }
+
+    #[test]
+    fn scores_named_bonuses_by_hand() {
+        // C 3, A 1, T 1, Qu 11, E 1, R 1, S 1, T 1, O 1
+        let (solver, result) = solve("DL . TW\n. DW .\nTL . .\n");
+        let expected = [
+            ("ACE", 17),   // (1 + 3x2 + 1) x2 + 1
+            ("CAT", 25),   // (3x2 + 1 + 1) x3 + 1
+            ("CATER", 62), // (3x2 + 1 + 1 + 1 + 1) x3 x2 + 2
+            ("QUEST", 34), // (11 + 1 + 1x3 + 1) x2 + 2
+            ("ROTE", 9),   // (1 + 1 + 1 + 1) x2 + 1
+            ("SET", 31),   // (1x3 + 1 + 1) x2 x3 + 1, through the T on the triple word
+            ("SQUAT", 50), // (1x3 + 11 + 1 + 1) x3 + 2
+        ];
+        assert_eq!(scores(&result), expected);
+        assert_eq!(result.total_bonus_score(), 228);
+        // Each word's letter values plus its length bonus
+        assert_eq!(result.total_base_score(), 6 + 6 + 9 + 16 + 16 + 5 + 4);
+
+        let set = result.words.iter().find(|f| f.word == "SET").unwrap();
+        assert_eq!(set.path, [(2, 0), (1, 1), (0, 2)]);
+        let cater = result.words.iter().find(|f| f.word == "CATER").unwrap();
+        let breakdown = solver.score_breakdown(cater).unwrap();
+        assert_eq!(breakdown.to_string(), "C 3x2 + A 1 + T 1 + E 1 + R 1 = 10, x6 = 60, +2 for length = 62");
+        assert_eq!(breakdown.total(), 62);
+    }
+
+    #[test]
+    fn scores_numbered_multipliers_by_hand() {
+        // C is worth 3x4, O 1x2, and the Qu and lower T cells multiply whole words
+        let (solver, result) = solve("L4 . .\nW5 . .\n. W4 L2\n");
+        let expected = [
+            ("ACE", 15),    // 1 + 3x4 + 1 + 1
+            ("CAT", 15),    // 3x4 + 1 + 1 + 1
+            ("CATER", 18),  // 3x4 + 1 + 1 + 1 + 1 + 2
+            ("QUEST", 282), // (11 + 1 + 1 + 1) x5 x4 + 2
+            ("ROTE", 21),   // (1 + 1x2 + 1 + 1) x4 + 1
+            ("SET", 13),    // (1 + 1 + 1) x4 + 1, through the lower T
+            ("SQUAT", 72),  // (1 + 11 + 1 + 1) x5 + 2
+        ];
+        assert_eq!(scores(&result), expected);
+        assert_eq!((result.total_bonus_score(), result.total_base_score()), (436, 62));
+        let set = result.words.iter().find(|f| f.word == "SET").unwrap();
+        assert_eq!(set.path, [(2, 0), (1, 1), (2, 1)]);
+        let rote = result.words.iter().find(|f| f.word == "ROTE").unwrap();
+        let breakdown = solver.score_breakdown(rote).unwrap();
+        assert_eq!(breakdown.to_string(), "R 1 + O 1x2 + T 1 + E 1 = 5, x4 = 20, +1 for length = 21");
+    }
+
+    #[test]
+    fn reads_multipliers_and_rejects_bad_layouts() {
+        let layout = BonusLayout::parse("l1 w2 L3\nW10 L7 .\n").unwrap();
+        let row = |r| (0..3).map(|c| layout.get(r, c)).collect::<Vec<_>>();
+        assert_eq!(row(0), [Bonus::None, Bonus::DoubleWord, Bonus::TripleLetter]);
+        assert_eq!(row(1), [Bonus::Word(10), Bonus::Letter(7), Bonus::None]);
+
+        let err = BonusLayout::parse(". L11").unwrap_err();
+        let unknown = "unknown bonus 'L11' (use ., DL, TL, DW, TW, or L<n> or W<n> for n up to 10)";
+        assert_eq!(err.to_string(), format!("invalid bonus layout: row 1, column 2: {}", unknown));
+        let err = BonusLayout::parse(". .\n.\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid bonus layout: row 2 has 1 cells, expected 2 like the first row");
+
+        let mut solver = BoggleSolver::from_words(Board::from_rows(&["cat", "qer", "sto"]), WORDS).unwrap();
+        let err = solver.set_bonus(Some(layout)).unwrap_err();
+        assert!(matches!(err, BoggleError::BonusShape { expected: (3, 3), found: (2, 3) }), "{:?}", err);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::BoggleError;
+
+/// A named dictionary from the config file: its word lists and the word lengths it
+/// is played with, when those differ from the defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictPreset {
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+}
+
+/// The settings read from a config file, a small subset of TOML:
+///
+/// ```toml
+/// # Relative paths are taken from the config file's directory
+/// [dict.sowpods]
+/// path = "lists/sowpods.txt"
+/// min_len = 4
+///
+/// [dict.house]
+/// paths = ["/usr/share/dict/twl06.txt", "house.txt"]
+/// max_len = 8
+/// ```
+///
+/// Only `[dict.NAME]` tables are read, each with `path` or `paths` and optionally
+/// `min_len` and `max_len`. Values are strings, whole numbers or one-line arrays of
+/// strings; `#` starts a comment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    pub dictionaries: Vec<DictPreset>,
+}
+
+impl Config {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| BoggleError::ConfigIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        Config::parse(&text, base).map_err(|e| match e {
+            BoggleError::InvalidConfig(msg) => BoggleError::InvalidConfig(format!("{}:{}", path.display(), msg)),
+            e => e,
+        })
+    }
+
+    /// Parses a config file's text, resolving relative dictionary paths against `base`.
+    /// Errors name the line, as `LINE: message`.
+    pub fn parse(text: &str, base: &Path) -> Result<Self, BoggleError> {
+        let mut dictionaries: Vec<DictPreset> = Vec::new();
+        // Whether the current table is a dictionary; keys of other tables are ignored
+        let mut in_dict = false;
+        for (i, line) in text.lines().enumerate() {
+            let invalid = |msg: &str| BoggleError::InvalidConfig(format!("{}: {}", i + 1, msg));
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(table) = line.strip_prefix('[') {
+                let table = strip_comment(table).trim_end().strip_suffix(']');
+                let table = table.ok_or_else(|| invalid("a table needs its ']'"))?.trim();
+                in_dict = false;
+                if let Some(name) = table.strip_prefix("dict.") {
+                    let name = unquote(name.trim()).ok_or_else(|| invalid("malformed dictionary name"))?;
+                    if name.is_empty() {
+                        return Err(invalid("a dictionary needs a name, as in [dict.sowpods]"));
+                    }
+                    if dictionaries.iter().any(|preset| preset.name == name) {
+                        return Err(invalid(&format!("dictionary '{}' is declared twice", name)));
+                    }
+                    dictionaries.push(DictPreset { name, paths: Vec::new(), min_len: None, max_len: None });
+                    in_dict = true;
+                }
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| invalid("expected key = value"))?;
+            let value = Value::parse(value.trim()).map_err(|msg| invalid(&msg))?;
+            let Some(preset) = dictionaries.last_mut().filter(|_| in_dict) else { continue };
+            match (key.trim(), value) {
+                ("path", Value::Text(path)) => preset.paths = vec![base.join(path)],
+                ("paths", Value::List(paths)) => preset.paths = paths.iter().map(|path| base.join(path)).collect(),
+                ("min_len", Value::Number(n)) => preset.min_len = Some(n),
+                ("max_len", Value::Number(n)) => preset.max_len = Some(n),
+                ("path" | "paths" | "min_len" | "max_len", _) => {
+                    return Err(invalid(&format!("{} has the wrong type", key.trim())));
+                }
+                (key, _) => return Err(invalid(&format!("unknown dictionary key '{}'", key))),
+            }
+        }
+        if let Some(preset) = dictionaries.iter().find(|preset| preset.paths.is_empty()) {
+            return Err(BoggleError::InvalidConfig(format!("dictionary '{}' has no path", preset.name)));
+        }
+        Ok(Config { dictionaries })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DictPreset> {
+        self.dictionaries.iter().find(|preset| preset.name == name)
+    }
+}
+
+enum Value {
+    Text(String),
+    Number(usize),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn parse(text: &str) -> Result<Self, String> {
+        if let Some(items) = text.strip_prefix('[') {
+            let mut list = Vec::new();
+            let mut rest = items.trim_start();
+            loop {
+                if let Some(after) = rest.strip_prefix(']') {
+                    return end(after).map(|()| Value::List(list));
+                }
+                let (item, after) = string(rest).ok_or("array items must be quoted strings")?;
+                list.push(item);
+                rest = after.trim_start();
+                if let Some(after) = rest.strip_prefix(',') {
+                    rest = after.trim_start();
+                } else if !rest.starts_with(']') {
+                    return Err("expected ',' or ']' in the array".to_string());
+                }
+            }
+        }
+        if let Some((text, after)) = string(text) {
+            return end(after).map(|()| Value::Text(text));
+        }
+        let number = strip_comment(text).trim();
+        number.parse().map(Value::Number).map_err(|_| format!("'{}' is not a string, whole number or array", number))
+    }
+}
+
+// A quoted string at the start of `text` and what follows it: "basic" with \" and \\
+// escapes, or 'literal' with none
+fn string(text: &str) -> Option<(String, &str)> {
+    let quote = text.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let mut value = String::new();
+    let mut chars = text[1..].char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            _ if ch == quote => return Some((value, &text[i + 2..])),
+            '\\' if quote == '"' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                escaped @ ('"' | '\\') => value.push(escaped),
+                _ => return None,
+            },
+            _ => value.push(ch),
+        }
+    }
+    None
+}
+
+// A name written bare or in quotes, as in [dict.house] or [dict."my list"]
+fn unquote(name: &str) -> Option<String> {
+    match string(name) {
+        Some((name, rest)) => rest.trim().is_empty().then_some(name),
+        None => Some(name.to_string()),
+    }
+}
+
+// Nothing may follow a value but a comment
+fn end(rest: &str) -> Result<(), String> {
+    if strip_comment(rest).trim().is_empty() { Ok(()) } else { Err("unexpected text after the value".to_string()) }
+}
+
+fn strip_comment(text: &str) -> &str {
+    text.split('#').next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("boggle_config_{}_{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn parse_error(text: &str) -> String {
+        match Config::parse(text, Path::new("/conf")) {
+            Err(BoggleError::InvalidConfig(msg)) => msg,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_presets_with_paths_against_the_files_directory() {
+        let dir = temp_dir("presets");
+        let path = dir.join("boggle.toml");
+        let text = "# presets\n[ui]\ntheme = 'dark'\n\n\
+                    [dict.sowpods]  # the big one\npath = \"lists/sowpods.txt\"\nmin_len = 4\n\n\
+                    [dict.\"house\"]\npaths = ['/usr/share/dict/twl06.txt', \"house.txt\"]  \nmax_len = 8\n";
+        fs::write(&path, text).unwrap();
+        let config = Config::from_path(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let sowpods = DictPreset {
+            name: "sowpods".to_string(),
+            paths: vec![dir.join("lists/sowpods.txt")],
+            min_len: Some(4),
+            max_len: None,
+        };
+        let house = DictPreset {
+            name: "house".to_string(),
+            paths: vec![PathBuf::from("/usr/share/dict/twl06.txt"), dir.join("house.txt")],
+            min_len: None,
+            max_len: Some(8),
+        };
+        assert_eq!(config, Config { dictionaries: vec![sowpods.clone(), house] });
+        assert_eq!(config.get("sowpods"), Some(&sowpods));
+        assert_eq!(config.get("twl"), None);
+    }
+
+    #[test]
+    fn a_missing_file_is_a_config_io_error() {
+        let path = temp_dir("missing").join("no_such.toml");
+        let err = Config::from_path(&path).unwrap_err();
+        assert!(matches!(err, BoggleError::ConfigIo(_)), "{:?}", err);
+        assert!(err.to_string().contains("no_such.toml"), "{}", err);
+    }
+
+    #[test]
+    fn names_the_line_of_malformed_toml() {
+        assert_eq!(parse_error("[dict.x\npath = 'a'\n"), "1: a table needs its ']'");
+        assert_eq!(parse_error("[dict.x]\npath 'a'\n"), "2: expected key = value");
+        assert_eq!(parse_error("[dict.x]\npath = 3\n"), "2: path has the wrong type");
+        assert_eq!(parse_error("[dict.x]\npath = 'a'\nsize = 3\n"), "3: unknown dictionary key 'size'");
+        assert_eq!(parse_error("[dict.x]\npaths = ['a' 'b']\n"), "2: expected ',' or ']' in the array");
+        assert_eq!(parse_error("[dict.x]\nmin_len = four\n"), "2: 'four' is not a string, whole number or array");
+        assert_eq!(parse_error("[dict.]\n"), "1: a dictionary needs a name, as in [dict.sowpods]");
+
+        // From a file, the error names the file too
+        let dir = temp_dir("malformed");
+        let path = dir.join("boggle.toml");
+        fs::write(&path, "[dict.x\n").unwrap();
+        let err = Config::from_path(&path).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(err.to_string(), format!("invalid config file: {}:1: a table needs its ']'", path.display()));
+    }
+
+    #[test]
+    fn rejects_presets_without_a_path_or_declared_twice() {
+        assert_eq!(parse_error("[dict.a]\npath = 'a.txt'\n[dict.b]\nmin_len = 4\n"), "dictionary 'b' has no path");
+        let twice = "[dict.a]\npath = 'a.txt'\n\n[dict.a]\npath = 'b.txt'\n";
+        assert_eq!(parse_error(twice), "4: dictionary 'a' is declared twice");
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date, as the daily puzzle is picked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// The date `year-month-day`, if there is one, such as no February 30th.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        (1..=days).contains(&day).then_some(Date { year, month, day })
+    }
+
+    /// Today's date in UTC, so everyone gets the same puzzle whatever their time zone.
+    pub fn today() -> Self {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Date::from_days((secs / 86_400) as i64)
+    }
+
+    // The date `days` after 1970-01-01, by Howard Hinnant's civil_from_days
+    fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Reads `YYYY-MM-DD`, as in `2024-06-01`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date '{}' (expected YYYY-MM-DD)", s);
+        let parts: Vec<&str> = s.split('-').collect();
+        let [year, month, day] = parts[..] else { return Err(invalid()) };
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+        let number = |part: &str| part.bytes().all(|b| b.is_ascii_digit()).then(|| part.parse().ok()).flatten();
+        match (number(year), number(month), number(day)) {
+            (Some(year), Some(month), Some(day)) => Date::new(year as i32, month, day).ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The seed of the daily puzzle for `date`: the 64-bit FNV-1a hash of the text
+/// `boggle-daily:YYYY-MM-DD:SALT`, the salt being empty when not given. Rolled with
+/// `roll`, it gives every player with the same date and salt the same board, so a
+/// group picks its own boards by agreeing on a salt.
+///
+/// Changing this changes every past puzzle, so it must stay as it is: 2024-06-01 with
+/// the salt `club` always rolls the classic board `cpso slpw veri otun`.
+pub fn daily_seed(date: Date, salt: &str) -> u64 {
+    format!("boggle-daily:{}:{}", date, salt)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::{CLASSIC, roll};
+    use crate::rng::Rng;
+
+    #[test]
+    fn past_puzzles_keep_their_boards() {
+        let date = Date::new(2024, 6, 1).unwrap();
+        let board = roll(&CLASSIC, &mut Rng::new(daily_seed(date, "club")));
+        let rows: Vec<String> = board.tiles().iter().map(|row| row.concat().to_lowercase()).collect();
+        assert_eq!(rows.join(" "), "cpso slpw veri otun");
+        assert_ne!(daily_seed(date, "club"), daily_seed(date, ""));
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use crate::error::BoggleError;
+use crate::trie::{WordIndex, WordNode};
+
+/// A node of a DAWG (minimized acyclic automaton). Identical subtrees are one shared
+/// node, so common suffixes like "-ING" are stored once instead of per word.
+pub struct DawgNode {
+    children: Vec<(char, Arc<DawgNode>)>, // Sorted by letter
+    is_word: bool,
+}
+
+/// A dictionary built by merging the identical suffix subtrees of a trie. Cloning it
+/// shares the nodes.
+#[derive(Clone)]
+pub struct Dawg {
+    root: Arc<DawgNode>,
+    node_count: usize,
+}
+
+// A node is identified by its word flag and its (letter, child) edges; children are
+// already canonical, so their addresses identify them.
+type Signature = (bool, Vec<(char, usize)>);
+
+impl Dawg {
+    /// Minimizes the trie below `root`, which can be any trie's root node.
+    pub fn from_trie<N: WordNode>(root: N) -> Self {
+        let mut registry: HashMap<Signature, Arc<DawgNode>> = HashMap::new();
+        let root = Self::canonical(root, &mut registry);
+        Dawg { root, node_count: registry.len() }
+    }
+
+    fn canonical<N: WordNode>(node: N, registry: &mut HashMap<Signature, Arc<DawgNode>>) -> Arc<DawgNode> {
+        // `children` is already in letter order
+        let children: Vec<(char, Arc<DawgNode>)> =
+            node.children().into_iter().map(|(ch, child)| (ch, Self::canonical(child, registry))).collect();
+
+        let signature: Signature =
+            (node.is_word(), children.iter().map(|(ch, child)| (*ch, Arc::as_ptr(child) as usize)).collect());
+        registry
+            .entry(signature)
+            .or_insert_with(|| Arc::new(DawgNode { children, is_word: node.is_word() }))
+            .clone()
+    }
+
+    pub fn root(&self) -> &DawgNode {
+        &self.root
+    }
+
+    /// Number of distinct nodes after merging.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// An estimate of the bytes the nodes take: each shared node once, with its `Arc`
+    /// counts and its edge list's capacity, but not the allocator's own overhead.
+    pub fn heap_bytes(&self) -> usize {
+        fn walk(node: &DawgNode, seen: &mut HashSet<*const DawgNode>) -> usize {
+            let mut bytes = 2 * std::mem::size_of::<usize>() + std::mem::size_of::<DawgNode>();
+            bytes += node.children.capacity() * std::mem::size_of::<(char, Arc<DawgNode>)>();
+            for (_, child) in &node.children {
+                if seen.insert(Arc::as_ptr(child)) {
+                    bytes += walk(child, seen);
+                }
+            }
+            bytes
+        }
+        walk(&self.root, &mut HashSet::new())
+    }
+}
+
+impl WordIndex for Dawg {
+    type Node<'a> = &'a DawgNode;
+
+    fn root(&self) -> &DawgNode {
+        Dawg::root(self)
+    }
+}
+
+impl WordNode for &DawgNode {
+    fn child(self, ch: char) -> Option<Self> {
+        self.children.iter().find(|(c, _)| *c == ch).map(|(_, child)| child.as_ref())
+    }
+
+    fn is_word(self) -> bool {
+        self.is_word
+    }
+
+    fn children(self) -> Vec<(char, Self)> {
+        self.children.iter().map(|(ch, child)| (*ch, child.as_ref())).collect()
+    }
+}
+
+// Binary layout: node count, then nodes in post-order (children before parents, root
+// last). Each node is a word flag, its edge count, and per edge the letter and child id.
+impl Dawg {
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut ids: HashMap<*const DawgNode, usize> = HashMap::new();
+        let mut order: Vec<&DawgNode> = Vec::new();
+        Self::number(&self.root, &mut ids, &mut order);
+
+        write_varint(out, order.len() as u64)?;
+        for node in order {
+            out.write_all(&[node.is_word as u8])?;
+            write_varint(out, node.children.len() as u64)?;
+            for (ch, child) in &node.children {
+                write_varint(out, *ch as u64)?;
+                write_varint(out, ids[&Arc::as_ptr(child)] as u64)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn number<'a>(node: &'a Arc<DawgNode>, ids: &mut HashMap<*const DawgNode, usize>, order: &mut Vec<&'a DawgNode>) {
+        if ids.contains_key(&Arc::as_ptr(node)) {
+            return;
+        }
+        for (_, child) in &node.children {
+            Self::number(child, ids, order);
+        }
+        ids.insert(Arc::as_ptr(node), order.len());
+        order.push(node);
+    }
+
+    pub fn read_from<R: Read>(input: &mut R) -> Result<Self, BoggleError> {
+        let corrupt = |msg: &str| BoggleError::CorruptCache(msg.to_string());
+
+        let count = read_varint(input)? as usize;
+        let mut nodes: Vec<Arc<DawgNode>> = Vec::with_capacity(count.min(1 << 24));
+        for _ in 0..count {
+            let mut flag = [0u8];
+            input.read_exact(&mut flag).map_err(BoggleError::DictionaryIo)?;
+            let edges = read_varint(input)? as usize;
+            let mut children = Vec::with_capacity(edges.min(256));
+            for _ in 0..edges {
+                let ch = char::from_u32(read_varint(input)? as u32).ok_or_else(|| corrupt("bad letter"))?;
+                let id = read_varint(input)? as usize;
+                // Post-order means every child was already read
+                let child = nodes.get(id).ok_or_else(|| corrupt("bad child index"))?;
+                children.push((ch, child.clone()));
+            }
+            nodes.push(Arc::new(DawgNode { children, is_word: flag[0] != 0 }));
+        }
+
+        let root = nodes.last().cloned().ok_or_else(|| corrupt("no nodes"))?;
+        Ok(Dawg { root, node_count: nodes.len() })
+    }
+}
+
+fn write_varint<W: Write>(out: &mut W, mut n: u64) -> io::Result<()> {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            return out.write_all(&[byte]);
+        }
+        out.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(input: &mut R) -> Result<u64, BoggleError> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        input.read_exact(&mut byte).map_err(BoggleError::DictionaryIo)?;
+        n |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(BoggleError::CorruptCache("varint too long".to_string()))
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::BoggleError;
+
+/// Word definitions from a `WORD<TAB>definition` file, looked up case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    map: HashMap<String, String>,
+}
+
+impl Definitions {
+    /// Loads a definitions file. Lines without a tab are skipped; for a word listed
+    /// twice the first definition wins.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| BoggleError::DefinitionsIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        Ok(Definitions::parse(&text))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut map = HashMap::new();
+        for line in text.lines() {
+            if let Some((word, definition)) = line.split_once('\t') {
+                map.entry(word.trim().to_uppercase()).or_insert_with(|| definition.trim().to_string());
+            }
+        }
+        Definitions { map }
+    }
+
+    pub fn get(&self, word: &str) -> Option<&str> {
+        self.map.get(&word.trim().to_uppercase()).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
@@ -0,0 +1,161 @@
+use crate::board::Board;
+use crate::rng::Rng;
+
+/// A physical set of Boggle cubes and the square grid they fill. Faces may hold more
+/// than one letter ("QU", "TH", ...), and an empty face is a solid block.
+pub struct DiceSet {
+    pub name: &'static str,
+    pub size: usize,
+    /// Shortest word that counts under the official rules for this set
+    pub min_len: usize,
+    pub dice: &'static [[&'static str; 6]],
+}
+
+/// The 16 cubes of classic 4x4 Boggle.
+pub const CLASSIC: DiceSet = DiceSet {
+    name: "classic",
+    size: 4,
+    min_len: 3,
+    dice: &[
+        ["A", "A", "E", "E", "G", "N"],
+        ["A", "B", "B", "J", "O", "O"],
+        ["A", "C", "H", "O", "P", "S"],
+        ["A", "F", "F", "K", "P", "S"],
+        ["A", "O", "O", "T", "T", "W"],
+        ["C", "I", "M", "O", "T", "U"],
+        ["D", "E", "I", "L", "R", "X"],
+        ["D", "E", "L", "R", "V", "Y"],
+        ["D", "I", "S", "T", "T", "Y"],
+        ["E", "E", "G", "H", "N", "W"],
+        ["E", "E", "I", "N", "S", "U"],
+        ["E", "H", "R", "T", "V", "W"],
+        ["E", "I", "O", "S", "S", "T"],
+        ["E", "L", "R", "T", "T", "Y"],
+        ["H", "I", "M", "N", "U", "QU"],
+        ["H", "L", "N", "N", "R", "Z"],
+    ],
+};
+
+/// The 25 cubes of Big Boggle (5x5).
+pub const BIG: DiceSet = DiceSet {
+    name: "big",
+    size: 5,
+    min_len: 4,
+    dice: &[
+        ["A", "A", "A", "F", "R", "S"],
+        ["A", "A", "E", "E", "E", "E"],
+        ["A", "A", "F", "I", "R", "S"],
+        ["A", "D", "E", "N", "N", "N"],
+        ["A", "E", "E", "E", "E", "M"],
+        ["A", "E", "E", "G", "M", "U"],
+        ["A", "E", "G", "M", "N", "N"],
+        ["A", "F", "I", "R", "S", "Y"],
+        ["B", "J", "K", "QU", "X", "Z"],
+        ["C", "C", "E", "N", "S", "T"],
+        ["C", "E", "I", "I", "L", "T"],
+        ["C", "E", "I", "L", "P", "T"],
+        ["C", "E", "I", "P", "S", "T"],
+        ["D", "D", "H", "N", "O", "T"],
+        ["D", "H", "H", "L", "O", "R"],
+        ["D", "H", "L", "N", "O", "R"],
+        ["D", "H", "L", "N", "O", "R"],
+        ["E", "I", "I", "I", "T", "T"],
+        ["E", "M", "O", "T", "T", "T"],
+        ["E", "N", "S", "S", "S", "U"],
+        ["F", "I", "P", "R", "S", "Y"],
+        ["G", "O", "R", "R", "V", "W"],
+        ["I", "P", "R", "R", "R", "Y"],
+        ["N", "O", "O", "T", "U", "W"],
+        ["O", "O", "O", "T", "T", "U"],
+    ],
+};
+
+/// The 36 cubes of Super Big Boggle (6x6), with a multi-letter cube and a block cube.
+pub const SUPER_BIG: DiceSet = DiceSet {
+    name: "super-big",
+    size: 6,
+    min_len: 4,
+    dice: &[
+        ["A", "A", "A", "F", "R", "S"],
+        ["A", "A", "E", "E", "E", "E"],
+        ["A", "A", "E", "E", "O", "O"],
+        ["A", "A", "F", "I", "R", "S"],
+        ["A", "B", "D", "E", "I", "O"],
+        ["A", "D", "E", "N", "N", "N"],
+        ["A", "E", "E", "E", "E", "M"],
+        ["A", "E", "E", "G", "M", "U"],
+        ["A", "E", "G", "M", "N", "N"],
+        ["A", "E", "I", "L", "M", "N"],
+        ["A", "E", "I", "N", "O", "U"],
+        ["A", "F", "I", "R", "S", "Y"],
+        ["AN", "ER", "HE", "IN", "QU", "TH"],
+        ["B", "B", "J", "K", "X", "Z"],
+        ["C", "C", "E", "N", "S", "T"],
+        ["C", "D", "D", "L", "N", "N"],
+        ["C", "E", "I", "I", "T", "T"],
+        ["C", "E", "I", "P", "S", "T"],
+        ["C", "F", "G", "N", "U", "Y"],
+        ["D", "D", "H", "N", "O", "T"],
+        ["D", "H", "H", "L", "O", "R"],
+        ["D", "H", "H", "N", "O", "W"],
+        ["D", "H", "L", "N", "O", "R"],
+        ["E", "H", "I", "L", "R", "S"],
+        ["E", "I", "I", "L", "S", "T"],
+        ["E", "I", "L", "P", "S", "T"],
+        ["E", "I", "O", "", "", ""],
+        ["E", "M", "T", "T", "T", "O"],
+        ["E", "N", "S", "S", "S", "U"],
+        ["G", "O", "R", "R", "V", "W"],
+        ["H", "I", "R", "S", "T", "V"],
+        ["H", "O", "P", "R", "S", "T"],
+        ["I", "P", "R", "S", "Y", "Y"],
+        ["J", "K", "QU", "W", "X", "Z"],
+        ["N", "O", "O", "T", "U", "W"],
+        ["O", "O", "O", "T", "T", "U"],
+    ],
+};
+
+/// Every built-in set, smallest first.
+pub const DICE_SETS: &[&DiceSet] = &[&CLASSIC, &BIG, &SUPER_BIG];
+
+impl DiceSet {
+    /// Every letter face of every cube, repeats included, so common letters come up
+    /// as often as they do on the real dice. Block faces are left out.
+    pub fn faces(&self) -> Vec<&'static str> {
+        self.dice.iter().flatten().copied().filter(|face| !face.is_empty()).collect()
+    }
+}
+
+/// The built-in set for an `n`x`n` board, if there is one.
+pub fn dice_for_size(n: usize) -> Option<&'static DiceSet> {
+    DICE_SETS.iter().copied().find(|set| set.size == n)
+}
+
+/// Shuffles the cubes into the grid and rolls each one to a random face. The cubes are
+/// shuffled first and then rolled in grid order, so an `Rng` with the same seed always
+/// gives the same board, on any platform.
+pub fn roll(set: &DiceSet, rng: &mut Rng) -> Board {
+    let mut order: Vec<usize> = (0..set.dice.len()).collect();
+    rng.shuffle(&mut order);
+
+    let faces: Vec<String> = order.iter().map(|&i| set.dice[i][rng.below(6)].to_string()).collect();
+    Board::new(faces.chunks(set.size).map(|row| row.to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(board: &Board) -> String {
+        board.tiles().iter().map(|row| row.concat().to_lowercase()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn a_seed_always_rolls_the_same_board() {
+        assert_eq!(rows(&roll(&CLASSIC, &mut Rng::new(53))), "hbue uepd esti xoat");
+        let big = dice_for_size(5).unwrap();
+        assert_eq!(rows(&roll(big, &mut Rng::new(53))), "ttiwe cehew gssmn prtequ ocfau");
+        assert_eq!(rows(&roll(&CLASSIC, &mut Rng::new(53))), rows(&roll(&CLASSIC, &mut Rng::new(53))));
+        assert_ne!(rows(&roll(&CLASSIC, &mut Rng::new(53))), rows(&roll(&CLASSIC, &mut Rng::new(54))));
+    }
+}
@@ -0,0 +1,1178 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::arena::ArenaTrie;
+use crate::board::{Board, WILDCARD};
+use crate::dawg::Dawg;
+use crate::encoding::{Encoding, Lines};
+use crate::error::BoggleError;
+use crate::fst::Fst;
+use crate::gzip;
+use crate::locale::Locale;
+use crate::solver::BoggleSolver;
+use crate::sorted::SortedWords;
+use crate::trie::{WordIndex, WordNode};
+
+const CACHE_MAGIC: &[u8; 8] = b"BOGDICT\0";
+const CACHE_VERSION: u8 = 2;
+
+// The structure the search walks: the arena trie, its minimized DAWG, a sorted list,
+// or a loaded FST
+#[derive(Clone)]
+pub(crate) enum Lexicon {
+    Trie(ArenaTrie),
+    Dawg(Dawg),
+    Sorted(SortedWords),
+    Fst(Fst),
+}
+
+/// Which word lengths (in letters) make it into the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthFilter {
+    pub min_len: usize,
+    /// There is no upper cap by default: a board can never spell more letters than it
+    /// holds, and the same dictionary may be reused for a bigger board
+    pub max_len: Option<usize>,
+}
+
+impl Default for LengthFilter {
+    fn default() -> Self {
+        LengthFilter { min_len: 3, max_len: None }
+    }
+}
+
+impl LengthFilter {
+    pub fn new(min_len: usize, max_len: Option<usize>) -> Result<Self, BoggleError> {
+        if let Some(max) = max_len
+            && min_len > max
+        {
+            return Err(BoggleError::InvalidLengthFilter { min_len, max_len: max });
+        }
+        Ok(LengthFilter { min_len, max_len })
+    }
+
+    /// Whether a word of `len` letters passes the filter.
+    pub fn allows(&self, len: usize) -> bool {
+        len >= self.min_len && self.max_len.is_none_or(|max| len <= max)
+    }
+}
+
+/// The letters a board has to offer, used to leave out words it could never spell
+/// while the dictionary is built. Each tile's letters count once, as no cell may be used
+/// twice, and each wildcard may make up for any one missing letter. When cells may be
+/// reused, a word only needs every letter somewhere on the board, and a board with a
+/// wildcard keeps every word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterPool {
+    ascii: [u16; 26],
+    other: Vec<(char, u16)>,
+    wildcards: u16,
+    reuse: bool,
+}
+
+impl LetterPool {
+    pub fn new(board: &Board, reuse: bool) -> Self {
+        let mut pool = LetterPool { ascii: [0; 26], other: Vec::new(), wildcards: 0, reuse };
+        for tile in board.tiles().iter().flatten() {
+            if tile == WILDCARD {
+                pool.wildcards += 1;
+                continue;
+            }
+            for ch in tile.chars() {
+                match ch {
+                    'A'..='Z' => pool.ascii[(ch as u8 - b'A') as usize] += 1,
+                    _ => match pool.other.iter_mut().find(|(other, _)| *other == ch) {
+                        Some((_, count)) => *count += 1,
+                        None => pool.other.push((ch, 1)),
+                    },
+                }
+            }
+        }
+        pool
+    }
+
+    /// Whether the board has enough of each letter for `word` (uppercase).
+    pub fn can_spell(&self, word: &str) -> bool {
+        if self.reuse {
+            return self.wildcards > 0 || word.chars().all(|ch| self.count(ch) > 0);
+        }
+        let mut ascii = self.ascii;
+        let mut other = None;
+        let mut spare = self.wildcards;
+        for ch in word.chars() {
+            let left = match ch {
+                'A'..='Z' => Some(&mut ascii[(ch as u8 - b'A') as usize]),
+                // Copied only for the rare word with a letter outside A-Z
+                _ => other
+                    .get_or_insert_with(|| self.other.clone())
+                    .iter_mut()
+                    .find(|(other, _)| *other == ch)
+                    .map(|(_, count)| count),
+            };
+            match left {
+                Some(left) if *left > 0 => *left -= 1,
+                _ if spare > 0 => spare -= 1,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn count(&self, ch: char) -> u16 {
+        match ch {
+            'A'..='Z' => self.ascii[(ch as u8 - b'A') as usize],
+            _ => self.other.iter().find(|(other, _)| *other == ch).map_or(0, |(_, count)| *count),
+        }
+    }
+}
+
+/// The size and shape of a dictionary's structure, from `Dictionary::stats`. Apart from
+/// `nodes`, the counts are of the trie the words spell, so a DAWG or FST, which shares
+/// the nodes for common endings, reports the same shape as the trie it was built from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrieStats {
+    /// Nodes actually stored, as `Dictionary::node_count` counts them.
+    pub nodes: usize,
+    pub words: usize,
+    /// Letters in the longest word.
+    pub max_depth: usize,
+    /// Trie nodes with at least one child, and the links out of them.
+    pub branch_nodes: usize,
+    pub edges: usize,
+    /// An estimate of the heap bytes the structure takes: the arena trie's node array
+    /// (one allocation, with no per-node hash table), the DAWG's shared nodes and edge
+    /// lists, the sorted list's strings, or the FST's buffer. Allocator overhead and
+    /// spare capacity the allocator keeps are not counted.
+    pub heap_bytes: usize,
+}
+
+impl TrieStats {
+    /// Children per node that has any.
+    pub fn branching(&self) -> f64 {
+        if self.branch_nodes == 0 { 0.0 } else { self.edges as f64 / self.branch_nodes as f64 }
+    }
+}
+
+/// One word list merged into a dictionary, and how many words it contributed that no
+/// earlier source already had.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub name: String,
+    pub new_words: usize,
+    /// How the file was decoded; see `LoadOptions::encoding`.
+    pub encoding: Encoding,
+}
+
+/// How long building a dictionary took: reading the word files (or the cache), and
+/// inserting into the trie plus any minimizing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadStats {
+    pub read_time: Duration,
+    pub build_time: Duration,
+    /// Every line read from the word files, blank ones included.
+    pub lines_read: usize,
+    /// Non-blank lines read from the word files, repeats included.
+    pub words_read: usize,
+    /// Lines skipped for holding something other than letters: digits, punctuation,
+    /// spaces inside the word.
+    pub words_skipped: usize,
+    /// Words dropped for being shorter or longer than the length filter allows.
+    pub words_filtered: usize,
+    pub words_inserted: usize,
+    /// Words left out because the board lacks their letters (see `LetterPool`).
+    pub words_pruned: usize,
+    /// Threads the trie was built on: 1 for the calling thread alone, 0 when nothing
+    /// was built (a cache or an FST).
+    pub build_threads: usize,
+}
+
+impl LoadStats {
+    // Counts one line of a word list by what `sift` made of it; inserted words are
+    // counted by the caller, who knows whether they were new
+    fn count(&mut self, line: &Line) {
+        self.lines_read += 1;
+        match line {
+            Line::Blank => return,
+            Line::NotLetters => self.words_skipped += 1,
+            Line::OutsideFilter => self.words_filtered += 1,
+            Line::Pruned => self.words_pruned += 1,
+            Line::Word(_) => {}
+        }
+        self.words_read += 1;
+    }
+
+    fn add(&mut self, other: &LoadStats) {
+        self.lines_read += other.lines_read;
+        self.words_read += other.words_read;
+        self.words_skipped += other.words_skipped;
+        self.words_filtered += other.words_filtered;
+        self.words_inserted += other.words_inserted;
+        self.words_pruned += other.words_pruned;
+    }
+}
+
+// What loading makes of one line of a word list
+enum Line {
+    Blank,
+    NotLetters,
+    OutsideFilter,
+    Pruned,
+    Word(String),
+}
+
+// A line holding anything but letters, such as a number or "don't", is skipped rather
+// than put in the trie, where no board could spell it anyway
+fn sift(line: &str, filter: LengthFilter, pool: Option<&LetterPool>, locale: Locale) -> Line {
+    // Lengths count letters, so a word through the Qu tile counts both Q and U
+    let upper = locale.upper(line.trim());
+    if upper.is_empty() {
+        Line::Blank
+    } else if !upper.chars().all(char::is_alphabetic) {
+        Line::NotLetters
+    } else if !filter.allows(upper.chars().count()) {
+        Line::OutsideFilter
+    } else if pool.is_some_and(|pool| !pool.can_spell(&upper)) {
+        Line::Pruned
+    } else {
+        Line::Word(upper)
+    }
+}
+
+/// How `Dictionary::from_paths_with` loads word lists, beyond the length filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Leave out every word the pool cannot spell, as `from_paths_for` does.
+    pub pool: Option<LetterPool>,
+    /// Fail with `TooManyWords` as soon as the word lists hold more words than this,
+    /// before the rest is read, so a runaway file is caught before it fills memory.
+    pub max_words: Option<usize>,
+    /// Threads to build the trie on; 0 or 1 builds it on the calling thread. The
+    /// dictionary is the same either way.
+    pub threads: usize,
+    /// How the words are uppercased, and then every word looked up; boards to solve
+    /// with the dictionary should be parsed with the same locale (`ParseOptions`).
+    pub locale: Locale,
+    /// The word lists' encoding. With `None`, a byte order mark picks UTF-8 or UTF-16,
+    /// and a file without one is UTF-8 except for any line that is not, which is read
+    /// as Latin-1 (and the file's `Source` says so). Given outright, a line that does
+    /// not decode is an error.
+    pub encoding: Option<Encoding>,
+}
+
+/// A loaded word list, along with the length filter it was built with.
+#[derive(Clone)]
+pub struct Dictionary {
+    pub(crate) lexicon: Lexicon,
+    filter: LengthFilter,
+    sources: Vec<Source>,
+    load: LoadStats,
+    pool: Option<LetterPool>,
+    max_words: Option<usize>,
+    threads: usize,
+    locale: Locale,
+    encoding: Option<Encoding>,
+    // Counting walks the whole trie, so it is done once and redone only after a change
+    nodes: OnceLock<usize>,
+    longest: OnceLock<usize>,
+    words: OnceLock<Vec<String>>,
+    stats: OnceLock<TrieStats>,
+}
+
+fn read_u32<R: Read>(input: &mut R) -> Result<usize, BoggleError> {
+    let mut bytes = [0u8; 4];
+    input.read_exact(&mut bytes).map_err(BoggleError::DictionaryIo)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+/// The word list compiled into the binary with the `embedded-dict` feature.
+#[cfg(feature = "embedded-dict")]
+pub const EMBEDDED_WORDS: &str = include_str!("../words.txt");
+
+// Name the source in the error, since several may be loaded together
+fn named(name: &str, e: io::Error) -> BoggleError {
+    BoggleError::DictionaryIo(io::Error::new(e.kind(), format!("{}: {}", name, e)))
+}
+
+// A word list as opened: the bytes of a saved FST, or lines of words
+enum WordFile {
+    Fst(Vec<u8>),
+    Words(Box<dyn BufRead>),
+}
+
+// Opens a word list, decompressing it first if it is gzipped (by extension or content).
+// The file is opened once and sniffed through its buffer, so a pipe loses nothing.
+fn open_words(path: &Path, name: &str) -> Result<WordFile, BoggleError> {
+    let mut file = BufReader::new(File::open(path).map_err(|e| named(name, e))?);
+    let head = file.fill_buf().map_err(|e| named(name, e))?;
+    let fst = Fst::is_fst(head);
+    let gzipped = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz")) || gzip::is_gzip(head);
+    if !fst && !gzipped {
+        return Ok(WordFile::Words(Box::new(file)));
+    }
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).map_err(|e| named(name, e))?;
+    if fst {
+        return Ok(WordFile::Fst(data));
+    }
+    let text = gzip::gunzip(&data).map_err(|e| named(name, io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    Ok(WordFile::Words(Box::new(io::Cursor::new(text))))
+}
+
+// Reads every line, failing once the words on them and the `read` before them pass `limit`
+fn read_words<R: BufRead>(
+    reader: R,
+    name: &str,
+    read: usize,
+    limit: Option<usize>,
+    encoding: Option<Encoding>,
+) -> Result<(Vec<String>, Encoding), BoggleError> {
+    let mut lines = Vec::new();
+    let mut words = read;
+    let mut reader = Lines::new(reader, encoding).map_err(|e| named(name, e))?;
+    for line in reader.by_ref() {
+        let line = line.map_err(|e| named(name, e))?;
+        if !line.trim().is_empty() {
+            words += 1;
+            if let Some(limit) = limit
+                && words > limit
+            {
+                return Err(BoggleError::TooManyWords { limit, source: name.to_string() });
+            }
+        }
+        lines.push(line);
+    }
+    Ok((lines, reader.encoding()))
+}
+
+impl Dictionary {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        Dictionary::from_path_with(path, LengthFilter::default())
+    }
+
+    pub fn from_path_with<P: AsRef<Path>>(path: P, filter: LengthFilter) -> Result<Self, BoggleError> {
+        Dictionary::from_paths(&[path], filter)
+    }
+
+    /// A solver for `board` that borrows this dictionary instead of owning it, with the
+    /// default rules. Load once and call this per board: nothing is rebuilt, and the
+    /// solvers can run side by side on other threads.
+    pub fn solver(&self, board: Board) -> Result<BoggleSolver<'_>, BoggleError> {
+        BoggleSolver::with_words(board, Cow::Borrowed(self))
+    }
+
+    /// Merges several word lists into one dictionary; duplicates collapse in the trie.
+    /// Gzipped lists (`.gz`, or starting with the gzip magic bytes) are decompressed.
+    /// A single FST file made by `Fst::save` is loaded as it is instead (see `from_fst`).
+    /// Fails on the first unreadable file, naming it, or if no word passes the filter.
+    pub fn from_paths<P: AsRef<Path>>(paths: &[P], filter: LengthFilter) -> Result<Self, BoggleError> {
+        Dictionary::from_paths_with(paths, filter, &LoadOptions::default())
+    }
+
+    /// Same as `from_paths`, but leaves out every word `pool` cannot spell, which for one
+    /// known board is most of them and makes both the load and the trie much smaller.
+    /// Solving the board the pool came from finds the same words, but the dictionary
+    /// no longer knows the words left out, so it suits only that board.
+    pub fn from_paths_for<P: AsRef<Path>>(
+        paths: &[P],
+        filter: LengthFilter,
+        pool: LetterPool,
+    ) -> Result<Self, BoggleError> {
+        Dictionary::from_paths_with(paths, filter, &LoadOptions { pool: Some(pool), ..LoadOptions::default() })
+    }
+
+    /// Same as `from_paths`, with the pruning and word limit of `opts`. The limit counts
+    /// the non-blank lines of all the lists together, before any filter; an FST is
+    /// held to it by the words it has.
+    pub fn from_paths_with<P: AsRef<Path>>(
+        paths: &[P],
+        filter: LengthFilter,
+        opts: &LoadOptions,
+    ) -> Result<Self, BoggleError> {
+        let start = Instant::now();
+        let mut dict = Dictionary::from_words_with(std::iter::empty::<&str>(), filter);
+        dict.pool = opts.pool.clone();
+        dict.max_words = opts.max_words;
+        dict.threads = opts.threads;
+        dict.locale = opts.locale;
+        dict.encoding = opts.encoding;
+        for path in paths {
+            let name = path.as_ref().display().to_string();
+            let data = match open_words(path.as_ref(), &name)? {
+                WordFile::Words(words) => {
+                    dict.add_source(words, name)?;
+                    continue;
+                }
+                WordFile::Fst(data) => data,
+            };
+            if paths.len() > 1 {
+                return Err(BoggleError::InvalidFst(format!(
+                    "{}: an FST cannot be merged with other word lists; build one from all of them",
+                    name
+                )));
+            }
+            let mut dict = Dictionary::with_fst(Fst::from_bytes(&data, &name, filter)?, name, filter, start)?;
+            // The words were uppercased when the FST was built; this is for lookups
+            dict.locale = opts.locale;
+            if let Some(limit) = opts.max_words
+                && dict.load.words_inserted > limit
+            {
+                return Err(BoggleError::TooManyWords { limit, source: path.as_ref().display().to_string() });
+            }
+            return Ok(dict);
+        }
+        dict.non_empty()
+    }
+
+    /// Loads an FST saved by `Fst::save`, searched in place without building a trie.
+    /// Only words `filter` allows are found, and it must not ask for lengths the FST
+    /// was built without. Like a DAWG, it cannot take more words or exclusions.
+    pub fn from_fst<P: AsRef<Path>>(path: P, filter: LengthFilter) -> Result<Self, BoggleError> {
+        let start = Instant::now();
+        let fst = Fst::load(&path, filter)?;
+        Dictionary::with_fst(fst, path.as_ref().display().to_string(), filter, start)
+    }
+
+    // A dictionary searching `fst`, which `name` was read from since `start`
+    fn with_fst(fst: Fst, name: String, filter: LengthFilter, start: Instant) -> Result<Self, BoggleError> {
+        let words = fst.word_count();
+        let mut dict = Dictionary::from_words_with(std::iter::empty::<&str>(), filter);
+        dict.lexicon = Lexicon::Fst(fst);
+        dict.sources.push(Source { name, new_words: words, encoding: Encoding::Utf8 });
+        dict.load.read_time = start.elapsed();
+        dict.load.words_inserted = words;
+        dict.non_empty()
+    }
+
+    /// Minimizes the dictionary into an FST, to `save` for `from_fst`.
+    pub fn to_fst(&self) -> Fst {
+        match &self.lexicon {
+            Lexicon::Trie(trie) => Fst::build(trie.root(), self.filter),
+            Lexicon::Dawg(dawg) => Fst::build(dawg.root(), self.filter),
+            Lexicon::Sorted(words) => Fst::build(words.root(), self.filter),
+            Lexicon::Fst(fst) => Fst::build(fst.root(), self.filter),
+        }
+    }
+
+    /// Loads a word list, one word per line, from any buffered reader; `name` is how the
+    /// source is reported in errors and `sources()`.
+    pub fn from_reader<R: BufRead>(reader: R, name: &str, filter: LengthFilter) -> Result<Self, BoggleError> {
+        Dictionary::from_reader_with(reader, name, filter, &LoadOptions::default())
+    }
+
+    /// Same as `from_reader`, with the options of `from_paths_with`.
+    pub fn from_reader_with<R: BufRead>(
+        reader: R,
+        name: &str,
+        filter: LengthFilter,
+        opts: &LoadOptions,
+    ) -> Result<Self, BoggleError> {
+        let mut dict = Dictionary::from_words_with(std::iter::empty::<&str>(), filter);
+        dict.pool = opts.pool.clone();
+        dict.max_words = opts.max_words;
+        dict.threads = opts.threads;
+        dict.locale = opts.locale;
+        dict.encoding = opts.encoding;
+        dict.add_source(reader, name.to_string())?;
+        dict.non_empty()
+    }
+
+    /// The word list compiled into the binary.
+    #[cfg(feature = "embedded-dict")]
+    pub fn embedded(filter: LengthFilter) -> Result<Self, BoggleError> {
+        Dictionary::from_reader(EMBEDDED_WORDS.as_bytes(), "<embedded>", filter)
+    }
+
+    fn add_source<R: BufRead>(&mut self, reader: R, name: String) -> Result<(), BoggleError> {
+        let start = Instant::now();
+        let (words, encoding) = read_words(reader, &name, self.load.words_read, self.max_words, self.encoding)?;
+        self.load.read_time += start.elapsed();
+        let new_words =
+            if self.threads > 1 { self.insert_words_parallel(&words) } else { self.insert_words(words) };
+        self.sources.push(Source { name, new_words, encoding });
+        Ok(())
+    }
+
+    // A board that spells nothing still has a usable dictionary, so pruned words count
+    fn non_empty(self) -> Result<Self, BoggleError> {
+        if self.load.words_pruned == 0 && self.sources.iter().all(|source| source.new_words == 0) {
+            return Err(BoggleError::EmptyDictionary);
+        }
+        Ok(self)
+    }
+
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Dictionary::from_words_with(words, LengthFilter::default())
+    }
+
+    pub fn from_words_with<I, S>(words: I, filter: LengthFilter) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut dict = Dictionary {
+            lexicon: Lexicon::Trie(ArenaTrie::new()),
+            filter,
+            sources: Vec::new(),
+            load: LoadStats::default(),
+            pool: None,
+            max_words: None,
+            threads: 1,
+            locale: Locale::Default,
+            encoding: None,
+            nodes: OnceLock::new(),
+            longest: OnceLock::new(),
+            words: OnceLock::new(),
+            stats: OnceLock::new(),
+        };
+        dict.insert_words(words);
+        dict
+    }
+
+    // Inserts the words passing the filter (see `sift`), returning how many were new
+    fn insert_words<I, S>(&mut self, words: I) -> usize
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let Lexicon::Trie(trie) = &mut self.lexicon else {
+            panic!("words can only be added before the dictionary is minimized");
+        };
+        let start = Instant::now();
+        let mut stats = LoadStats::default();
+        for word in words {
+            let line = sift(word.as_ref(), self.filter, self.pool.as_ref(), self.locale);
+            stats.count(&line);
+            if let Line::Word(word) = line
+                && trie.insert(&word)
+            {
+                stats.words_inserted += 1;
+            }
+        }
+        self.load.build_time += start.elapsed();
+        self.load.build_threads = self.load.build_threads.max(1);
+        self.inserted(&stats)
+    }
+
+    // Same as `insert_words`, on `self.threads` threads. The lines are sifted in even
+    // chunks, then the words are grouped by first letter and each group built into a
+    // trie of its own, so no two threads ever touch the same nodes; the subtries are
+    // grafted under the root at the end
+    fn insert_words_parallel(&mut self, words: &[String]) -> usize {
+        let Lexicon::Trie(trie) = &mut self.lexicon else {
+            panic!("words can only be added before the dictionary is minimized");
+        };
+        let start = Instant::now();
+        let threads = self.threads;
+        let (filter, pool, locale) = (self.filter, self.pool.as_ref(), self.locale);
+        let sifted: Vec<(LoadStats, Vec<String>)> = thread::scope(|scope| {
+            let workers: Vec<_> = words
+                .chunks(words.len().div_ceil(threads).max(1))
+                .map(|lines| {
+                    scope.spawn(move || {
+                        let mut stats = LoadStats::default();
+                        let mut kept = Vec::new();
+                        for line in lines {
+                            let line = sift(line, filter, pool, locale);
+                            stats.count(&line);
+                            if let Line::Word(word) = line {
+                                kept.push(word);
+                            }
+                        }
+                        (stats, kept)
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join().expect("a loading thread panicked")).collect()
+        });
+
+        let mut stats = LoadStats::default();
+        let mut groups: BTreeMap<char, Vec<String>> = BTreeMap::new();
+        for (partial, kept) in sifted {
+            stats.add(&partial);
+            for word in kept {
+                groups.entry(word.chars().next().unwrap_or_default()).or_default().push(word);
+            }
+        }
+        let groups: Vec<Vec<String>> = groups.into_values().collect();
+
+        // Thread i builds groups i, i + threads, ...; a word is new if neither its own
+        // subtrie nor the words already loaded have it
+        let existing = &*trie;
+        let built: Vec<(ArenaTrie, usize)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.min(groups.len()))
+                .map(|i| {
+                    let groups = &groups;
+                    scope.spawn(move || {
+                        groups
+                            .iter()
+                            .skip(i)
+                            .step_by(threads)
+                            .map(|group| {
+                                let mut subtrie = ArenaTrie::new();
+                                let new = group.iter().filter(|w| subtrie.insert(w) && !existing.contains(w)).count();
+                                (subtrie, new)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().expect("a loading thread panicked")).collect()
+        });
+        for (subtrie, new) in &built {
+            trie.graft(subtrie);
+            stats.words_inserted += new;
+        }
+
+        self.load.build_time += start.elapsed();
+        self.load.build_threads = self.load.build_threads.max(threads);
+        self.inserted(&stats)
+    }
+
+    // Adds a batch's counts to the load stats, returning how many words were new
+    fn inserted(&mut self, stats: &LoadStats) -> usize {
+        self.load.add(stats);
+        self.nodes = OnceLock::new();
+        self.stats = OnceLock::new();
+        self.longest = OnceLock::new();
+        self.words = OnceLock::new();
+        stats.words_inserted
+    }
+
+    /// Takes `words` (any case) out of the dictionary, wherever they came from, so they
+    /// are never found. Returns how many were present. Like adding words, this must
+    /// happen before `minimize`.
+    pub fn exclude<I, S>(&mut self, words: I) -> usize
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let Lexicon::Trie(trie) = &mut self.lexicon else {
+            panic!("words can only be excluded before the dictionary is minimized");
+        };
+        let locale = self.locale;
+        let removed = words.into_iter().filter(|word| trie.remove(&locale.upper(word.as_ref().trim()))).count();
+        self.nodes = OnceLock::new();
+        self.stats = OnceLock::new();
+        self.longest = OnceLock::new();
+        self.words = OnceLock::new();
+        removed
+    }
+
+    /// Excludes every word in a word-list file, one per line, which may be gzipped.
+    /// An FST-backed dictionary cannot drop words, so that is an error.
+    pub fn exclude_path<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, BoggleError> {
+        if let Lexicon::Fst(_) = self.lexicon {
+            return Err(BoggleError::InvalidFst(
+                "words cannot be excluded from an FST; rebuild it with build-fst --exclude".to_string(),
+            ));
+        }
+        let name = path.as_ref().display().to_string();
+        let WordFile::Words(words) = open_words(path.as_ref(), &name)? else {
+            return Err(BoggleError::InvalidFst(format!("{}: words to exclude must be a word list, not an FST", name)));
+        };
+        Ok(self.exclude(read_words(words, &name, 0, None, self.encoding)?.0))
+    }
+
+    /// The files merged into this dictionary, in load order.
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+
+    /// How many leading letters of `word` (any case) some dictionary word starts with;
+    /// every letter when `word` is in the dictionary.
+    pub fn longest_prefix(&self, word: &str) -> usize {
+        fn walk<I: WordIndex>(index: &I, word: &str) -> usize {
+            let mut node = index.root();
+            let mut letters = 0;
+            for ch in word.chars() {
+                match node.child(ch) {
+                    Some(next) => node = next,
+                    None => break,
+                }
+                letters += 1;
+            }
+            letters
+        }
+        let word = self.normalize(word);
+        match &self.lexicon {
+            Lexicon::Trie(trie) => walk(trie, &word),
+            Lexicon::Dawg(dawg) => walk(dawg, &word),
+            Lexicon::Sorted(words) => walk(words, &word),
+            Lexicon::Fst(fst) => walk(fst, &word),
+        }
+    }
+
+    /// The words fitting `pattern` (any case), in alphabetical order: `.` or `?` stands
+    /// for any one letter and `*` for any run of letters, none included, so `B.GG.E`
+    /// gives BOGGLE and BUGGLE. Only the wildcards branch; a fixed letter follows one
+    /// link, so the more of them a pattern has, the less of the dictionary is walked.
+    pub fn matches(&self, pattern: &str) -> Vec<String> {
+        fn walk<N: WordNode>(node: N, pattern: &[char], word: &mut String, found: &mut Vec<String>) {
+            let Some((&first, rest)) = pattern.split_first() else {
+                if node.is_word() {
+                    found.push(word.clone());
+                }
+                return;
+            };
+            let len = word.len();
+            let after = match first {
+                // A star takes no letter, or takes one and stays to take more
+                '*' => {
+                    walk(node, rest, word, found);
+                    pattern
+                }
+                '.' | '?' => rest,
+                ch => {
+                    if let Some(next) = node.child(ch) {
+                        word.push(ch);
+                        walk(next, rest, word, found);
+                        word.truncate(len);
+                    }
+                    return;
+                }
+            };
+            for (ch, next) in node.children() {
+                word.push(ch);
+                walk(next, after, word, found);
+                word.truncate(len);
+            }
+        }
+        let pattern: Vec<char> = self.normalize(pattern).chars().collect();
+        let mut found = Vec::new();
+        match &self.lexicon {
+            Lexicon::Trie(trie) => walk(trie.root(), &pattern, &mut String::new(), &mut found),
+            Lexicon::Dawg(dawg) => walk(dawg.root(), &pattern, &mut String::new(), &mut found),
+            Lexicon::Sorted(words) => walk(words.root(), &pattern, &mut String::new(), &mut found),
+            Lexicon::Fst(fst) => walk(fst.root(), &pattern, &mut String::new(), &mut found),
+        }
+        // Two stars can reach one word by different splits
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// The dictionary words within `max_distance` edits of `word` (any case), each edit
+    /// changing, adding or dropping one letter, closest first and then alphabetically;
+    /// `word` itself is left out. The trie is walked with a row of edit distances per
+    /// node, and a branch is given up once every entry of its row is over the bound.
+    pub fn suggest(&self, word: &str, max_distance: usize) -> Vec<String> {
+        struct Search<'a> {
+            target: &'a [char],
+            max_distance: usize,
+            word: String,
+            found: Vec<(usize, String)>,
+        }
+        fn walk<N: WordNode>(node: N, row: &[usize], search: &mut Search) {
+            for (ch, next) in node.children() {
+                // The distances from the letters so far plus `ch` to each start of the target
+                let mut next_row = Vec::with_capacity(row.len());
+                next_row.push(row[0] + 1);
+                for (j, &target) in search.target.iter().enumerate() {
+                    let changed = row[j] + usize::from(target != ch);
+                    next_row.push(changed.min(row[j + 1] + 1).min(next_row[j] + 1));
+                }
+                if next_row.iter().min().is_some_and(|&best| best > search.max_distance) {
+                    continue;
+                }
+                let len = search.word.len();
+                search.word.push(ch);
+                let distance = next_row[search.target.len()];
+                if next.is_word() && distance <= search.max_distance && distance > 0 {
+                    search.found.push((distance, search.word.clone()));
+                }
+                walk(next, &next_row, search);
+                search.word.truncate(len);
+            }
+        }
+        let target: Vec<char> = self.normalize(word).chars().collect();
+        let row: Vec<usize> = (0..=target.len()).collect();
+        let mut search = Search { target: &target, max_distance, word: String::new(), found: Vec::new() };
+        match &self.lexicon {
+            Lexicon::Trie(trie) => walk(trie.root(), &row, &mut search),
+            Lexicon::Dawg(dawg) => walk(dawg.root(), &row, &mut search),
+            Lexicon::Sorted(words) => walk(words.root(), &row, &mut search),
+            Lexicon::Fst(fst) => walk(fst.root(), &row, &mut search),
+        }
+        search.found.sort_unstable();
+        search.found.into_iter().map(|(_, word)| word).collect()
+    }
+
+    /// Whether `word` (any case) is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        fn walk<I: WordIndex>(index: &I, word: &str) -> bool {
+            let mut node = index.root();
+            for ch in word.chars() {
+                match node.child(ch) {
+                    Some(next) => node = next,
+                    None => return false,
+                }
+            }
+            node.is_word()
+        }
+        let word = self.normalize(word);
+        match &self.lexicon {
+            Lexicon::Trie(trie) => walk(trie, &word),
+            Lexicon::Dawg(dawg) => walk(dawg, &word),
+            Lexicon::Sorted(words) => words.contains(&word),
+            Lexicon::Fst(fst) => walk(fst, &word),
+        }
+    }
+
+    pub fn filter(&self) -> LengthFilter {
+        self.filter
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// `word` trimmed and uppercased the way the dictionary's words were.
+    pub fn normalize(&self, word: &str) -> String {
+        self.locale.upper(word.trim())
+    }
+
+    pub fn load_stats(&self) -> LoadStats {
+        self.load
+    }
+
+    /// Replaces the trie with its minimized DAWG. Results are unchanged; memory use drops.
+    pub fn minimize(&mut self) {
+        if let Lexicon::Trie(trie) = &self.lexicon {
+            let start = Instant::now();
+            self.lexicon = Lexicon::Dawg(trie.minimize());
+            self.load.build_time += start.elapsed();
+            self.nodes = OnceLock::new();
+            self.stats = OnceLock::new();
+        }
+    }
+
+    /// Replaces the trie or DAWG with a sorted list of its words, searched by binary
+    /// search. Results are unchanged; this is mostly a second backend to compare with.
+    pub fn sort_words(&mut self) {
+        if let Lexicon::Sorted(_) = self.lexicon {
+            return;
+        }
+        let start = Instant::now();
+        // The list becomes the dictionary, so it is not kept a second time
+        self.words();
+        self.lexicon = Lexicon::Sorted(SortedWords::new(self.words.take().unwrap_or_default()));
+        self.load.build_time += start.elapsed();
+        self.nodes = OnceLock::new();
+        self.stats = OnceLock::new();
+    }
+
+    /// Every word, in letter order. Walked out of the dictionary structure on first use
+    /// and kept from then on, until words are added or taken out.
+    pub fn words(&self) -> &[String] {
+        fn collect<N: WordNode>(node: N, word: &mut String, words: &mut Vec<String>) {
+            if node.is_word() {
+                words.push(word.clone());
+            }
+            for (ch, child) in node.children() {
+                word.push(ch);
+                collect(child, word, words);
+                word.pop();
+            }
+        }
+        self.words.get_or_init(|| {
+            let mut words = Vec::new();
+            match &self.lexicon {
+                Lexicon::Trie(trie) => collect(trie.root(), &mut String::new(), &mut words),
+                Lexicon::Dawg(dawg) => collect(dawg.root(), &mut String::new(), &mut words),
+                Lexicon::Sorted(sorted) => collect(sorted.root(), &mut String::new(), &mut words),
+                Lexicon::Fst(fst) => collect(fst.root(), &mut String::new(), &mut words),
+            }
+            words
+        })
+    }
+
+    /// Number of nodes in the structure currently used for the search. A sorted list has
+    /// no nodes of its own, so each word counts as one.
+    pub fn node_count(&self) -> usize {
+        *self.nodes.get_or_init(|| match &self.lexicon {
+            Lexicon::Trie(trie) => trie.node_count(),
+            Lexicon::Dawg(dawg) => dawg.node_count(),
+            Lexicon::Sorted(words) => words.len(),
+            Lexicon::Fst(fst) => fst.node_count(),
+        })
+    }
+
+    /// Node, word and edge counts, the longest word and a memory estimate, worked out in
+    /// one walk over every word on first use.
+    pub fn stats(&self) -> TrieStats {
+        fn walk<N: WordNode>(node: N, depth: usize, stats: &mut TrieStats) {
+            stats.words += usize::from(node.is_word());
+            stats.max_depth = stats.max_depth.max(depth);
+            let children = node.children();
+            if !children.is_empty() {
+                stats.branch_nodes += 1;
+                stats.edges += children.len();
+            }
+            for (_, child) in children {
+                walk(child, depth + 1, stats);
+            }
+        }
+        *self.stats.get_or_init(|| {
+            let mut stats = TrieStats { nodes: self.node_count(), ..TrieStats::default() };
+            match &self.lexicon {
+                Lexicon::Trie(trie) => {
+                    walk(trie.root(), 0, &mut stats);
+                    stats.heap_bytes = trie.heap_bytes();
+                }
+                Lexicon::Dawg(dawg) => {
+                    walk(dawg.root(), 0, &mut stats);
+                    stats.heap_bytes = dawg.heap_bytes();
+                }
+                Lexicon::Sorted(words) => {
+                    walk(words.root(), 0, &mut stats);
+                    stats.heap_bytes = words.heap_bytes();
+                }
+                Lexicon::Fst(fst) => {
+                    walk(fst.root(), 0, &mut stats);
+                    stats.heap_bytes = fst.byte_len();
+                }
+            }
+            stats
+        })
+    }
+
+    /// Letters in the longest word, or 0 for an empty dictionary. Worked out on first use,
+    /// by walking every path through the trie.
+    pub fn longest_word(&self) -> usize {
+        fn depth<N: WordNode>(node: N) -> usize {
+            node.children().into_iter().map(|(_, child)| depth(child) + 1).max().unwrap_or(0)
+        }
+        *self.longest.get_or_init(|| match &self.lexicon {
+            Lexicon::Trie(trie) => depth(trie.root()),
+            Lexicon::Dawg(dawg) => depth(dawg.root()),
+            Lexicon::Sorted(words) => depth(words.root()),
+            Lexicon::Fst(fst) => depth(fst.root()),
+        })
+    }
+
+    /// Writes the dictionary to a compact binary file. The minimized automaton is what
+    /// gets stored, so a loaded dictionary is always DAWG-backed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(CACHE_MAGIC)?;
+        out.write_all(&[CACHE_VERSION])?;
+        out.write_all(&(self.filter.min_len as u32).to_le_bytes())?;
+        out.write_all(&(self.filter.max_len.unwrap_or(0) as u32).to_le_bytes())?;
+        out.write_all(&(self.sources.len() as u32).to_le_bytes())?;
+        for source in &self.sources {
+            out.write_all(&(source.name.len() as u32).to_le_bytes())?;
+            out.write_all(source.name.as_bytes())?;
+            out.write_all(&(source.new_words as u32).to_le_bytes())?;
+        }
+        match &self.lexicon {
+            Lexicon::Trie(trie) => trie.minimize().write_to(&mut out)?,
+            Lexicon::Dawg(dawg) => dawg.write_to(&mut out)?,
+            Lexicon::Sorted(words) => Dawg::from_trie(words.root()).write_to(&mut out)?,
+            Lexicon::Fst(fst) => Dawg::from_trie(fst.root()).write_to(&mut out)?,
+        }
+        out.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let start = Instant::now();
+        let mut input = BufReader::new(File::open(path).map_err(BoggleError::DictionaryIo)?);
+        let invalid = |msg: &str| BoggleError::CorruptCache(msg.to_string());
+
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic).map_err(BoggleError::DictionaryIo)?;
+        if &magic != CACHE_MAGIC {
+            return Err(invalid("not a dictionary cache file"));
+        }
+        let mut version = [0u8];
+        input.read_exact(&mut version).map_err(BoggleError::DictionaryIo)?;
+        if version[0] != CACHE_VERSION {
+            return Err(invalid("unsupported dictionary cache version"));
+        }
+        let min_len = read_u32(&mut input)?;
+        let max_len = Some(read_u32(&mut input)?).filter(|&max| max > 0);
+        let mut sources = Vec::new();
+        for _ in 0..read_u32(&mut input)? {
+            let mut name = vec![0u8; read_u32(&mut input)?];
+            input.read_exact(&mut name).map_err(BoggleError::DictionaryIo)?;
+            let name = String::from_utf8(name).map_err(|_| invalid("bad source name"))?;
+            // The cache keeps the words, already decoded
+            sources.push(Source { name, new_words: read_u32(&mut input)?, encoding: Encoding::Utf8 });
+        }
+
+        let dawg = Dawg::read_from(&mut input)?;
+        let load = LoadStats {
+            read_time: start.elapsed(),
+            words_inserted: sources.iter().map(|s| s.new_words).sum(),
+            ..LoadStats::default()
+        };
+        Ok(Dictionary {
+            lexicon: Lexicon::Dawg(dawg),
+            filter: LengthFilter { min_len, max_len },
+            sources,
+            load,
+            pool: None,
+            max_words: None,
+            threads: 1,
+            locale: Locale::Default,
+            encoding: None,
+            nodes: OnceLock::new(),
+            longest: OnceLock::new(),
+            words: OnceLock::new(),
+            stats: OnceLock::new(),
+        })
+    }
+
+    /// Loads `cache` if it is at least as new as every source and was built from the same
+    /// sources with the same length filter; otherwise rebuilds and rewrites the cache.
+    /// Failing to write the cache is not an error, since the dictionary itself loaded fine.
+    pub fn from_paths_cached<P: AsRef<Path>, Q: AsRef<Path>>(
+        paths: &[P],
+        cache: Q,
+        filter: LengthFilter,
+    ) -> Result<Self, BoggleError> {
+        let cache = cache.as_ref();
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        let fresh = modified(cache).is_some_and(|cache_time| {
+            paths.iter().all(|path| modified(path.as_ref()).is_some_and(|t| t <= cache_time))
+        });
+        if fresh
+            && let Ok(dict) = Dictionary::load(cache)
+            && dict.filter == filter
+            && dict.sources.iter().map(|s| s.name.clone()).eq(paths.iter().map(|p| p.as_ref().display().to_string()))
+        {
+            return Ok(dict);
+        }
+
+        let dict = Dictionary::from_paths(paths, filter)?;
+        let _ = dict.save(cache);
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_dictionary_solves_many_boards() {
+        let path = std::env::temp_dir().join(format!("boggle_dict_many_{}.txt", std::process::id()));
+        fs::write(&path, "cat\ncater\nace\nquest\nsquat\nrote\ntoga\ndog\n").unwrap();
+        let dict = Dictionary::from_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let boards = [["cat", "qer", "sto"], ["tog", "xax", "xxx"], ["dog", "xxx", "xxx"]];
+        let expected: [&[&str]; 3] = [&["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT"], &["TOGA"], &["DOG"]];
+        let found = |dict: &Dictionary, rows: &[&str; 3]| -> Vec<String> {
+            let mut words: Vec<String> =
+                dict.solver(Board::from_rows(rows)).unwrap().solve().words.into_iter().map(|f| f.word).collect();
+            words.sort();
+            words
+        };
+        for (rows, expected) in boards.iter().zip(expected) {
+            assert_eq!(found(&dict, rows), expected, "{:?}", rows);
+        }
+        // The solvers only borrow the trie, so they can share it across threads too
+        std::thread::scope(|s| {
+            let solves: Vec<_> = boards.iter().map(|rows| s.spawn(|| found(&dict, rows))).collect();
+            for (solve, expected) in solves.into_iter().zip(expected) {
+                assert_eq!(solve.join().unwrap(), expected);
+            }
+        });
+    }
+
+    #[test]
+    fn matches_patterns_with_wildcards_anywhere() {
+        let dict = Dictionary::from_words(["boggle", "buggle", "goggle", "toggle", "bog", "bogs", "boggles", "bagel"]);
+        let matches = |pattern| dict.matches(pattern);
+        assert_eq!(matches("B.GG.E"), ["BOGGLE", "BUGGLE"]);
+        // Leading
+        assert_eq!(matches(".OGGLE"), ["BOGGLE", "GOGGLE", "TOGGLE"]);
+        assert_eq!(matches("?og"), ["BOG"]);
+        assert_eq!(matches("*GLE"), ["BOGGLE", "BUGGLE", "GOGGLE", "TOGGLE"]);
+        // Trailing
+        assert_eq!(matches("BOG."), ["BOGS"]);
+        assert_eq!(matches("boggle?"), ["BOGGLES"]);
+        assert_eq!(matches("BOG*"), ["BOG", "BOGGLE", "BOGGLES", "BOGS"]);
+        // Nothing fits
+        assert!(matches("Z.....").is_empty());
+        assert!(matches("B.GG").is_empty());
+        assert!(matches("......?.").is_empty());
+    }
+
+    #[test]
+    fn suggests_words_one_edit_away() {
+        let dict = Dictionary::from_words(["car", "card", "care", "cart", "cat", "scar", "dog"]);
+        // A changed letter, a missing one and an extra one
+        assert_eq!(dict.suggest("cord", 1), ["CARD"]);
+        assert_eq!(dict.suggest("crd", 1), ["CARD"]);
+        assert_eq!(dict.suggest("caxrd", 1), ["CARD"]);
+        // All three at once, leaving out CAR itself
+        assert_eq!(dict.suggest("car", 1), ["CARD", "CARE", "CART", "CAT", "SCAR"]);
+    }
+
+    #[test]
+    fn suggestions_stay_within_the_distance() {
+        let dict = Dictionary::from_words(["car", "card", "care", "cart", "cat", "scar", "dog"]);
+        assert!(dict.suggest("car", 0).is_empty());
+        assert!(dict.suggest("cord", 0).is_empty());
+        // CARD first as the closest; CAT, SCAR and DOG are three edits from CORD
+        assert_eq!(dict.suggest("cord", 2), ["CARD", "CAR", "CARE", "CART"]);
+    }
+
+    #[test]
+    fn stats_count_a_small_trie_exactly() {
+        // Root, C, A, T, R, D: CAT and CAR share C-A, and CARD runs on from CAR
+        let mut dict = Dictionary::from_words(["cat", "car", "card"]);
+        let shape = TrieStats { words: 3, max_depth: 4, branch_nodes: 4, edges: 5, ..TrieStats::default() };
+        let stats = dict.stats();
+        assert_eq!(TrieStats { nodes: 6, heap_bytes: 0, ..shape }, TrieStats { heap_bytes: 0, ..stats });
+        assert_eq!(dict.node_count(), 6);
+        assert!(stats.heap_bytes > 0);
+        assert_eq!(stats.branching(), 1.25);
+
+        // The DAWG stores the T and D leaves as one node, and still reports the trie's shape
+        dict.minimize();
+        let stats = dict.stats();
+        assert_eq!(TrieStats { nodes: 5, heap_bytes: 0, ..shape }, TrieStats { heap_bytes: 0, ..stats });
+        assert_eq!(dict.node_count(), 5);
+        assert!(stats.heap_bytes > 0);
+    }
+
+    #[test]
+    fn accented_words_reach_the_trie_in_every_encoding() {
+        let utf16 = |big: bool| -> Vec<u8> {
+            let units = "\u{feff}café\nnaïve\nstraße\n".encode_utf16();
+            units.flat_map(|unit| if big { unit.to_be_bytes() } else { unit.to_le_bytes() }).collect()
+        };
+        let files: [(&[u8], Encoding); 5] = [
+            ("café\nnaïve\nstraße\n".as_bytes(), Encoding::Utf8),
+            (b"caf\xe9\nna\xefve\nstra\xdfe\n", Encoding::Latin1),
+            (&utf16(false), Encoding::Utf16Le),
+            (&utf16(true), Encoding::Utf16Be),
+            // UTF-8 but for one Latin-1 line
+            (b"caf\xc3\xa9\nna\xefve\nstra\xc3\x9fe\n", Encoding::Latin1),
+        ];
+        for (bytes, encoding) in files {
+            let dict = Dictionary::from_reader(bytes, "words", LengthFilter::default()).unwrap();
+            assert_eq!(dict.words(), ["CAFÉ", "NAÏVE", "STRASSE"], "{}", encoding);
+            assert!(dict.contains("café") && dict.contains("Straße"));
+            assert_eq!(dict.sources()[0].encoding, encoding);
+        }
+
+        // Forcing UTF-8 fails on the Latin-1 bytes rather than guessing
+        let opts = LoadOptions { encoding: Some(Encoding::Utf8), ..LoadOptions::default() };
+        let latin1: &[u8] = b"caf\xe9\n";
+        let err = Dictionary::from_reader_with(latin1, "words", LengthFilter::default(), &opts).err().unwrap();
+        assert_eq!(err.to_string(), "cannot read dictionary: words: line 1 is not valid UTF-8");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::board::Board;
+
+// Bold black on yellow, for the cells of a highlighted path
+const HIGHLIGHT: &str = "\x1b[1;30;43m";
+const RESET: &str = "\x1b[0m";
+
+/// The board as rows of tiles, printed the way the cubes show them ("Qu", blocks as
+/// `#`), with each cell of `path` numbered by its step from 1. With `color` those cells
+/// are highlighted and the number follows the tile ("S1"); without it the number is in
+/// brackets ("S[1]"). A cell the path visits twice lists both steps ("E[2,5]"). Columns
+/// line up, and a blank line separates the layers of a 3D board.
+pub fn render_path(board: &Board, path: &[(usize, usize)], color: bool) -> String {
+    let labels: Vec<Vec<(String, bool)>> = (0..board.rows())
+        .map(|r| (0..board.cols()).map(|c| label(board, path, r, c, color)).collect())
+        .collect();
+    let width = labels.iter().flatten().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for (r, row) in labels.iter().enumerate() {
+        if r > 0 && r % board.layer_rows() == 0 {
+            out.push('\n');
+        }
+        let cells: Vec<String> = row
+            .iter()
+            .map(|(label, marked)| {
+                let pad = " ".repeat(width - label.chars().count());
+                if *marked && color { format!("{}{}{}{}", HIGHLIGHT, label, RESET, pad) } else { label.clone() + &pad }
+            })
+            .collect();
+        out.push_str(cells.join(" ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+// The text shown for a cell, and whether the path uses it
+fn label(board: &Board, path: &[(usize, usize)], r: usize, c: usize, color: bool) -> (String, bool) {
+    let mut chars = board.tile(r, c).chars();
+    let tile = chars.next().map_or("#".to_string(), |first| first.to_string() + &chars.as_str().to_lowercase());
+    let steps: Vec<String> =
+        path.iter().enumerate().filter(|&(_, &cell)| cell == (r, c)).map(|(i, _)| (i + 1).to_string()).collect();
+    match (steps.is_empty(), color) {
+        (true, _) => (tile, false),
+        (false, true) => (format!("{}{}", tile, steps.join(",")), true),
+        (false, false) => (format!("{}[{}]", tile, steps.join(",")), true),
+    }
+}
+
+/// `word` with only its first `revealed` letters showing and the rest as `_`, spaced
+/// out so the length can be counted: "S E _ _ _ _ _". Revealing every letter shows the
+/// word spaced out the same way.
+pub fn mask_word(word: &str, revealed: usize) -> String {
+    let letters: Vec<String> =
+        word.chars().enumerate().map(|(i, ch)| if i < revealed { ch.to_string() } else { "_".to_string() }).collect();
+    letters.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::ParseOptions;
+    use crate::solver::BoggleSolver;
+
+    fn board(rows: &[&str]) -> Board {
+        Board::parse_rows(rows, &ParseOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn numbers_the_path_of_a_checked_word() {
+        let solver = BoggleSolver::from_words(board(&["cat", "qer", "sto"]), ["quest", "cater"]).unwrap();
+        let path = solver.check_word("quest").unwrap();
+        assert_eq!(render_path(solver.board(), &path, false), "C     A     T\nQu[1] E[2]  R\nS[3]  T[4]  O\n");
+        let lit = |label: &str| format!("{}{}{}", HIGHLIGHT, label, RESET);
+        let colored = format!("C   A   T\n{} {}  R\n{}  {}  O\n", lit("Qu1"), lit("E2"), lit("S3"), lit("T4"));
+        assert_eq!(render_path(solver.board(), &path, true), colored);
+        let path = solver.check_word("cater").unwrap();
+        assert_eq!(render_path(solver.board(), &path, false), "C[1] A[2] T[3]\nQu   E[4] R[5]\nS    T    O\n");
+        assert_eq!(solver.check_word("rote"), None);
+    }
+
+    #[test]
+    fn lists_every_step_of_a_reused_cell() {
+        let path = [(0, 0), (0, 1), (1, 1), (0, 1)];
+        assert_eq!(render_path(&board(&["ab#", "cd#"]), &path, false), "A[1]   B[2,4] #\nC      D[3]   #\n");
+        assert_eq!(render_path(&board(&["ab", "cd"]), &[], false), "A B\nC D\n");
+    }
+
+    #[test]
+    fn masks_the_unrevealed_letters() {
+        assert_eq!(mask_word("SERPENT", 2), "S E _ _ _ _ _");
+        assert_eq!(mask_word("SERPENT", 0), "_ _ _ _ _ _ _");
+        assert_eq!(mask_word("CAT", 9), "C A T");
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+/// How a word list's bytes are read as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Utf8,
+    /// ISO 8859-1: each byte is the character of the same number, so any bytes decode.
+    Latin1,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    /// `utf8`, `latin1`, or `utf16`, which is little-endian unless the file's byte order
+    /// mark says otherwise; `utf16le` and `utf16be` name the order outright.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "utf8" => Ok(Encoding::Utf8),
+            "latin1" | "iso88591" => Ok(Encoding::Latin1),
+            "utf16" | "utf16le" => Ok(Encoding::Utf16Le),
+            "utf16be" => Ok(Encoding::Utf16Be),
+            _ => Err(format!("unknown encoding '{}' (choose utf8, latin1 or utf16)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "Latin-1",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+        })
+    }
+}
+
+/// The lines of a word list, with the line endings taken off (`\n` or `\r\n`, as
+/// `BufRead::lines` takes them), and the encoding they turned out to be in.
+pub(crate) struct Lines<R> {
+    reader: R,
+    encoding: Encoding,
+    // Whether a line that is not UTF-8 is read as Latin-1 rather than failing, and
+    // whether one was
+    fallback: bool,
+    fell_back: bool,
+    // UTF-16 is decoded whole, as its lines cannot be split at a newline byte
+    decoded: Option<std::vec::IntoIter<String>>,
+    line: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// The lines of `reader` in `encoding`. With `None`, a byte order mark picks the
+    /// encoding, and without one the text is UTF-8 but for any line that is not, which
+    /// is read as Latin-1. A byte order mark is dropped rather than read as part of the
+    /// first word, unless the text is Latin-1, where its bytes are letters. `utf16`
+    /// given outright still takes its byte order from the mark.
+    pub(crate) fn new(mut reader: R, encoding: Option<Encoding>) -> io::Result<Self> {
+        let bom = match reader.fill_buf()? {
+            [0xef, 0xbb, 0xbf, ..] => Some((Encoding::Utf8, 3)),
+            [0xff, 0xfe, ..] => Some((Encoding::Utf16Le, 2)),
+            [0xfe, 0xff, ..] => Some((Encoding::Utf16Be, 2)),
+            _ => None,
+        };
+        let utf16 = |encoding| matches!(encoding, Encoding::Utf16Le | Encoding::Utf16Be);
+        let marked = match (encoding, bom) {
+            (None, Some((found, len))) => Some((found, len)),
+            (Some(wanted), Some((found, len))) if wanted == found || utf16(wanted) && utf16(found) => {
+                Some((found, len))
+            }
+            _ => None,
+        };
+        if let Some((_, len)) = marked {
+            reader.consume(len);
+        }
+        let read_as = marked.map_or(encoding.unwrap_or(Encoding::Utf8), |(found, _)| found);
+        Ok(Lines {
+            reader,
+            encoding: read_as,
+            fallback: encoding.is_none() && read_as == Encoding::Utf8,
+            fell_back: false,
+            decoded: None,
+            line: 0,
+        })
+    }
+
+    /// The encoding the lines so far were read in: Latin-1 once any line was not UTF-8.
+    pub(crate) fn encoding(&self) -> Encoding {
+        if self.fell_back { Encoding::Latin1 } else { self.encoding }
+    }
+
+    fn utf16(&mut self) -> io::Result<Vec<String>> {
+        let mut bytes = Vec::new();
+        self.reader.read_to_end(&mut bytes)?;
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        if bytes.len() % 2 == 1 {
+            return Err(invalid("odd number of bytes for UTF-16"));
+        }
+        let big = self.encoding == Encoding::Utf16Be;
+        let units = bytes.chunks_exact(2).map(|pair| {
+            let pair = [pair[0], pair[1]];
+            if big { u16::from_be_bytes(pair) } else { u16::from_le_bytes(pair) }
+        });
+        let text: String =
+            char::decode_utf16(units).collect::<Result<_, _>>().map_err(|_| invalid("invalid UTF-16"))?;
+        Ok(text.lines().map(str::to_string).collect())
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        if matches!(self.encoding, Encoding::Utf16Le | Encoding::Utf16Be) {
+            if self.decoded.is_none() {
+                match self.utf16() {
+                    Ok(lines) => self.decoded = Some(lines.into_iter()),
+                    Err(e) => {
+                        self.decoded = Some(Vec::new().into_iter());
+                        return Some(Err(e));
+                    }
+                }
+            }
+            return self.decoded.as_mut()?.next().map(Ok);
+        }
+
+        let mut bytes = Vec::new();
+        match self.reader.read_until(b'\n', &mut bytes) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        self.line += 1;
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+        let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| b as char).collect();
+        if self.encoding == Encoding::Latin1 {
+            return Some(Ok(latin1(&bytes)));
+        }
+        Some(match String::from_utf8(bytes) {
+            Ok(line) => Ok(line),
+            Err(e) if self.fallback => {
+                self.fell_back = true;
+                Ok(latin1(e.as_bytes()))
+            }
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {} is not valid UTF-8", self.line),
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big: bool) -> Vec<u8> {
+        let units = text.encode_utf16();
+        units.flat_map(|unit| if big { unit.to_be_bytes() } else { unit.to_le_bytes() }).collect()
+    }
+
+    fn read(bytes: &[u8], encoding: Option<Encoding>) -> io::Result<(Vec<String>, Encoding)> {
+        let mut lines = Lines::new(bytes, encoding)?;
+        let words = lines.by_ref().collect::<io::Result<_>>()?;
+        Ok((words, lines.encoding()))
+    }
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_utf8_with_or_without_a_mark() {
+        let expected = (words(&["café", "straße"]), Encoding::Utf8);
+        assert_eq!(read("café\r\nstraße\n".as_bytes(), None).unwrap(), expected);
+        assert_eq!(read("\u{feff}café\nstraße".as_bytes(), None).unwrap(), expected);
+        assert_eq!(read("\u{feff}café\nstraße".as_bytes(), Some(Encoding::Utf8)).unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_latin1() {
+        let bytes = b"caf\xe9\nstra\xdfe\nna\xefve\n";
+        let expected = (words(&["café", "straße", "naïve"]), Encoding::Latin1);
+        assert_eq!(read(bytes, Some(Encoding::Latin1)).unwrap(), expected);
+        assert_eq!(read(bytes, None).unwrap(), expected);
+        // Forced, the bytes of a UTF-8 mark are letters like any others
+        assert_eq!(read(b"\xef\xbb\xbfab", Some(Encoding::Latin1)).unwrap().0, words(&["ï»¿ab"]));
+    }
+
+    #[test]
+    fn reads_utf16_by_its_byte_order_mark() {
+        let text = "\u{feff}café\r\nstraße\nΩmega\n";
+        for (big, encoding) in [(false, Encoding::Utf16Le), (true, Encoding::Utf16Be)] {
+            let expected = (words(&["café", "straße", "Ωmega"]), encoding);
+            assert_eq!(read(&utf16(text, big), None).unwrap(), expected);
+            // `utf16` takes the order from the mark, whichever it names
+            assert_eq!(read(&utf16(text, big), Some(Encoding::Utf16Le)).unwrap(), expected);
+        }
+        // Without a mark, utf16 means little-endian
+        assert_eq!(read(&utf16("ab\ncd", false), Some(Encoding::Utf16Le)).unwrap().0, words(&["ab", "cd"]));
+        assert_eq!(read(&utf16("ab\ncd", true), Some(Encoding::Utf16Be)).unwrap().0, words(&["ab", "cd"]));
+
+        let odd = read(b"\xff\xfea\x00b", None).unwrap_err();
+        assert_eq!(odd.to_string(), "odd number of bytes for UTF-16");
+        let unpaired = read(b"\xff\xfe\x00\xd8a\x00", None).unwrap_err();
+        assert_eq!(unpaired.to_string(), "invalid UTF-16");
+    }
+
+    #[test]
+    fn a_mixed_file_falls_back_to_latin1_line_by_line() {
+        let bytes = b"caf\xc3\xa9\nna\xefve\nstra\xc3\x9fe\n";
+        let (lines, encoding) = read(bytes, None).unwrap();
+        // The UTF-8 lines stay UTF-8; only the line that is not is read as Latin-1
+        assert_eq!(lines, words(&["café", "naïve", "straße"]));
+        assert_eq!(encoding, Encoding::Latin1);
+
+        let strict = read(bytes, Some(Encoding::Utf8)).unwrap_err();
+        assert_eq!(strict.to_string(), "line 2 is not valid UTF-8");
+    }
+
+    #[test]
+    fn names_parse_and_print() {
+        let names = [
+            ("utf8", Encoding::Utf8),
+            ("UTF-8", Encoding::Utf8),
+            ("latin1", Encoding::Latin1),
+            ("ISO-8859-1", Encoding::Latin1),
+            ("utf16", Encoding::Utf16Le),
+            ("utf-16le", Encoding::Utf16Le),
+            ("UTF16BE", Encoding::Utf16Be),
+        ];
+        for (name, encoding) in names {
+            assert_eq!(name.parse(), Ok(encoding), "{}", name);
+        }
+        let unknown = "ebcdic".parse::<Encoding>().unwrap_err();
+        assert_eq!(unknown, "unknown encoding 'ebcdic' (choose utf8, latin1 or utf16)");
+        assert_eq!(Encoding::Utf16Be.to_string(), "UTF-16BE");
+    }
+}
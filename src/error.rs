@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong building a board, a dictionary or a solver.
+/// Rows and columns are 1-based, as the user typed them.
+#[derive(Debug)]
+pub enum BoggleError {
+    EmptyBoard,
+    RaggedBoard { row: usize, expected: usize, found: usize },
+    InvalidCharacter { row: usize, col: usize, ch: char },
+    /// A `(` without its `)`, or the other way round.
+    UnbalancedGroup { row: usize, col: usize },
+    WildcardInGroup { row: usize, col: usize },
+    /// A one-line board whose tile count is not a perfect square.
+    NotSquare { tiles: usize },
+    /// A one-line board whose tile count is not a whole number of rows `cols` wide.
+    NotRows { tiles: usize, cols: usize },
+    /// A row whose tile count differs from the number of columns asked for.
+    ColumnCount { row: usize, expected: usize, found: usize },
+    /// A hex board with an even number of rows, which cannot be a hexagon.
+    HexRowCount { rows: usize },
+    /// A hex board row whose length does not fit a hexagon with that many rows.
+    HexRowLength { row: usize, expected: usize, found: usize },
+    /// A one-line hex board whose tile count no hexagon has.
+    NotHexagon { tiles: usize },
+    /// A layer of a 3D board whose rows and columns differ from the first layer's.
+    RaggedLayers { layer: usize, expected: (usize, usize), found: (usize, usize) },
+    /// A bonus layout whose rows and columns differ from the board's.
+    BonusShape { expected: (usize, usize), found: (usize, usize) },
+    /// A bonus layout file with an unknown symbol or a short row.
+    InvalidBonus(String),
+    BonusIo(io::Error),
+    InvalidLengthFilter { min_len: usize, max_len: usize },
+    /// A cell asked for by position that the board does not have; counted from 0.
+    CellOffBoard { row: usize, col: usize, rows: usize, cols: usize },
+    BoardIo(io::Error),
+    DictionaryIo(io::Error),
+    /// No word in the dictionary files passed the length filter.
+    EmptyDictionary,
+    /// The word lists hold more than `limit` words; `source` is the one being read
+    /// when the limit was passed.
+    TooManyWords { limit: usize, source: String },
+    CorruptCache(String),
+    /// A file that is not a usable FST, or that cannot serve the asked-for word lengths.
+    InvalidFst(String),
+    DefinitionsIo(io::Error),
+    FrequenciesIo(io::Error),
+    /// A player's or a judged word list could not be read.
+    WordListIo(io::Error),
+    /// Malformed JSON, or JSON without the fields a board or result needs.
+    InvalidJson(String),
+    ConfigIo(io::Error),
+    /// A config file that is not the TOML `Config` reads, or a preset without a path.
+    InvalidConfig(String),
+}
+
+impl BoggleError {
+    /// Whether the error is about the board's contents rather than reading a file.
+    pub fn is_invalid_board(&self) -> bool {
+        matches!(
+            self,
+            BoggleError::EmptyBoard
+                | BoggleError::RaggedBoard { .. }
+                | BoggleError::InvalidCharacter { .. }
+                | BoggleError::UnbalancedGroup { .. }
+                | BoggleError::WildcardInGroup { .. }
+                | BoggleError::NotSquare { .. }
+                | BoggleError::NotRows { .. }
+                | BoggleError::ColumnCount { .. }
+                | BoggleError::HexRowCount { .. }
+                | BoggleError::HexRowLength { .. }
+                | BoggleError::NotHexagon { .. }
+                | BoggleError::RaggedLayers { .. }
+                | BoggleError::InvalidJson(_)
+        )
+    }
+}
+
+impl fmt::Display for BoggleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoggleError::EmptyBoard => write!(f, "board is empty"),
+            BoggleError::RaggedBoard { row, expected, found } => {
+                write!(f, "row {} has {} tiles, expected {} like the first row", row, found, expected)
+            }
+            BoggleError::InvalidCharacter { row, col, ch } => {
+                write!(f, "row {}, column {}: '{}' is not a letter", row, col, ch)
+            }
+            BoggleError::UnbalancedGroup { row, col } => write!(f, "row {}, column {}: unbalanced parenthesis", row, col),
+            BoggleError::WildcardInGroup { row, col } => {
+                write!(f, "row {}, column {}: a wildcard cannot be part of a tile group", row, col)
+            }
+            BoggleError::NotSquare { tiles } => {
+                write!(f, "{} tiles is not a square board; separate rows with '/'", tiles)
+            }
+            BoggleError::NotRows { tiles, cols } => {
+                write!(f, "{} tiles cannot be cut into rows of {}", tiles, cols)
+            }
+            BoggleError::ColumnCount { row, expected, found } => {
+                write!(f, "row {} has {} tiles, but the board is {} columns wide", row, found, expected)
+            }
+            BoggleError::HexRowCount { rows } => {
+                write!(f, "a hex board has an odd number of rows (3, 5, 7, ...), not {}", rows)
+            }
+            BoggleError::HexRowLength { row, expected, found } => {
+                write!(f, "row {} has {} tiles, but that row of the hexagon needs {}", row, found, expected)
+            }
+            BoggleError::NotHexagon { tiles } => {
+                write!(f, "{} tiles is not a hexagon (7, 19, 37, ...); separate rows with '/'", tiles)
+            }
+            BoggleError::RaggedLayers { layer, expected, found } => write!(
+                f,
+                "layer {} is {} x {}, expected {} x {} like the first layer",
+                layer, found.0, found.1, expected.0, expected.1
+            ),
+            BoggleError::BonusShape { expected, found } => write!(
+                f,
+                "the bonus layout is {} x {}, but the board is {} x {}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            BoggleError::InvalidBonus(msg) => write!(f, "invalid bonus layout: {}", msg),
+            BoggleError::BonusIo(e) => write!(f, "cannot read bonus layout: {}", e),
+            BoggleError::InvalidLengthFilter { min_len, max_len } => {
+                write!(f, "minimum word length {} is greater than maximum {}", min_len, max_len)
+            }
+            BoggleError::CellOffBoard { row, col, rows, cols } => {
+                write!(f, "cell {},{} is off the {} x {} board (rows and columns count from 0)", row, col, rows, cols)
+            }
+            BoggleError::BoardIo(e) => write!(f, "cannot read board: {}", e),
+            BoggleError::DictionaryIo(e) => write!(f, "cannot read dictionary: {}", e),
+            BoggleError::EmptyDictionary => write!(f, "dictionary has no words of an allowed length"),
+            BoggleError::TooManyWords { limit, source } => {
+                write!(f, "dictionary has more than {} words (passed while reading {})", limit, source)
+            }
+            BoggleError::CorruptCache(msg) => write!(f, "corrupt dictionary cache: {}", msg),
+            BoggleError::InvalidFst(msg) => write!(f, "invalid FST: {}", msg),
+            BoggleError::DefinitionsIo(e) => write!(f, "cannot read definitions: {}", e),
+            BoggleError::FrequenciesIo(e) => write!(f, "cannot read word frequencies: {}", e),
+            BoggleError::WordListIo(e) => write!(f, "cannot read word list: {}", e),
+            BoggleError::InvalidJson(msg) => write!(f, "invalid JSON: {}", msg),
+            BoggleError::ConfigIo(e) => write!(f, "cannot read config file: {}", e),
+            BoggleError::InvalidConfig(msg) => write!(f, "invalid config file: {}", msg),
+        }
+    }
+}
+
+impl Error for BoggleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BoggleError::BoardIo(e)
+            | BoggleError::BonusIo(e)
+            | BoggleError::DictionaryIo(e)
+            | BoggleError::DefinitionsIo(e)
+            | BoggleError::FrequenciesIo(e)
+            | BoggleError::WordListIo(e)
+            | BoggleError::ConfigIo(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! A C interface for embedding the solver in other languages; `include/boggle_solver.h`
+//! declares it. Strings cross the boundary as NUL-terminated UTF-8. A function that
+//! fails returns null and leaves a message for `boggle_last_error`; no panic escapes.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::board::{Board, ParseOptions};
+use crate::dictionary::{Dictionary, LengthFilter};
+use crate::score::score;
+use crate::solver::{BoggleSolver, SolveResult};
+
+/// A solve's words, with C copies of each word kept alive for the accessors.
+pub struct FfiResult {
+    result: SolveResult,
+    words: Vec<CString>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: String) {
+    // A message with an interior NUL is cut short rather than lost
+    let message = CString::new(message).unwrap_or_else(|e| {
+        let end = e.nul_position();
+        CString::new(&e.into_vec()[..end]).unwrap_or_default()
+    });
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// Runs `f`, turning an error or a panic into a null pointer and a last-error message
+fn guard<T>(f: impl FnOnce() -> Result<T, String>) -> *mut T {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Box::into_raw(Box::new(value)),
+        Ok(Err(message)) => {
+            set_error(message);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_error("internal error: the solver panicked".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn to_str<'a>(text: *const c_char, what: &str) -> Result<&'a str, String> {
+    if text.is_null() {
+        return Err(format!("{} is null", what));
+    }
+    unsafe { CStr::from_ptr(text) }.to_str().map_err(|_| format!("{} is not valid UTF-8", what))
+}
+
+/// The message from the last failed call on this thread, or null. The string stays
+/// valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn boggle_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Loads a word list with the default length filter. Free it with `boggle_dict_free`.
+///
+/// # Safety
+///
+/// `path` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_dict_load(path: *const c_char) -> *mut Dictionary {
+    guard(|| {
+        let path = unsafe { to_str(path, "path") }?;
+        Dictionary::from_paths(&[path], LengthFilter::default()).map_err(|e| e.to_string())
+    })
+}
+
+/// # Safety
+///
+/// `dict` must be null or a pointer from `boggle_dict_load` that was not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_dict_free(dict: *mut Dictionary) {
+    if !dict.is_null() {
+        drop(unsafe { Box::from_raw(dict) });
+    }
+}
+
+/// Solves a `rows` x `cols` board given as its tiles in row order, such as
+/// `"srpseuimeahwwdzr"` for 4 x 4 (`q` is the Qu tile). Free the result with
+/// `boggle_result_free`.
+///
+/// # Safety
+///
+/// `dict` must be null or a live pointer from `boggle_dict_load`, which the call only
+/// reads, and `board` null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_solve(
+    dict: *const Dictionary,
+    board: *const c_char,
+    rows: usize,
+    cols: usize,
+) -> *mut FfiResult {
+    guard(|| {
+        let dict = unsafe { dict.as_ref() }.ok_or("dictionary is null")?;
+        let text = unsafe { to_str(board, "board") }?;
+        let line = Board::parse_rows(&[text], &ParseOptions::default()).map_err(|e| e.to_string())?;
+        let tiles = &line.tiles()[0];
+        if rows == 0 || cols == 0 || tiles.len() != rows * cols {
+            return Err(format!("board has {} tiles, expected {} x {}", tiles.len(), rows, cols));
+        }
+        let board = Board::new(tiles.chunks(cols).map(|row| row.to_vec()).collect());
+
+        // The solver only borrows the dictionary, so the caller's is untouched on every path
+        let result = BoggleSolver::with_words(board, Cow::Borrowed(dict)).map_err(|e| e.to_string())?.solve();
+        let words = result.words.iter().map(|f| CString::new(f.word.as_str()).unwrap_or_default()).collect();
+        Ok(FfiResult { result, words })
+    })
+}
+
+/// # Safety
+///
+/// `result` must be null or a pointer from `boggle_solve` that was not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_result_free(result: *mut FfiResult) {
+    if !result.is_null() {
+        drop(unsafe { Box::from_raw(result) });
+    }
+}
+
+/// Number of words found; 0 for a null result.
+///
+/// # Safety
+///
+/// `result` must be null or a live pointer from `boggle_solve`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_result_count(result: *const FfiResult) -> usize {
+    unsafe { result.as_ref() }.map_or(0, |r| r.words.len())
+}
+
+/// Sum of the word scores; 0 for a null result.
+///
+/// # Safety
+///
+/// `result` must be null or a live pointer from `boggle_solve`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_result_total_score(result: *const FfiResult) -> u32 {
+    unsafe { result.as_ref() }.map_or(0, |r| r.result.total_score())
+}
+
+/// The `index`th word, longest first, or null past the end. Owned by the result.
+///
+/// # Safety
+///
+/// `result` must be null or a live pointer from `boggle_solve`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_result_word(result: *const FfiResult, index: usize) -> *const c_char {
+    unsafe { result.as_ref() }.and_then(|r| r.words.get(index)).map_or(ptr::null(), |word| word.as_ptr())
+}
+
+/// The score of the `index`th word, or 0 past the end.
+///
+/// # Safety
+///
+/// `result` must be null or a live pointer from `boggle_solve`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn boggle_result_word_score(result: *const FfiResult, index: usize) -> u32 {
+    unsafe { result.as_ref() }.and_then(|r| r.result.words.get(index)).map_or(0, |f| score(&f.word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(boggle_last_error()) }.to_str().unwrap().to_string()
+    }
+
+    unsafe fn words(result: *const FfiResult) -> Vec<(String, u32)> {
+        (0..unsafe { boggle_result_count(result) })
+            .map(|i| {
+                let word = unsafe { CStr::from_ptr(boggle_result_word(result, i)) }.to_str().unwrap().to_string();
+                (word, unsafe { boggle_result_word_score(result, i) })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solves_through_the_c_interface() {
+        let path = std::env::temp_dir().join(format!("boggle_ffi_{}.txt", std::process::id()));
+        std::fs::write(&path, "cat\ncater\nace\nquest\nsquat\nrote\n").unwrap();
+        let dict = unsafe { boggle_dict_load(c(path.to_str().unwrap()).as_ptr()) };
+        std::fs::remove_file(&path).unwrap();
+        assert!(!dict.is_null());
+
+        unsafe {
+            let board = c("catqersto");
+            let result = boggle_solve(dict, board.as_ptr(), 3, 3);
+            assert!(!result.is_null());
+            let expected = [("CATER", 2), ("QUEST", 2), ("SQUAT", 2), ("ROTE", 1), ("ACE", 1), ("CAT", 1)];
+            assert_eq!(words(result), expected.map(|(word, points)| (word.to_string(), points)));
+            assert_eq!(boggle_result_total_score(result), 9);
+            assert!(boggle_result_word(result, 6).is_null());
+            assert_eq!(boggle_result_word_score(result, 6), 0);
+            boggle_result_free(result);
+
+            // A failed solve leaves the dictionary as it was
+            assert!(boggle_solve(dict, board.as_ptr(), 2, 4).is_null());
+            assert_eq!(last_error(), "board has 9 tiles, expected 2 x 4");
+            assert!(boggle_solve(dict, c("ca1qersto").as_ptr(), 3, 3).is_null());
+            let result = boggle_solve(dict, board.as_ptr(), 3, 3);
+            assert_eq!(boggle_result_count(result), 6);
+            boggle_result_free(result);
+            boggle_dict_free(dict);
+        }
+    }
+
+    #[test]
+    fn failures_return_null_with_a_message() {
+        unsafe {
+            assert!(boggle_dict_load(ptr::null()).is_null());
+            assert_eq!(last_error(), "path is null");
+            assert!(boggle_dict_load(c("/nonexistent/boggle/words.txt").as_ptr()).is_null());
+            assert!(last_error().contains("words.txt"), "{}", last_error());
+            assert!(boggle_solve(ptr::null(), c("abcd").as_ptr(), 2, 2).is_null());
+            assert_eq!(last_error(), "dictionary is null");
+
+            assert_eq!(boggle_result_count(ptr::null()), 0);
+            assert_eq!(boggle_result_total_score(ptr::null()), 0);
+            assert!(boggle_result_word(ptr::null(), 0).is_null());
+            boggle_result_free(ptr::null_mut());
+            boggle_dict_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::BoggleError;
+
+// The rarity of a word missing from the list, and of any word with a count of 0
+const RAREST: u32 = 1000;
+
+/// How often words occur, from a `WORD<TAB>count` file, looked up case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct Frequencies {
+    counts: HashMap<String, u64>,
+}
+
+impl Frequencies {
+    /// Loads a frequency file. Lines without a tab or whose count is not a whole number
+    /// are skipped; for a word listed twice the first count wins.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| BoggleError::FrequenciesIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        Ok(Frequencies::parse(&text))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut counts = HashMap::new();
+        for line in text.lines() {
+            if let Some((word, count)) = line.split_once('\t')
+                && let Ok(count) = count.trim().parse()
+            {
+                counts.entry(word.trim().to_uppercase()).or_insert(count);
+            }
+        }
+        Frequencies { counts }
+    }
+
+    pub fn get(&self, word: &str) -> Option<u64> {
+        self.counts.get(&word.trim().to_uppercase()).copied()
+    }
+
+    /// How rare `word` is, from 1000 for a word the list does not have down towards 0
+    /// for the most common: 1000 / (1 + ln(1 + count)), rounded.
+    pub fn rarity(&self, word: &str) -> u32 {
+        match self.get(word) {
+            Some(count) => (RAREST as f64 / (1.0 + (count as f64).ln_1p())).round() as u32,
+            None => RAREST,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::solver::BoggleSolver;
+
+    // QUEST's count of 0 makes it as rare as the unlisted SQUAT; the bad lines are skipped
+    // and CAT's second count ignored
+    const FREQS: &str = "cat\t1000\nCATER\t1000\nace\t50\nrote\t5\nquest\t0\nbad line\nsquat\tmany\ncat\t1\n";
+
+    #[test]
+    fn reads_counts_case_insensitively() {
+        let path = std::env::temp_dir().join(format!("boggle_freqs_{}.tsv", std::process::id()));
+        fs::write(&path, FREQS).unwrap();
+        let freqs = Frequencies::from_path(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(freqs.len(), 5);
+        assert_eq!((freqs.get("CAT"), freqs.get("cater"), freqs.get("Squat")), (Some(1000), Some(1000), None));
+
+        let rarities = ["QUEST", "SQUAT", "ROTE", "ACE", "CAT"].map(|word| freqs.rarity(word));
+        assert_eq!(rarities, [1000, 1000, 358, 203, 126]);
+
+        let err = Frequencies::from_path(&path).unwrap_err();
+        assert!(matches!(err, BoggleError::FrequenciesIo(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn sorts_rarest_first() {
+        let freqs = Frequencies::parse(FREQS);
+        let board = Board::from_rows(&["cat", "qer", "sto"]);
+        let words = ["cat", "cater", "ace", "quest", "squat", "rote"];
+        let result = BoggleSolver::from_words(board, words).unwrap().solve().sorted_by_rarity(&freqs);
+        let order: Vec<&str> = result.words.iter().map(|f| f.word.as_str()).collect();
+        // Ties, between the rarest pair and the most common, break alphabetically
+        assert_eq!(order, ["QUEST", "SQUAT", "ROTE", "ACE", "CAT", "CATER"]);
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::dictionary::LengthFilter;
+use crate::error::BoggleError;
+use crate::trie::{WordIndex, WordNode};
+
+const MAGIC: &[u8; 8] = b"BOGFST\0\0";
+const VERSION: u8 = 1;
+// The header before the automaton: magic, version, min and max length, node count,
+// root offset and automaton length
+const HEADER_LEN: usize = 8 + 1 + 4 * 5;
+
+/// A minimized automaton stored as one flat byte buffer and walked in place, for word
+/// lists too big to keep as a trie. It is built once, from any dictionary structure,
+/// and saved to a file that later loads without rebuilding anything.
+///
+/// Each node is a little-endian `u32` holding its edge count shifted left by one and
+/// its word flag in the low bit, followed by its edges in letter order, each a `u32`
+/// letter and the `u32` offset of the node it leads to. Children are written before
+/// their parents, so every edge points back into the buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fst {
+    bytes: Vec<u8>,
+    root: u32,
+    node_count: usize,
+    words: usize,
+    // The word lengths it was built with, and the narrower ones to report now
+    built: LengthFilter,
+    filter: LengthFilter,
+}
+
+/// A node of an `Fst`: its offset in the buffer, and the letters spelled so far, so
+/// a length filter narrower than the one it was built with can still be applied.
+#[derive(Debug, Clone, Copy)]
+pub struct FstNode<'a> {
+    fst: &'a Fst,
+    offset: u32,
+    letters: usize,
+}
+
+// A node's word flag and edges, with the children already written
+type Signature = (bool, Vec<(char, u32)>);
+
+impl Fst {
+    /// Minimizes the words below `root`, which were loaded with `filter`, into a buffer.
+    pub fn build<N: WordNode>(root: N, filter: LengthFilter) -> Self {
+        let mut bytes = Vec::new();
+        let mut registry = HashMap::new();
+        let root = Self::encode(root, &mut bytes, &mut registry);
+        let fst = Fst { bytes, root, node_count: registry.len(), words: 0, built: filter, filter };
+        Fst { words: fst.count_words(), ..fst }
+    }
+
+    // Writes `node` unless an identical one already was, returning its offset
+    fn encode<N: WordNode>(node: N, bytes: &mut Vec<u8>, registry: &mut HashMap<Signature, u32>) -> u32 {
+        let edges: Vec<(char, u32)> =
+            node.children().into_iter().map(|(ch, child)| (ch, Self::encode(child, bytes, registry))).collect();
+        let signature = (node.is_word(), edges);
+        if let Some(&offset) = registry.get(&signature) {
+            return offset;
+        }
+        let offset = u32::try_from(bytes.len()).expect("automaton is larger than 4 GiB");
+        let (is_word, edges) = &signature;
+        bytes.extend_from_slice(&((edges.len() as u32) << 1 | *is_word as u32).to_le_bytes());
+        for &(ch, target) in edges {
+            bytes.extend_from_slice(&(ch as u32).to_le_bytes());
+            bytes.extend_from_slice(&target.to_le_bytes());
+        }
+        registry.insert(signature, offset);
+        offset
+    }
+
+    /// Whether `data` starts like a saved FST.
+    pub fn is_fst(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.bytes.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        for n in [
+            self.built.min_len,
+            self.built.max_len.unwrap_or(0),
+            self.node_count,
+            self.root as usize,
+            self.bytes.len(),
+        ] {
+            out.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        out.extend_from_slice(&self.bytes);
+        fs::write(path, out)
+    }
+
+    /// Reads a saved FST, to report only words `filter` allows. Fails if the file was
+    /// built without some of the words `filter` asks for, or is damaged: every offset
+    /// is checked here so the search never needs to.
+    pub fn load<P: AsRef<Path>>(path: P, filter: LengthFilter) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let data = fs::read(path)
+            .map_err(|e| BoggleError::DictionaryIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+        Fst::from_bytes(&data, &path.display().to_string(), filter)
+    }
+
+    /// Same as `load`, for a saved FST already read into `data`; `name` is how errors
+    /// refer to it.
+    pub fn from_bytes(data: &[u8], name: &str, filter: LengthFilter) -> Result<Self, BoggleError> {
+        let invalid = |msg: &str| BoggleError::InvalidFst(format!("{}: {}", name, msg));
+        if !Fst::is_fst(data) || data.len() < HEADER_LEN {
+            return Err(invalid("not an FST file"));
+        }
+        if data[8] != VERSION {
+            return Err(invalid("unsupported version; rebuild it with build-fst"));
+        }
+        let field = |i: usize| u32::from_le_bytes(data[9 + 4 * i..13 + 4 * i].try_into().unwrap_or_default());
+        let built = LengthFilter { min_len: field(0) as usize, max_len: Some(field(1) as usize).filter(|&n| n > 0) };
+        let (root, len) = (field(3), field(4) as usize);
+        let bytes = data[HEADER_LEN..].to_vec();
+        if bytes.len() != len {
+            return Err(invalid("truncated"));
+        }
+
+        let narrower = filter.min_len >= built.min_len
+            && built.max_len.is_none_or(|max| filter.max_len.is_some_and(|wanted| wanted <= max));
+        if !narrower {
+            return Err(invalid(&format!(
+                "it holds only words of {}; rebuild it with build-fst to allow {}",
+                describe(built),
+                describe(filter)
+            )));
+        }
+
+        let fst = Fst { bytes, root, node_count: 0, words: 0, built, filter };
+        let node_count = fst.check().ok_or_else(|| invalid("corrupt automaton"))?;
+        Ok(Fst { node_count, words: fst.count_words(), ..fst })
+    }
+
+    // Shared nodes are reached by many words, so each is counted once per depth
+    fn count_words(&self) -> usize {
+        fn count(node: FstNode, memo: &mut HashMap<(u32, usize), usize>) -> usize {
+            if let Some(&n) = memo.get(&(node.offset, node.letters)) {
+                return n;
+            }
+            let below: usize = node.children().into_iter().map(|(_, child)| count(child, memo)).sum();
+            let n = node.is_word() as usize + below;
+            memo.insert((node.offset, node.letters), n);
+            n
+        }
+        count(self.root(), &mut HashMap::new())
+    }
+
+    // Walks the buffer node by node, checking that every edge leads to the start of an
+    // earlier node and the root is a node. Returns the number of nodes
+    fn check(&self) -> Option<usize> {
+        let mut starts = vec![false; self.bytes.len()];
+        let mut pos = 0;
+        let mut count = 0;
+        while pos < self.bytes.len() {
+            let edges = (self.read(pos)? >> 1) as usize;
+            let end = pos.checked_add(4 + 8 * edges)?;
+            if end > self.bytes.len() {
+                return None;
+            }
+            for edge in 0..edges {
+                char::from_u32(self.read(pos + 4 + 8 * edge)?)?;
+                let target = self.read(pos + 8 + 8 * edge)? as usize;
+                if target >= pos || !starts[target] {
+                    return None;
+                }
+            }
+            starts[pos] = true;
+            count += 1;
+            pos = end;
+        }
+        starts.get(self.root as usize).copied().unwrap_or(false).then_some(count)
+    }
+
+    fn read(&self, pos: usize) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes.get(pos..pos + 4)?.try_into().ok()?))
+    }
+
+    // Offsets come from `check`ed edges, so they are always in the buffer
+    fn u32_at(&self, pos: usize) -> u32 {
+        u32::from_le_bytes(self.bytes[pos..pos + 4].try_into().expect("four bytes"))
+    }
+
+    pub fn root(&self) -> FstNode<'_> {
+        FstNode { fst: self, offset: self.root, letters: 0 }
+    }
+
+    /// Number of distinct nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Number of words, counting only those the length filter allows.
+    pub fn word_count(&self) -> usize {
+        self.words
+    }
+
+    /// Size of the automaton in bytes, without the file header.
+    pub fn byte_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+// "3 or more letters", "3 to 8 letters"
+fn describe(filter: LengthFilter) -> String {
+    match filter.max_len {
+        Some(max) => format!("{} to {} letters", filter.min_len, max),
+        None => format!("{} or more letters", filter.min_len),
+    }
+}
+
+impl<'a> FstNode<'a> {
+    fn edge_count(self) -> usize {
+        (self.fst.u32_at(self.offset as usize) >> 1) as usize
+    }
+
+    // The letter and target of edge `i`
+    fn edge(self, i: usize) -> (u32, u32) {
+        let pos = self.offset as usize + 4 + 8 * i;
+        (self.fst.u32_at(pos), self.fst.u32_at(pos + 4))
+    }
+
+    fn at(self, offset: u32) -> FstNode<'a> {
+        FstNode { fst: self.fst, offset, letters: self.letters + 1 }
+    }
+}
+
+impl WordNode for FstNode<'_> {
+    fn child(self, ch: char) -> Option<Self> {
+        // Edges are in letter order
+        let (mut lo, mut hi) = (0, self.edge_count());
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let (letter, target) = self.edge(mid);
+            match letter.cmp(&(ch as u32)) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(self.at(target)),
+            }
+        }
+        None
+    }
+
+    fn is_word(self) -> bool {
+        self.fst.u32_at(self.offset as usize) & 1 == 1 && self.fst.filter.allows(self.letters)
+    }
+
+    fn children(self) -> Vec<(char, Self)> {
+        (0..self.edge_count())
+            .map(|i| {
+                let (letter, target) = self.edge(i);
+                (char::from_u32(letter).expect("letters are checked on load"), self.at(target))
+            })
+            .collect()
+    }
+}
+
+impl WordIndex for Fst {
+    type Node<'a> = FstNode<'a>;
+
+    fn root(&self) -> FstNode<'_> {
+        Fst::root(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, ParseOptions};
+    use crate::dictionary::Dictionary;
+    use crate::solver::BoggleSolver;
+
+    const WORDS: [&str; 8] = ["cat", "cater", "ace", "quest", "squat", "rote", "toes", "zebra"];
+
+    fn found(dict: Dictionary) -> Vec<String> {
+        let board = Board::parse_rows(&["cat", "qer", "sto"], &ParseOptions::default()).unwrap();
+        let mut words: Vec<String> =
+            BoggleSolver::with_dictionary(board, dict).unwrap().solve().words.into_iter().map(|f| f.word).collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn an_fst_solves_like_the_trie() {
+        let dict = Dictionary::from_words(WORDS);
+        let fst = dict.to_fst();
+        assert_eq!(fst.word_count(), WORDS.len());
+        let expected = found(dict);
+        assert_eq!(expected, ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT", "TOES"]);
+
+        // Saved and read back, by path and by the words-file loader that sniffs it
+        let path = std::env::temp_dir().join(format!("boggle_fst_{}.fst", std::process::id()));
+        fst.save(&path).unwrap();
+        assert_eq!(found(Dictionary::from_fst(&path, LengthFilter::default()).unwrap()), expected);
+        assert_eq!(found(Dictionary::from_paths(&[&path], LengthFilter::default()).unwrap()), expected);
+        let data = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Fst::from_bytes(&data, "words.fst", LengthFilter::default()).unwrap(), fst);
+
+        let err = Fst::from_bytes(&data[..data.len() - 1], "words.fst", LengthFilter::default()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid FST: words.fst: truncated");
+    }
+}
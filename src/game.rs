@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::error::BoggleError;
+use crate::score::score;
+use crate::solver::{BoggleSolver, SolveResult};
+
+/// One player's list of words for a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Player {
+    pub name: String,
+    pub words: Vec<String>,
+}
+
+/// Why a player's word does not count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invalid {
+    /// Shorter than the dictionary's minimum length.
+    TooShort,
+    /// Longer than the dictionary's maximum length.
+    TooLong,
+    NotInDictionary,
+    NotOnBoard,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Invalid::TooShort => write!(f, "too short"),
+            Invalid::TooLong => write!(f, "too long"),
+            Invalid::NotInDictionary => write!(f, "not in the dictionary"),
+            Invalid::NotOnBoard => write!(f, "not on the board"),
+        }
+    }
+}
+
+/// How one player's words were judged. Words keep the order the player listed them in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerScore {
+    pub name: String,
+    /// Valid words no other player found; only these score.
+    pub valid: Vec<String>,
+    /// Valid words another player also found, each with the other players' names.
+    pub duplicates: Vec<(String, Vec<String>)>,
+    pub invalid: Vec<(String, Invalid)>,
+    pub score: u32,
+}
+
+impl Player {
+    /// Reads a word list, one word per line, named after the file without its extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, BoggleError> {
+        let path = path.as_ref();
+        let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into());
+        Ok(Player::new(name, read_word_list(path)?))
+    }
+
+    /// A player with `words` trimmed and uppercased. Blank lines are dropped, and a word
+    /// listed more than once is kept only the first time.
+    pub fn new<I, S>(name: String, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut seen = HashSet::new();
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_uppercase())
+            .filter(|word| !word.is_empty() && seen.insert(word.clone()))
+            .collect();
+        Player { name, words }
+    }
+}
+
+/// The words in a file, one per line, trimmed and uppercased in file order. Blank lines
+/// are dropped; repeats are kept.
+pub fn read_word_list<P: AsRef<Path>>(path: P) -> Result<Vec<String>, BoggleError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .map_err(|e| BoggleError::WordListIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+    Ok(text.lines().map(|line| line.trim().to_uppercase()).filter(|word| !word.is_empty()).collect())
+}
+
+/// Whether `word` (any case) counts on the solver's board: it must pass the dictionary's
+/// length filter, be in the dictionary, and have a path, found by a search for just
+/// that word.
+pub fn judge_word(solver: &BoggleSolver, word: &str) -> Result<(), Invalid> {
+    let word = solver.dictionary().normalize(word);
+    let filter = solver.dictionary().filter();
+    let letters = word.chars().count();
+    if letters < filter.min_len {
+        Err(Invalid::TooShort)
+    } else if !filter.allows(letters) {
+        Err(Invalid::TooLong)
+    } else if !solver.dictionary().contains(&word) {
+        Err(Invalid::NotInDictionary)
+    } else if solver.check_word(&word).is_none() {
+        Err(Invalid::NotOnBoard)
+    } else {
+        Ok(())
+    }
+}
+
+/// Each check `judge_word` makes on a word, with how far the word got in the ones
+/// it fails, from `explain_word`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The word, trimmed and uppercased.
+    pub word: String,
+    /// Its length in letters, as the length filter counts it.
+    pub letters: usize,
+    /// `TooShort` or `TooLong` when the length filter rejects it.
+    pub length: Option<Invalid>,
+    pub in_dictionary: bool,
+    /// How many of its first letters some dictionary word starts with.
+    pub dictionary_prefix: usize,
+    /// How many of its first letters the board can spell, and the path spelling them:
+    /// a path for the whole word if there is one.
+    pub board_prefix: usize,
+    pub path: Vec<(usize, usize)>,
+}
+
+impl Explanation {
+    pub fn on_board(&self) -> bool {
+        self.board_prefix == self.letters
+    }
+
+    /// The first check the word fails, in the order `judge_word` makes them.
+    pub fn verdict(&self) -> Result<(), Invalid> {
+        match self.length {
+            Some(reason) => Err(reason),
+            None if !self.in_dictionary => Err(Invalid::NotInDictionary),
+            None if !self.on_board() => Err(Invalid::NotOnBoard),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Why `word` (any case) is or is not found on the solver's board. Unlike `judge_word`
+/// every check is made, and the dictionary and board ones report the longest start of
+/// the word that passes.
+pub fn explain_word(solver: &BoggleSolver, word: &str) -> Explanation {
+    let word = solver.dictionary().normalize(word);
+    let filter = solver.dictionary().filter();
+    let letters = word.chars().count();
+    let length = if letters < filter.min_len {
+        Some(Invalid::TooShort)
+    } else if !filter.allows(letters) {
+        Some(Invalid::TooLong)
+    } else {
+        None
+    };
+    let (board_prefix, path) = solver.longest_traceable(&word);
+    Explanation {
+        letters,
+        length,
+        in_dictionary: solver.dictionary().contains(&word),
+        dictionary_prefix: solver.dictionary().longest_prefix(&word),
+        board_prefix,
+        path,
+        word,
+    }
+}
+
+/// Scores a round the way Boggle does: each word is checked with `judge_word`, and a
+/// valid word found by more than one player scores for none of them.
+pub fn score_players(solver: &BoggleSolver, players: &[Player]) -> Vec<PlayerScore> {
+    let verdicts: Vec<Vec<Result<(), Invalid>>> =
+        players.iter().map(|player| player.words.iter().map(|word| judge_word(solver, word)).collect()).collect();
+
+    // Which players found each valid word
+    let mut finders: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (player, verdicts)) in players.iter().zip(&verdicts).enumerate() {
+        for (word, verdict) in player.words.iter().zip(verdicts) {
+            if verdict.is_ok() {
+                finders.entry(word).or_default().push(i);
+            }
+        }
+    }
+
+    players
+        .iter()
+        .zip(&verdicts)
+        .enumerate()
+        .map(|(i, (player, verdicts))| {
+            let mut result = PlayerScore {
+                name: player.name.clone(),
+                valid: Vec::new(),
+                duplicates: Vec::new(),
+                invalid: Vec::new(),
+                score: 0,
+            };
+            for (word, verdict) in player.words.iter().zip(verdicts) {
+                match verdict {
+                    Err(reason) => result.invalid.push((word.clone(), *reason)),
+                    Ok(()) if finders[word.as_str()].len() > 1 => {
+                        let others = finders[word.as_str()].iter().filter(|&&other| other != i);
+                        let others = others.map(|&other| players[other].name.clone()).collect();
+                        result.duplicates.push((word.clone(), others));
+                    }
+                    Ok(()) => {
+                        result.score += score(word);
+                        result.valid.push(word.clone());
+                    }
+                }
+            }
+            result
+        })
+        .collect()
+}
+
+/// What one guess of a `Game` came to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guess {
+    /// A word new to the game, worth these points.
+    Valid(u32),
+    /// A valid word already guessed, which counts only the first time.
+    Repeated,
+    Invalid(Invalid),
+}
+
+/// One player's game against the solver's board: each guess is judged with
+/// `judge_word` as it is made, and the valid ones kept in the order they came.
+#[derive(Clone)]
+pub struct Game<'a> {
+    solver: &'a BoggleSolver<'a>,
+    found: Vec<String>,
+    score: u32,
+}
+
+impl<'a> Game<'a> {
+    pub fn new(solver: &'a BoggleSolver<'a>) -> Self {
+        Game { solver, found: Vec::new(), score: 0 }
+    }
+
+    /// Judges `word` (any case), counting it if it is valid and new.
+    pub fn guess(&mut self, word: &str) -> Guess {
+        let word = self.solver.dictionary().normalize(word);
+        if let Err(reason) = judge_word(self.solver, &word) {
+            return Guess::Invalid(reason);
+        }
+        if self.found.contains(&word) {
+            return Guess::Repeated;
+        }
+        let points = score(&word);
+        self.score += points;
+        self.found.push(word);
+        Guess::Valid(points)
+    }
+
+    /// The valid words guessed, in order.
+    pub fn found(&self) -> &[String] {
+        &self.found
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// The words of `solution` the player did not find, in the same order.
+    pub fn missed(&self, solution: &SolveResult) -> SolveResult {
+        let found: HashSet<&str> = self.found.iter().map(String::as_str).collect();
+        let words = solution.words.iter().filter(|f| !found.contains(f.word.as_str())).cloned().collect();
+        SolveResult { words }
+    }
+}
+
+/// Where a timed game reads the time, so one can be played against a fake clock.
+pub trait Clock {
+    /// Time since the game started.
+    fn elapsed(&self) -> Duration;
+}
+
+/// The wall clock, counting from when it was started.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock(Instant);
+
+impl SystemClock {
+    pub fn start() -> Self {
+        SystemClock(Instant::now())
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Plays `game` until `limit` has passed on `clock` or `guesses` closes, judging each
+/// guess as it arrives and writing the verdict with the running score and the time
+/// left to `out`. Guesses come over a channel, fed by a thread reading stdin or by a
+/// test, so time running out cuts off a player still typing. Returns whether it did.
+pub fn play_timed(
+    game: &mut Game,
+    guesses: &Receiver<String>,
+    out: &mut impl Write,
+    clock: &impl Clock,
+    limit: Duration,
+) -> io::Result<bool> {
+    loop {
+        let left = limit.saturating_sub(clock.elapsed());
+        if left.is_zero() {
+            writeln!(out, "Time's up!")?;
+            return Ok(true);
+        }
+        let guess = match guesses.recv_timeout(left) {
+            Ok(guess) => guess,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return Ok(false),
+        };
+        let word = game.solver.dictionary().normalize(&guess);
+        if word.is_empty() {
+            continue;
+        }
+        let verdict = match game.guess(&word) {
+            Guess::Valid(points) => format!("yes, +{}", points),
+            Guess::Repeated => "already found".to_string(),
+            Guess::Invalid(reason) => reason.to_string(),
+        };
+        // Rounded up, so the first guess does not already show a second gone
+        let left = limit.saturating_sub(clock.elapsed()).as_millis().div_ceil(1000);
+        writeln!(out, "{}: {} ({} points, {}:{:02} left)", word, verdict, game.score(), left / 60, left % 60)?;
+    }
+}
@@ -0,0 +1,395 @@
+//! Just enough gzip (RFC 1952) and DEFLATE (RFC 1951) to read compressed word lists.
+//! The whole stream is decoded at once; word lists are small enough for that.
+
+// Base lengths and distances for each code, and how many extra bits follow it
+const LENGTH_BASE: [u16; 29] =
+    [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] =
+    [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+// The order a dynamic block lists its code-length code lengths in
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+const MAX_BITS: usize = 15;
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xedb8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |c, &b| CRC_TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8))
+}
+
+/// Whether `data` starts like a gzip file.
+pub(crate) fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decompresses a gzip file, including several members one after another as `cat`
+/// makes them, checking each member's CRC and length.
+pub(crate) fn gunzip(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    loop {
+        let start = out.len();
+        pos = skip_header(data, pos)?;
+        let mut bits = Bits { data, pos, buffer: 0, count: 0 };
+        inflate(&mut bits, &mut out)?;
+        // The trailer starts at the next whole byte
+        pos = bits.pos;
+        let trailer = data.get(pos..pos + 8).ok_or("truncated gzip trailer")?;
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if crc != crc32(&out[start..]) {
+            return Err("gzip checksum mismatch".to_string());
+        }
+        if size != (out.len() - start) as u32 {
+            return Err("gzip length mismatch".to_string());
+        }
+        pos += 8;
+        if !is_gzip(&data[pos..]) {
+            return Ok(out);
+        }
+    }
+}
+
+// The position just past a member header
+fn skip_header(data: &[u8], pos: usize) -> Result<usize, String> {
+    const FHCRC: u8 = 2;
+    const FEXTRA: u8 = 4;
+    const FNAME: u8 = 8;
+    const FCOMMENT: u8 = 16;
+
+    if !is_gzip(&data[pos..]) {
+        return Err("not a gzip file".to_string());
+    }
+    let header = data.get(pos..pos + 10).ok_or("truncated gzip header")?;
+    if header[2] != 8 {
+        return Err(format!("unsupported gzip compression method {}", header[2]));
+    }
+    let flags = header[3];
+    let mut pos = pos + 10;
+    if flags & FEXTRA != 0 {
+        let len = data.get(pos..pos + 2).ok_or("truncated gzip header")?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    // The original file name and a comment, each ended by a NUL
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let rest = data.get(pos..).unwrap_or_default();
+            pos += rest.iter().position(|&b| b == 0).ok_or("truncated gzip header")? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    if pos > data.len() {
+        return Err("truncated gzip header".to_string());
+    }
+    Ok(pos)
+}
+
+// Reads bits least significant first, as DEFLATE packs them
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn take(&mut self, n: u32) -> Result<u32, String> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos).ok_or("truncated deflate stream")?;
+            self.buffer |= (byte as u32) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u32 << n) - 1);
+        self.buffer >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    // Drops the rest of the current byte
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+// A canonical Huffman code: how many codes there are of each length, and the symbols
+// in code order
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+        // More codes of a length than the shorter ones leave room for is not a code
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = left * 2 - count as i32;
+            if left < 0 {
+                return Err("invalid Huffman code".to_string());
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16, String> {
+        // `code` is compared against the first code of each length in turn
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..=MAX_BITS {
+            code |= bits.take(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code in deflate stream".to_string())
+    }
+}
+
+fn inflate(bits: &mut Bits, out: &mut Vec<u8>) -> Result<(), String> {
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => stored(bits, out)?,
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                codes(bits, out, &Huffman::new(&lengths)?, &Huffman::new(&[5; 30])?)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(bits)?;
+                codes(bits, out, &literals, &distances)?;
+            }
+            _ => return Err("invalid deflate block type".to_string()),
+        }
+        if last {
+            bits.align();
+            return Ok(());
+        }
+    }
+}
+
+fn stored(bits: &mut Bits, out: &mut Vec<u8>) -> Result<(), String> {
+    bits.align();
+    let header = bits.data.get(bits.pos..bits.pos + 4).ok_or("truncated deflate stream")?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    if len != !u16::from_le_bytes([header[2], header[3]]) {
+        return Err("corrupt stored deflate block".to_string());
+    }
+    let start = bits.pos + 4;
+    out.extend_from_slice(bits.data.get(start..start + len as usize).ok_or("truncated deflate stream")?);
+    bits.pos = start + len as usize;
+    Ok(())
+}
+
+// Reads the literal/length and distance codes a dynamic block starts with
+fn dynamic_codes(bits: &mut Bits) -> Result<(Huffman, Huffman), String> {
+    let literal_count = bits.take(5)? as usize + 257;
+    let distance_count = bits.take(5)? as usize + 1;
+    let length_count = bits.take(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err("too many codes in deflate block".to_string());
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..length_count] {
+        code_lengths[index] = bits.take(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (len, repeat) = match code_lengths.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last().ok_or("deflate repeat with no previous length")?, 3 + bits.take(2)?),
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        if lengths.len() + repeat as usize > literal_count + distance_count {
+            return Err("deflate code lengths overrun".to_string());
+        }
+        lengths.extend(std::iter::repeat_n(len, repeat as usize));
+    }
+    if lengths[256] == 0 {
+        return Err("deflate block has no end code".to_string());
+    }
+    Ok((Huffman::new(&lengths[..literal_count])?, Huffman::new(&lengths[literal_count..])?))
+}
+
+// Decodes literals and back-references up to the end-of-block code
+fn codes(bits: &mut Bits, out: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+        let code = symbol - 257;
+        if code >= LENGTH_BASE.len() {
+            return Err("invalid deflate length code".to_string());
+        }
+        let len = LENGTH_BASE[code] as usize + bits.take(LENGTH_EXTRA[code] as u32)? as usize;
+        let code = distances.decode(bits)? as usize;
+        if code >= DIST_BASE.len() {
+            return Err("invalid deflate distance code".to_string());
+        }
+        let distance = DIST_BASE[code] as usize + bits.take(DIST_EXTRA[code] as u32)? as usize;
+        if distance > out.len() {
+            return Err("deflate distance reaches before the start".to_string());
+        }
+        // Byte by byte, since a copy may overlap the bytes it produces
+        let from = out.len() - distance;
+        for i in 0..len {
+            out.push(out[from + i]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, ParseOptions};
+    use crate::dictionary::{Dictionary, LengthFilter};
+    use crate::error::BoggleError;
+    use crate::solver::BoggleSolver;
+
+    const WORDS: &str = "cat\ncater\nace\nquest\nsquat\nrote\n";
+    // WORDS as `gzip -0` (a stored block) and `gzip -1` (fixed Huffman codes) write it
+    const STORED: &str = "1f8b0800000000000403011f00e0ff6361740a63617465720a6163650a71756573740a7371756174\
+        0a726f74650aa0ff240d1f000000";
+    const FIXED: &str = "1f8b08000000000004034b4e2ce14a4e2c492de24a4c4ee52a2c4d2d2ee12a2e2c058a16e597a472\
+        0100a0ff240d1f000000";
+    // LONGER as `gzip -9` writes it, with dynamic Huffman codes
+    const LONGER: [&str; 16] = [
+        "cat", "cater", "caters", "ace", "aces", "quest", "quests", "squat", "squats", "rote", "rotes", "toes", "star",
+        "stare", "tears", "rates",
+    ];
+    const DYNAMIC: &str = "1f8b080000000000020325cc310e00200c02c09d5f360db3b1e0ffad75e01608194686595f21922f\
+        c23e94bf82f6e9e128d43207c1ab91a306c28cfea878dd05745e06fe5b000000";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn longer() -> String {
+        LONGER.map(|word| format!("{}\n", word)).concat()
+    }
+
+    #[test]
+    fn inflates_each_block_type() {
+        assert!(is_gzip(&bytes(FIXED)) && !is_gzip(WORDS.as_bytes()));
+        assert_eq!(gunzip(&bytes(STORED)).unwrap(), WORDS.as_bytes());
+        assert_eq!(gunzip(&bytes(FIXED)).unwrap(), WORDS.as_bytes());
+        assert_eq!(gunzip(&bytes(DYNAMIC)).unwrap(), longer().as_bytes());
+        // Members one after another, as `cat a.gz b.gz` makes them
+        let both = [bytes(FIXED), bytes(DYNAMIC)].concat();
+        assert_eq!(gunzip(&both).unwrap(), (WORDS.to_string() + &longer()).as_bytes());
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn rejects_damaged_streams() {
+        let fixed = bytes(FIXED);
+        let mut crc = fixed.clone();
+        crc[fixed.len() - 8] ^= 1;
+        assert_eq!(gunzip(&crc).unwrap_err(), "gzip checksum mismatch");
+        let mut size = fixed.clone();
+        size[fixed.len() - 1] ^= 1;
+        assert_eq!(gunzip(&size).unwrap_err(), "gzip length mismatch");
+        assert_eq!(gunzip(&fixed[..20]).unwrap_err(), "truncated deflate stream");
+        assert_eq!(gunzip(&fixed[..fixed.len() - 3]).unwrap_err(), "truncated gzip trailer");
+        assert_eq!(gunzip(&fixed[..6]).unwrap_err(), "truncated gzip header");
+        assert_eq!(gunzip(WORDS.as_bytes()).unwrap_err(), "not a gzip file");
+    }
+
+    #[test]
+    fn solves_against_a_gzipped_word_list() {
+        let dir = std::env::temp_dir();
+        let board = Board::parse_rows(&["cat", "qer", "sto"], &ParseOptions::default()).unwrap();
+        let solve = |dict: Dictionary| {
+            let found = BoggleSolver::with_dictionary(board.clone(), dict).unwrap().solve();
+            let mut words: Vec<String> = found.words.into_iter().map(|found| found.word).collect();
+            words.sort();
+            words
+        };
+        // Found by the extension, and by the magic bytes without one
+        let cases = [("words.txt.gz", bytes(DYNAMIC), longer()), ("words", bytes(FIXED), WORDS.to_string())];
+        for (name, data, plain) in cases {
+            let path = dir.join(format!("boggle_gzip_{}_{}", std::process::id(), name));
+            std::fs::write(&path, data).unwrap();
+            let dict = Dictionary::from_paths(&[&path], LengthFilter::default());
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(solve(dict.unwrap()), solve(Dictionary::from_words(plain.lines())), "{}", name);
+        }
+        let dict = Dictionary::from_words(WORDS.lines());
+        assert_eq!(solve(dict), ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT"]);
+    }
+
+    #[test]
+    fn a_damaged_file_fails_naming_it() {
+        let fixed = bytes(FIXED);
+        let mut corrupt = fixed.clone();
+        corrupt[fixed.len() - 8] ^= 1;
+        let cases = [
+            ("bad.gz", corrupt, "gzip checksum mismatch"),
+            ("short.gz", fixed[..20].to_vec(), "truncated deflate stream"),
+        ];
+        for (name, data, message) in cases {
+            let path = std::env::temp_dir().join(format!("boggle_gzip_{}_{}", std::process::id(), name));
+            std::fs::write(&path, data).unwrap();
+            let err = Dictionary::from_paths(&[&path], LengthFilter::default()).err().unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert!(matches!(err, BoggleError::DictionaryIo(_)), "{:?}", err);
+            assert_eq!(err.to_string(), format!("cannot read dictionary: {}: {}", path.display(), message));
+        }
+    }
+}
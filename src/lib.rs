@@ -0,0 +1,83 @@
+mod arena;
+mod board;
+mod bonus;
+mod config;
+mod daily;
+mod dawg;
+mod definitions;
+mod dice;
+mod dictionary;
+mod display;
+mod encoding;
+mod error;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod frequency;
+mod fst;
+mod game;
+mod gzip;
+mod json;
+mod locale;
+mod optimize;
+mod output;
+mod result_cache;
+mod rng;
+mod score;
+#[cfg(feature = "serve")]
+mod server;
+mod solver;
+mod sorted;
+mod tournament;
+mod trie;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use arena::{ArenaNode, ArenaTrie};
+pub use board::{Board, ParseOptions, Topology, WILDCARD, parse_row};
+pub use bonus::{Bonus, BonusLayout, ScoreBreakdown, base_score, letter_value, score_breakdown};
+pub use config::{Config, DictPreset};
+pub use daily::{Date, daily_seed};
+pub use dawg::{Dawg, DawgNode};
+pub use definitions::Definitions;
+pub use dice::{BIG, CLASSIC, DICE_SETS, DiceSet, SUPER_BIG, dice_for_size, roll};
+#[cfg(feature = "embedded-dict")]
+pub use dictionary::EMBEDDED_WORDS;
+pub use display::{mask_word, render_path};
+pub use dictionary::{Dictionary, LengthFilter, LetterPool, LoadOptions, LoadStats, Source, TrieStats};
+pub use encoding::Encoding;
+pub use error::BoggleError;
+#[cfg(feature = "ffi")]
+pub use ffi::FfiResult;
+pub use frequency::Frequencies;
+pub use fst::{Fst, FstNode};
+pub use game::{
+    Clock, Explanation, Game, Guess, Invalid, Player, PlayerScore, SystemClock, explain_word, judge_word, play_timed,
+    read_word_list, score_players,
+};
+pub use locale::Locale;
+pub use optimize::{
+    Candidate, CandidateOptions, OptimizeOptions, Optimized, Rank, TargetOptions, Targeted, best_of, optimize,
+    roll_target,
+};
+pub use output::{
+    from_json, to_csv, to_json, to_json_series, to_json_stats, to_json_with, to_markdown, to_ndjson_summary,
+    to_ndjson_word,
+};
+pub use result_cache::ResultCache;
+pub use rng::Rng;
+pub use score::score;
+#[cfg(feature = "serve")]
+pub use server::serve;
+pub use solver::{
+    Algorithm, BoggleSolver, FoundWord, Mode, SolveDelta, SolveResult, SolveStats, SortOrder, StartBreakdown,
+    WordFilter,
+};
+pub use sorted::{SortedNode, SortedWords};
+pub use tournament::{Round, RoundScore, Series, parse_series, read_series};
+pub use trie::{TrieNode, WordIndex, WordNode};
+#[cfg(feature = "tui")]
+pub use tui::explore;
+#[cfg(feature = "wasm")]
+pub use wasm::solve_board;
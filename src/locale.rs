@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+/// How letters are uppercased, the same way for dictionary words, board tiles and words
+/// typed in, so a word and the tiles spelling it always agree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// Unicode's own uppercasing: `i` is `I`, and `ß` is `SS` while `ẞ` stays as it is.
+    #[default]
+    Default,
+    /// Turkish dotted and dotless i: `i` is `İ` and `ı` is `I`.
+    Turkish,
+    /// German: `ß` and `ẞ` are both `SS`, one board cell holding two letters.
+    German,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "default" => Ok(Locale::Default),
+            "tr" => Ok(Locale::Turkish),
+            "de" => Ok(Locale::German),
+            _ => Err(format!("unknown locale '{}' (choose default, tr or de)", s)),
+        }
+    }
+}
+
+impl Locale {
+    /// `text` uppercased a letter at a time. Uppercase text comes back unchanged, so
+    /// normalizing twice does no harm.
+    pub fn upper(self, text: &str) -> String {
+        text.chars().map(|ch| self.upper_char(ch)).collect()
+    }
+
+    /// One letter uppercased, which may take several characters, as `ß` does.
+    pub fn upper_char(self, ch: char) -> String {
+        match (self, ch) {
+            (Locale::Turkish, 'i') => "İ".to_string(),
+            (Locale::Turkish, 'ı') => "I".to_string(),
+            (Locale::German, 'ß' | 'ẞ') => "SS".to_string(),
+            _ => ch.to_uppercase().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, ParseOptions};
+    use crate::dictionary::{Dictionary, LengthFilter, LoadOptions};
+    use crate::solver::BoggleSolver;
+
+    // A solver whose board and dictionary are both read in `locale`
+    fn solver(locale: Locale, rows: &[&str], words: &str) -> BoggleSolver<'static> {
+        let board = Board::parse_rows(rows, &ParseOptions { locale, ..ParseOptions::default() }).unwrap();
+        let opts = LoadOptions { locale, ..LoadOptions::default() };
+        let dict = Dictionary::from_reader_with(words.as_bytes(), "words", LengthFilter::default(), &opts).unwrap();
+        BoggleSolver::with_dictionary(board, dict).unwrap()
+    }
+
+    fn found(solver: &BoggleSolver) -> Vec<String> {
+        let mut words = solver.solve_all();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn uppercases_letter_by_letter() {
+        assert_eq!(Locale::Default.upper("kişi ılık"), "KIŞI ILIK");
+        assert_eq!(Locale::Turkish.upper("kişi ılık"), "KİŞİ ILIK");
+        assert_eq!(Locale::Default.upper("straße STRAẞE"), "STRASSE STRAẞE");
+        assert_eq!(Locale::German.upper("straße STRAẞE"), "STRASSE STRASSE");
+        for locale in [Locale::Default, Locale::Turkish, Locale::German] {
+            assert_eq!(locale.upper(&locale.upper("iıßẞé")), locale.upper("iıßẞé"), "{:?}", locale);
+        }
+        assert_eq!("tr".parse(), Ok(Locale::Turkish));
+        assert_eq!("de".parse(), Ok(Locale::German));
+        assert_eq!("xx".parse::<Locale>(), Err("unknown locale 'xx' (choose default, tr or de)".to_string()));
+    }
+
+    #[test]
+    fn turkish_i_matches_on_the_board_and_in_the_dictionary() {
+        let words = "iyi\nkız\n";
+        let turkish = solver(Locale::Turkish, &["iyi", "kız"], words);
+        assert_eq!(found(&turkish), ["KIZ", "İYİ"]);
+        assert!(turkish.check_word("iyi").is_some() && turkish.check_word("kız").is_some());
+        // A typed dotted i is İ, so "kiz" is KİZ, which is not KIZ
+        assert_eq!(turkish.check_word("kiz"), None);
+
+        // Read without the locale, both sides agree on I for either letter
+        let plain = solver(Locale::Default, &["iyi", "kız"], words);
+        assert_eq!(found(&plain), ["IYI", "KIZ"]);
+        assert!(plain.check_word("kiz").is_some());
+    }
+
+    #[test]
+    fn german_sharp_s_is_one_cell_spelling_ss() {
+        let words = "gruß\nstraße\n";
+        let german = solver(Locale::German, &["grUẞ", "tram"], words);
+        assert_eq!(german.board().tile(0, 3), "SS");
+        assert_eq!(found(&german), ["GRUSS"]);
+        assert_eq!(german.check_word("Gruß"), Some(vec![(0, 0), (0, 1), (0, 2), (0, 3)]));
+        assert_eq!(german.check_word("GRUSS"), german.check_word("gruß"));
+
+        // Unicode keeps the capital ẞ as it is, so that board no longer spells the word
+        let plain = solver(Locale::Default, &["grUẞ", "tram"], words);
+        assert_eq!(plain.board().tile(0, 3), "ẞ");
+        assert_eq!(found(&plain), Vec::<String>::new());
+        assert_eq!(found(&solver(Locale::Default, &["gruß", "tram"], words)), ["GRUSS"]);
+    }
+}
@@ -1,154 +1,2608 @@
-use std::collections::{HashSet, HashMap};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use boggle_solver::{
+    Algorithm, Board, BoggleError, BoggleSolver, BonusLayout, CandidateOptions, Config, Date, Definitions, DiceSet,
+    DictPreset, Dictionary, Encoding, FoundWord, Frequencies, Game, Invalid, LengthFilter, LetterPool, LoadOptions,
+    Locale, Mode, OptimizeOptions, ParseOptions, Player, Rank, ResultCache, Rng, RoundScore, Series, SolveResult,
+    SolveStats, SortOrder, StartBreakdown, SystemClock, TargetOptions, Topology, WordFilter, best_of, daily_seed,
+    dice_for_size, explain_word, judge_word, mask_word, optimize, play_timed, read_series, read_word_list, render_path,
+    roll, roll_target, score, score_players, to_csv, to_json, to_json_series, to_json_stats, to_json_with, to_markdown,
+    to_ndjson_summary, to_ndjson_word,
+};
+
+// Exit codes, so scripts can tell failures apart
+const EXIT_USAGE: u8 = 2;
+const EXIT_BAD_BOARD: u8 = 3;
+const EXIT_FILE_IO: u8 = 4;
+const EXIT_DICTIONARY: u8 = 5;
+// --judge found at least one word that does not count
+const EXIT_INVALID_WORDS: u8 = 6;
+// The board fell short of --require-words or --require-score
+const EXIT_BELOW_REQUIREMENT: u8 = 7;
+
+// Where serve listens without --listen: this machine only
+const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+// How long a game of play lasts without --seconds: the classic three-minute sand timer
+const PLAY_SECONDS: f64 = 180.0;
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!("unknown format '{}' (choose text, json, ndjson, csv or markdown)", s)),
+        }
+    }
+}
+
+/// The dictionary structure the search walks.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    #[default]
+    Trie,
+    Dawg,
+    Sorted,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "trie" => Ok(Backend::Trie),
+            "dawg" => Ok(Backend::Dawg),
+            "sorted" => Ok(Backend::Sorted),
+            _ => Err(format!("unknown backend '{}' (choose trie, dawg or sorted)", s)),
+        }
+    }
+}
+
+/// How much goes to stderr besides the results and errors.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    /// `-q`: nothing else, not even warnings or the chosen seed
+    Quiet,
+    #[default]
+    Normal,
+    /// `-v`: what each dictionary contributed, what was filtered out, and the solve time
+    Verbose,
+    /// `-vv`: also the node counts and how many words start at each cell
+    Debug,
+}
+
+/// The subcommands. Without one the historic command line still works: board rows and
+/// flags mean a solve, and `--generate`, `--optimize`, `--check` and `--judge` pick the
+/// other modes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Solve,
+    Generate,
+    Optimize,
+    Check,
+    Explain,
+    Judge,
+    BuildFst,
+    Serve,
+    Repl,
+    Pattern,
+    Tournament,
+    Play,
+    Help,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "solve" => Some(Command::Solve),
+            "generate" => Some(Command::Generate),
+            "optimize" => Some(Command::Optimize),
+            "check" => Some(Command::Check),
+            "explain" => Some(Command::Explain),
+            "judge" => Some(Command::Judge),
+            "build-fst" => Some(Command::BuildFst),
+            "serve" => Some(Command::Serve),
+            "repl" => Some(Command::Repl),
+            "pattern" => Some(Command::Pattern),
+            "tournament" => Some(Command::Tournament),
+            "play" => Some(Command::Play),
+            "help" => Some(Command::Help),
+            _ => None,
+        }
+    }
+}
+
+// The synopsis lines of each subcommand; `None` marks the historic flag-only forms
+const SYNOPSIS: &[(Option<Command>, &str)] = &[
+    (Some(Command::Solve), "solve [options] <row1> <row2> ... <rowN>"),
+    (Some(Command::Solve), "solve [options] <tiles> [--cols N]"),
+    (Some(Command::Solve), "solve [options] --board <file>"),
+    (Some(Command::Solve), "solve [options] --stdin < boards.txt"),
+    (Some(Command::Solve), "solve [options] --player alice.txt --player bob.txt <row1> ... <rowN>"),
+    (Some(Command::Generate), "generate [--size N] [--seed N] [options]"),
+    (Some(Command::Generate), "generate --daily [--date YYYY-MM-DD] [--salt TEXT] [--size N] [options]"),
+    (Some(Command::Generate), "generate --candidates N [--keep K] [--rank-by score|words] [options]"),
+    (Some(Command::Optimize), "optimize [--size N] [--iterations N] [--seconds S] [--seed N] [options]"),
+    (Some(Command::Check), "check <word> [options] <row1> ... <rowN>"),
+    (Some(Command::Explain), "explain <word> [options] <row1> ... <rowN>"),
+    (Some(Command::Judge), "judge <file> [options] <row1> ... <rowN>"),
+    (Some(Command::BuildFst), "build-fst <words.txt> <words.fst> [--min-len N] [--max-len N] [--exclude FILE]"),
+    (Some(Command::Serve), "serve [--listen ADDR] [options]"),
+    (Some(Command::Repl), "repl [options]   (then board <rows>, solve, check <word>, top N, quit on stdin)"),
+    (Some(Command::Pattern), "pattern <pattern> [--force] [--min-len N] [--max-len N] [options]   (B.GG.E, ?A*)"),
+    (Some(Command::Tournament), "tournament <boards.txt> [options]   (a board per line, or per blank-line block)"),
+    (Some(Command::Play), "play [--seconds S] [--size N] [--seed N] [options] [<row1> ... <rowN>]"),
+    (Some(Command::Help), "help [command]"),
+    (None, "[options] <row1> ... <rowN>   (no command: the same as solve)"),
+];
 
 #[derive(Default)]
-struct TrieNode {
-    children: HashMap<char, TrieNode>,
-    is_word: bool,
+struct Options {
+    show_all: bool,
+    by_score: bool,
+    show_paths: bool,
+    format: Format,
+    backend: Backend,
+    cache: bool,
+    // Where solve results are kept from one run to the next
+    cache_dir: Option<PathBuf>,
+    threads: usize,
+    timeout: Option<Duration>,
+    filter: LengthFilter,
+    dicts: Vec<String>,
+    // The word lists of the --dict-name preset, used when no --dict is given
+    preset_dicts: Vec<PathBuf>,
+    dict_name: Option<String>,
+    list_dicts: bool,
+    excludes: Vec<String>,
+    max_dict_words: Option<usize>,
+    players: Vec<String>,
+    judge: Option<String>,
+    output: Option<String>,
+    verbosity: Verbosity,
+    no_prune: bool,
+    stats: bool,
+    summary: bool,
+    heatmap: bool,
+    // Print how many words start at each cell, counting each word at every start it has
+    // with `every_start`
+    start_breakdown: bool,
+    every_start: bool,
+    no_color: bool,
+    coverage: bool,
+    live: bool,
+    interactive: bool,
+    // Play against the board: word counts by length, with letters revealed on request
+    hints: bool,
+    // Print every word with only its first N letters showing
+    hints_level: Option<usize>,
+    sort: SortOrder,
+    word_filter: WordFilter,
+    through: Option<(usize, usize)>,
+    top: Option<usize>,
+    mode: Mode,
+    algorithm: Algorithm,
+    wrap: bool,
+    topology: Topology,
+    // Where each --layer starts a new layer in `rows`, and whether a board file is layered
+    layer_starts: Vec<usize>,
+    layered: bool,
+    // How many tiles wide a board typed as one run of tiles is
+    cols: Option<usize>,
+    no_diagonals: bool,
+    allow_reuse: bool,
+    count_paths: bool,
+    // A file of DL/TL/DW/TW cells to score words by
+    bonus: Option<String>,
+    board_file: Option<String>,
+    batch: bool,
+    check: Option<String>,
+    // Set with `check`: explain each step of checking the word
+    explain: bool,
+    // A word whose path to draw on the board
+    show_path: Option<String>,
+    // Print the board's canonical form instead of solving it
+    canonical: bool,
+    defs: Option<String>,
+    freq: Option<String>,
+    digraphs: Vec<String>,
+    literal_q: bool,
+    locale: Locale,
+    encoding: Option<Encoding>,
+    generate: Option<&'static DiceSet>,
+    optimize: Option<&'static DiceSet>,
+    candidates: Option<usize>,
+    keep: Option<usize>,
+    rank: Option<Rank>,
+    // --target-words or --target-score and its range, and --attempts
+    target: Option<(Rank, RangeInclusive<usize>)>,
+    attempts: Option<usize>,
+    // The fewest words and points the board must offer, after the filters
+    require_words: Option<usize>,
+    require_score: Option<u32>,
+    iterations: Option<usize>,
+    seconds: Option<f64>,
+    seed: Option<u64>,
+    // --daily's date, today's unless --date gives one, and --salt
+    daily: bool,
+    date: Option<Date>,
+    salt: Option<String>,
+    rows: Vec<String>,
+    // The word list and the FST file to build from it
+    build_fst: Option<(String, String)>,
+    // The address serve listens on
+    serve: Option<String>,
+    repl: bool,
+    // The dictionary query of pattern, and whether one without a fixed letter may run
+    pattern: Option<String>,
+    force: bool,
+    // The file of boards tournament plays, one round each
+    tournament: Option<String>,
+    // Play a timed game against the board, --seconds long
+    play: bool,
+    help: bool,
 }
 
-impl TrieNode {
-    fn insert(&mut self, word: &str) {
-        let mut node = self;
-        for c in word.chars() {
-            node = node.children.entry(c).or_default();
+/// Reads the options after the program name and `command`, if one was given.
+fn parse_args(command: Option<Command>) -> Result<Options, String> {
+    let mut opts = Options { threads: 1, top: Some(10), ..Options::default() };
+    let (mut min_len, mut max_len) = (None, None);
+    let mut size = 4;
+    let mut word_filter = WordFilter::new();
+    let mut args = env::args().skip(if command.is_some() { 2 } else { 1 });
+    match command {
+        Some(Command::Generate) => opts.generate = dice_for_size(size),
+        Some(Command::Optimize) => opts.optimize = dice_for_size(size),
+        Some(Command::Check) => opts.check = Some(args.next().ok_or("check needs the word to look for")?),
+        Some(Command::Explain) => {
+            opts.check = Some(args.next().ok_or("explain needs the word to look for")?);
+            opts.explain = true;
+        }
+        Some(Command::Judge) => opts.judge = Some(args.next().ok_or("judge needs a word-list file")?),
+        Some(Command::BuildFst) => {
+            let words = args.next().ok_or("build-fst needs a word list and the FST file to write")?;
+            let fst = args.next().ok_or("build-fst needs a word list and the FST file to write")?;
+            opts.build_fst = Some((words, fst));
+        }
+        Some(Command::Serve) => opts.serve = Some(DEFAULT_LISTEN.to_string()),
+        Some(Command::Repl) => opts.repl = true,
+        Some(Command::Pattern) => opts.pattern = Some(args.next().ok_or("pattern needs the pattern to match")?),
+        Some(Command::Tournament) => opts.tournament = Some(args.next().ok_or("tournament needs a file of boards")?),
+        Some(Command::Play) => opts.play = true,
+        Some(Command::Solve) | Some(Command::Help) | None => {}
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => opts.help = true,
+            "--all" => opts.show_all = true,
+            "--by-score" => opts.by_score = true,
+            "--paths" => opts.show_paths = true,
+            "--json" => opts.format = Format::Json,
+            "--format" => {
+                opts.format = args.next().ok_or("--format needs text, json, ndjson, csv or markdown")?.parse()?
+            }
+            "--dawg" => opts.backend = Backend::Dawg,
+            "--backend" => opts.backend = args.next().ok_or("--backend needs trie, dawg or sorted")?.parse()?,
+            "--cache" => opts.cache = true,
+            "--cache-dir" => opts.cache_dir = Some(args.next().ok_or("--cache-dir needs a directory")?.into()),
+            "--threads" => {
+                let value = args.next().ok_or("--threads needs a value")?;
+                opts.threads = value.parse().map_err(|_| format!("invalid thread count '{}'", value))?;
+            }
+            "--timeout-ms" => {
+                let value = args.next().ok_or("--timeout-ms needs a value")?;
+                let ms = value.parse().map_err(|_| format!("invalid --timeout-ms value '{}'", value))?;
+                opts.timeout = Some(Duration::from_millis(ms));
+            }
+            "--min-len" => min_len = Some(parse_len(&arg, args.next())?),
+            "--max-len" => max_len = Some(parse_len(&arg, args.next())?),
+            "--dict" => opts.dicts.push(args.next().ok_or("--dict needs a path")?),
+            "--dict-name" => opts.dict_name = Some(args.next().ok_or("--dict-name needs a name from the config file")?),
+            "--list-dicts" => opts.list_dicts = true,
+            "--force" => opts.force = true,
+            "--exclude" => opts.excludes.push(args.next().ok_or("--exclude needs a path")?),
+            "--max-dict-words" => opts.max_dict_words = Some(parse_count(&arg, args.next())?),
+            "--player" => opts.players.push(args.next().ok_or("--player needs a path")?),
+            "--judge" => opts.judge = Some(args.next().ok_or("--judge needs a path")?),
+            "--output" => opts.output = Some(args.next().ok_or("--output needs a path")?),
+            // Repeating -v is the same as -vv
+            "-v" | "--verbose" => {
+                opts.verbosity =
+                    if opts.verbosity >= Verbosity::Verbose { Verbosity::Debug } else { Verbosity::Verbose };
+            }
+            "-vv" => opts.verbosity = Verbosity::Debug,
+            "-q" | "--quiet" => opts.verbosity = Verbosity::Quiet,
+            "--no-prune" => opts.no_prune = true,
+            "--stats" => opts.stats = true,
+            "--summary" => opts.summary = true,
+            "--heatmap" => opts.heatmap = true,
+            "--start-breakdown" => opts.start_breakdown = true,
+            "--every-start" => opts.every_start = true,
+            "--no-color" => opts.no_color = true,
+            "--coverage" => opts.coverage = true,
+            "--live" => opts.live = true,
+            "--interactive" => opts.interactive = true,
+            "--hints" => opts.hints = true,
+            "--hints-level" => opts.hints_level = Some(parse_count(&arg, args.next())?),
+            "--wrap" => opts.wrap = true,
+            "--hex" => opts.topology = Topology::Hex,
+            "--layer" => opts.layer_starts.push(opts.rows.len()),
+            "--layered" => opts.layered = true,
+            "--cols" => opts.cols = Some(parse_count(&arg, args.next())?),
+            "--no-diagonals" => opts.no_diagonals = true,
+            "--allow-reuse" => opts.allow_reuse = true,
+            "--count-paths" => opts.count_paths = true,
+            "--bonus" => opts.bonus = Some(args.next().ok_or("--bonus needs a path")?),
+            "--mode" => opts.mode = args.next().ok_or("--mode needs boggle, wordsearch or anagram")?.parse()?,
+            "--algorithm" => {
+                opts.algorithm = args.next().ok_or("--algorithm needs board, words or auto")?.parse()?;
+            }
+            "--top" => {
+                let value = args.next().ok_or("--top needs a number or 'all'")?;
+                opts.top = match value.as_str() {
+                    "all" => None,
+                    _ => Some(value.parse().map_err(|_| format!("invalid --top value '{}'", value))?),
+                };
+            }
+            "--starts-with" => word_filter = word_filter.starts_with(&args.next().ok_or("--starts-with needs text")?),
+            "--ends-with" => word_filter = word_filter.ends_with(&args.next().ok_or("--ends-with needs text")?),
+            "--contains" => word_filter = word_filter.contains(&args.next().ok_or("--contains needs text")?),
+            // The Spelling Bee name for the same filter
+            "--through" | "--require-cell" => {
+                let value = args.next().ok_or(format!("{} needs a cell like 2,3", arg))?;
+                let cell =
+                    value.split_once(',').and_then(|(r, c)| Some((r.trim().parse().ok()?, c.trim().parse().ok()?)));
+                opts.through = Some(cell.ok_or(format!("{} needs a cell like 2,3, got '{}'", arg, value))?);
+            }
+            "--require-letter" => {
+                let value = args.next().ok_or("--require-letter needs a letter")?;
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if ch.is_alphabetic() => word_filter = word_filter.requires_letter(ch),
+                    _ => return Err(format!("--require-letter needs a single letter, got '{}'", value)),
+                }
+            }
+            "--sort" => opts.sort = args.next().ok_or("--sort needs alpha, length, score, rarity or paths")?.parse()?,
+            "--stdin" => opts.batch = true,
+            "--generate" => opts.generate = dice_for_size(size),
+            "--optimize" => opts.optimize = dice_for_size(size),
+            "--candidates" => opts.candidates = Some(parse_count(&arg, args.next())?),
+            "--keep" => opts.keep = Some(parse_count(&arg, args.next())?),
+            "--require-words" => opts.require_words = Some(parse_count(&arg, args.next())?),
+            "--require-score" => {
+                let value = args.next().ok_or("--require-score needs a value")?;
+                opts.require_score = Some(value.parse().map_err(|_| format!("invalid score '{}'", value))?);
+            }
+            "--rank-by" => opts.rank = Some(args.next().ok_or("--rank-by needs score or words")?.parse()?),
+            "--target-words" | "--target-score" => {
+                if opts.target.is_some() {
+                    return Err("give one of --target-words and --target-score".to_string());
+                }
+                let metric = if arg == "--target-words" { Rank::Words } else { Rank::Score };
+                opts.target = Some((metric, parse_range(&arg, args.next())?));
+            }
+            "--attempts" => opts.attempts = Some(parse_count(&arg, args.next())?),
+            "--iterations" => {
+                let value = args.next().ok_or("--iterations needs a value")?;
+                opts.iterations = Some(value.parse().map_err(|_| format!("invalid iteration count '{}'", value))?);
+            }
+            "--seconds" => {
+                let value = args.next().ok_or("--seconds needs a value")?;
+                let seconds: f64 = value.parse().map_err(|_| format!("invalid number of seconds '{}'", value))?;
+                if !(seconds > 0.0 && seconds.is_finite()) {
+                    return Err(format!("invalid number of seconds '{}'", value));
+                }
+                opts.seconds = Some(seconds);
+            }
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                opts.seed = Some(value.parse().map_err(|_| format!("invalid seed '{}'", value))?);
+            }
+            "--daily" => opts.daily = true,
+            "--date" => {
+                let value = args.next().ok_or("--date needs a value")?;
+                opts.date = Some(value.parse()?);
+            }
+            "--salt" => opts.salt = Some(args.next().ok_or("--salt needs a value")?),
+            "--size" => {
+                let value = args.next().ok_or("--size needs a value")?;
+                size = value.parse().map_err(|_| format!("invalid board size '{}'", value))?;
+                if dice_for_size(size).is_none() {
+                    return Err(format!("no dice set for size {} (choose 4, 5 or 6)", size));
+                }
+                opts.generate = opts.generate.and(dice_for_size(size));
+                opts.optimize = opts.optimize.and(dice_for_size(size));
+            }
+            "--literal-q" => opts.literal_q = true,
+            "--locale" => opts.locale = args.next().ok_or("--locale needs a value")?.parse()?,
+            "--encoding" => opts.encoding = Some(args.next().ok_or("--encoding needs a value")?.parse()?),
+            "--digraphs" => {
+                let value = args.next().ok_or("--digraphs needs a list like LL,CH,RR")?;
+                opts.digraphs = value.split(',').map(|d| d.trim().to_uppercase()).filter(|d| !d.is_empty()).collect();
+            }
+            "--defs" => opts.defs = Some(args.next().ok_or("--defs needs a path")?),
+            "--freq" => opts.freq = Some(args.next().ok_or("--freq needs a path")?),
+            "--check" => opts.check = Some(args.next().ok_or("--check needs a word")?),
+            "--show-path" => opts.show_path = Some(args.next().ok_or("--show-path needs a word")?),
+            "--canonical" => opts.canonical = true,
+            "--explain" => {
+                opts.check = Some(args.next().ok_or("--explain needs a word")?);
+                opts.explain = true;
+            }
+            "--listen" => opts.serve = Some(args.next().ok_or("--listen needs an address such as 127.0.0.1:8080")?),
+            "--board" => opts.board_file = Some(args.next().ok_or("--board needs a path")?),
+            // Rows never start with "--" and a letter, so that is a mistyped option
+            _ if arg.strip_prefix("--").is_some_and(|name| name.starts_with(|c: char| c.is_ascii_alphabetic())) => {
+                return Err(format!("unknown option '{}'", arg));
+            }
+            _ => opts.rows.push(arg),
+        }
+    }
+    if opts.build_fst.is_some() && (!opts.rows.is_empty() || opts.board_file.is_some() || opts.batch) {
+        return Err("build-fst takes no board".to_string());
+    }
+    if opts.sort == SortOrder::Paths && !opts.count_paths {
+        return Err("--sort paths needs --count-paths".to_string());
+    }
+    if opts.sort == SortOrder::Rarity && opts.freq.is_none() {
+        return Err("--sort rarity needs a frequency list from --freq FILE".to_string());
+    }
+    if opts.serve.is_some() && command != Some(Command::Serve) {
+        return Err("--listen only applies to serve".to_string());
+    }
+    if opts.serve.is_some() && (!opts.rows.is_empty() || opts.board_file.is_some() || opts.batch) {
+        return Err("serve takes its boards from POST /solve requests, not the command line".to_string());
+    }
+    if opts.serve.is_some() && !cfg!(feature = "serve") {
+        return Err("serve needs a build with the serve feature (cargo run --features serve)".to_string());
+    }
+    if let Some(pattern) = &opts.pattern {
+        if !opts.rows.is_empty() || opts.board_file.is_some() || opts.batch {
+            return Err("pattern searches the dictionary; it takes no board".to_string());
+        }
+        if pattern.is_empty() || !pattern.chars().all(|ch| ch.is_alphabetic() || matches!(ch, '.' | '?' | '*')) {
+            return Err(format!(
+                "pattern '{}' may hold only letters, '.' or '?' for one letter and '*' for any run",
+                pattern
+            ));
         }
-        node.is_word = true;
+        if !opts.force && !pattern.chars().any(char::is_alphabetic) {
+            return Err(format!(
+                "pattern '{}' has no fixed letter, so it lists much of the dictionary; add --force to run it",
+                pattern
+            ));
+        }
+    } else if opts.force {
+        return Err("--force only applies to pattern".to_string());
+    }
+    if opts.repl && (!opts.rows.is_empty() || opts.board_file.is_some() || opts.batch || opts.generate.is_some()) {
+        return Err("repl takes its boards from board commands on stdin, not the command line".to_string());
+    }
+    if opts.tournament.is_some() {
+        if !opts.rows.is_empty() || opts.board_file.is_some() || opts.batch || opts.generate.is_some() {
+            return Err("tournament plays the boards in its file; drop the rows, --board and --stdin".to_string());
+        }
+        if matches!(opts.format, Format::Ndjson | Format::Csv | Format::Markdown) {
+            return Err("tournament prints a table or, with --json, one JSON document".to_string());
+        }
+    }
+    let rolls = matches!(command, Some(Command::Generate) | Some(Command::Optimize));
+    if rolls && (!opts.rows.is_empty() || opts.board_file.is_some() || opts.batch) {
+        return Err("generate and optimize roll their own board; drop the rows, --board and --stdin".to_string());
+    }
+    if opts.topology == Topology::Hex && (opts.wrap || opts.no_diagonals) {
+        return Err("--hex cannot be combined with --wrap or --no-diagonals".to_string());
+    }
+    if opts.topology == Topology::Hex && (rolls || opts.generate.is_some()) {
+        return Err("--hex boards are typed in; the dice only roll square boards".to_string());
+    }
+    let needs_paths = opts.show_paths || opts.show_path.is_some() || opts.count_paths || opts.through.is_some();
+    let needs_paths = needs_paths || opts.heatmap || opts.coverage || opts.start_breakdown || opts.bonus.is_some();
+    if opts.mode == Mode::Anagram && needs_paths {
+        return Err("anagram mode finds words without paths, so it cannot be combined with --paths, \
+             --show-path, --count-paths, --through, --heatmap, --coverage, --start-breakdown or --bonus"
+            .to_string());
+    }
+    let layers = !opts.layer_starts.is_empty() || opts.layered;
+    if opts.bonus.is_some() && (layers || opts.topology == Topology::Hex) {
+        return Err("--bonus layouts are square grids; they cannot be used with --hex or 3D boards".to_string());
+    }
+    if opts.cols.is_some() && (layers || opts.topology == Topology::Hex) {
+        return Err("--cols cuts a square grid into rows; it cannot be used with --hex or 3D boards".to_string());
+    }
+    if layers && (opts.topology == Topology::Hex || opts.wrap) {
+        return Err("3D boards cannot be combined with --hex or --wrap".to_string());
+    }
+    if layers && (rolls || opts.generate.is_some() || opts.batch) {
+        return Err(
+            "3D boards are typed in or read with --board; they cannot be rolled or read from --stdin".to_string(),
+        );
+    }
+    if !opts.layer_starts.is_empty() && opts.board_file.is_some() {
+        return Err("--layer separates typed rows; a --board file uses --layered and blank lines".to_string());
+    }
+    if opts.interactive && !cfg!(feature = "tui") {
+        return Err("--interactive needs a build with the tui feature (cargo run --features tui)".to_string());
+    }
+    if opts.interactive && (opts.live || opts.batch || opts.format != Format::Text) {
+        return Err("--interactive cannot be combined with --live, --stdin, --json or --format".to_string());
+    }
+    let hints = opts.hints || opts.hints_level.is_some();
+    if hints && (opts.live || opts.batch || opts.interactive || opts.format != Format::Text) {
+        return Err(
+            "--hints and --hints-level cannot be combined with --live, --stdin, --interactive, --json or --format"
+                .to_string(),
+        );
+    }
+    if opts.every_start && !opts.start_breakdown {
+        return Err("--every-start changes how --start-breakdown counts; add --start-breakdown".to_string());
+    }
+    if opts.start_breakdown && opts.format != Format::Text {
+        return Err("--start-breakdown prints a text grid; with --json, --stats reports the counts".to_string());
+    }
+    let searches = opts.optimize.is_some() || opts.candidates.is_some();
+    if opts.canonical && (opts.batch || searches || opts.format != Format::Text) {
+        return Err("--canonical prints one board; it cannot be combined with --stdin, --candidates, --optimize, \
+                    --json or --format"
+            .to_string());
+    }
+    if opts.judge.is_some() && !opts.players.is_empty() {
+        return Err("--judge and --player cannot be combined".to_string());
     }
+    let scoring = !opts.players.is_empty() || opts.judge.is_some();
+    if opts.play {
+        if opts.batch || opts.live || opts.interactive || hints || scoring || opts.check.is_some() {
+            return Err(
+                "play is a game of its own; it cannot be combined with --stdin, --live, --interactive, --hints, \
+                 --player, --judge or --check"
+                    .to_string(),
+            );
+        }
+        if opts.format == Format::Ndjson {
+            return Err("play reveals the words at the end; --format ndjson streams them instead".to_string());
+        }
+        // Without a board of its own the game rolls one
+        if opts.rows.is_empty() && opts.board_file.is_none() {
+            opts.generate = dice_for_size(size);
+        }
+    }
+    if scoring && (opts.live || opts.batch || opts.check.is_some() || opts.format != Format::Text) {
+        return Err("--player/--judge cannot be combined with --live, --stdin, --check, --json or --format".to_string());
+    }
+    if opts.candidates.is_some() && (opts.generate.is_none() || opts.optimize.is_some()) {
+        return Err("--candidates needs generate".to_string());
+    }
+    if opts.candidates.is_none() && (opts.keep.is_some() || opts.rank.is_some()) {
+        return Err("--keep and --rank-by only apply with --candidates".to_string());
+    }
+    if opts.candidates.is_some() && (opts.live || opts.interactive || scoring || opts.check.is_some()) {
+        return Err(
+            "--candidates cannot be combined with --live, --interactive, --player, --judge or --check".to_string(),
+        );
+    }
+    if opts.daily && (opts.generate.is_none() || opts.optimize.is_some() || opts.candidates.is_some()) {
+        return Err("--daily picks the board for generate and play, without --candidates".to_string());
+    }
+    if opts.target.is_some() && (opts.generate.is_none() || opts.optimize.is_some() || opts.candidates.is_some()) {
+        return Err("--target-words and --target-score roll the board for generate, without --candidates".to_string());
+    }
+    if opts.target.is_none() && opts.attempts.is_some() {
+        return Err("--attempts only applies with --target-words or --target-score".to_string());
+    }
+    if opts.daily && opts.seed.is_some() {
+        return Err("--daily picks its own seed; drop --seed".to_string());
+    }
+    if !opts.daily && (opts.date.is_some() || opts.salt.is_some()) {
+        return Err("--date and --salt only apply with --daily".to_string());
+    }
+    if opts.daily {
+        opts.seed = Some(daily_seed(daily_date(&opts), opts.salt.as_deref().unwrap_or_default()));
+    }
+    let requires = opts.require_words.is_some() || opts.require_score.is_some();
+    if requires && (opts.batch || opts.optimize.is_some() || opts.candidates.is_some()) {
+        return Err(
+            "--require-words and --require-score check one board, not --stdin, --optimize or --candidates".to_string(),
+        );
+    }
+    if requires && (opts.interactive || scoring || opts.check.is_some()) {
+        return Err(
+            "--require-words/--require-score do not apply to --interactive, --player, --judge or --check".to_string(),
+        );
+    }
+    if opts.output.is_some() && (opts.live || opts.interactive || scoring || opts.check.is_some()) {
+        return Err("--output cannot be combined with --live, --interactive, --player, --judge or --check".to_string());
+    }
+    if opts.cache_dir.is_some()
+        && (opts.live || opts.bonus.is_some() || opts.max_dict_words.is_some() || opts.format == Format::Ndjson)
+    {
+        return Err(
+            "--cache-dir cannot be combined with --live, --bonus, --max-dict-words or --format ndjson".to_string(),
+        );
+    }
+    if opts.live && opts.timeout.is_some() {
+        return Err("--live cannot be combined with --timeout-ms".to_string());
+    }
+    if opts.live && opts.format != Format::Text {
+        return Err("--live prints plain words and cannot be combined with --json or --format".to_string());
+    }
+    let many_boards = opts.batch || opts.optimize.is_some() || opts.candidates.is_some();
+    if opts.format == Format::Ndjson && (many_boards || opts.through.is_some()) {
+        return Err(
+            "--format ndjson streams one board's words; it cannot be combined with --stdin, --optimize, \
+             --candidates or --through"
+                .to_string(),
+        );
+    }
+    if opts.format == Format::Markdown && (opts.batch || opts.candidates.is_some()) {
+        return Err(
+            "--format markdown reports one board; it cannot be combined with --stdin or --candidates".to_string()
+        );
+    }
+    // The bigger dice sets officially require 4-letter words unless overridden
+    let default_min = opts.generate.or(opts.optimize).map_or(LengthFilter::default().min_len, |set| set.min_len);
+    // A preset's lengths stand in for --min-len and --max-len; its paths only for --dict
+    if let Some(name) = &opts.dict_name {
+        let preset = find_preset(name, opts.dicts.is_empty())?;
+        (min_len, max_len) = (min_len.or(preset.min_len), max_len.or(preset.max_len));
+        if opts.dicts.is_empty() {
+            opts.preset_dicts = preset.paths;
+        }
+    }
+    opts.filter = LengthFilter::new(min_len.unwrap_or(default_min), max_len).map_err(|e| e.to_string())?;
+    opts.word_filter = word_filter;
+    Ok(opts)
 }
 
-struct BoggleSolver {
-    trie: TrieNode,
-    board: Vec<Vec<String>>,  // Each tile can be multiple chars (e.g., "QU")
-    rows: i32,
-    cols: i32,
+// $BOGGLE_CONFIG, or config.toml in the boggle_solver directory of $XDG_CONFIG_HOME or ~/.config
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("BOGGLE_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("boggle_solver").join("config.toml"))
 }
 
-impl BoggleSolver {
-    fn new(board: Vec<Vec<String>>, dict_path: &str) -> std::io::Result<Self> {
-        let mut trie = TrieNode::default();
-        let file = File::open(dict_path)?;
-        let reader = BufReader::new(file);
+// The config file's dictionary called `name`, checking its files exist when they are to be read
+fn find_preset(name: &str, check_paths: bool) -> Result<DictPreset, String> {
+    let path = config_path().ok_or("--dict-name needs a config file; set BOGGLE_CONFIG to its path")?;
+    if !path.is_file() {
+        return Err(format!("--dict-name {}: there is no config file at {}", name, path.display()));
+    }
+    let config = Config::from_path(&path).map_err(|e| e.to_string())?;
+    let Some(preset) = config.get(name) else {
+        let names: Vec<&str> = config.dictionaries.iter().map(|preset| preset.name.as_str()).collect();
+        return Err(match names.as_slice() {
+            [] => format!("no dictionary named '{}': {} declares none", name, path.display()),
+            names => format!("no dictionary named '{}' in {}; try {}", name, path.display(), names.join(", ")),
+        });
+    };
+    if check_paths && let Some(missing) = preset.paths.iter().find(|path| !path.is_file()) {
+        return Err(format!("dictionary '{}' lists {}, which does not exist", name, missing.display()));
+    }
+    Ok(preset.clone())
+}
+
+/// The --list-dicts listing: each preset in the config file with its files and lengths.
+fn list_dicts() -> ExitCode {
+    let Some(path) = config_path() else {
+        eprintln!("No config file: set BOGGLE_CONFIG to its path");
+        return ExitCode::SUCCESS;
+    };
+    if !path.is_file() {
+        eprintln!("No config file at {}; declare dictionaries there in [dict.NAME] tables", path.display());
+        return ExitCode::SUCCESS;
+    }
+    let config = match Config::from_path(&path) {
+        Ok(config) => config,
+        Err(e) => return report(&e),
+    };
+    println!("Dictionaries in {}:", path.display());
+    for preset in &config.dictionaries {
+        let lengths = match (preset.min_len, preset.max_len) {
+            (None, None) => String::new(),
+            (Some(min), None) => format!(" ({} or more letters)", min),
+            (None, Some(max)) => format!(" (up to {} letters)", max),
+            (Some(min), Some(max)) => format!(" ({} to {} letters)", min, max),
+        };
+        println!("  {}{}", preset.name, lengths);
+        for file in &preset.paths {
+            println!("    {}{}", file.display(), if file.is_file() { "" } else { " (missing)" });
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// A count of at least one, for --candidates and --keep
+fn parse_count(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or(format!("{} needs a number", flag))?;
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid count '{}' for {}", value, flag)),
+    }
+}
+
+// MIN..MAX, both ends included
+fn parse_range(flag: &str, value: Option<String>) -> Result<RangeInclusive<usize>, String> {
+    let value = value.ok_or(format!("{} needs a range, as in 80..150", flag))?;
+    let range = value.split_once("..").and_then(|(low, high)| Some((low.parse().ok()?, high.parse().ok()?)));
+    match range {
+        Some((low, high)) if low <= high => Ok(low..=high),
+        _ => Err(format!("invalid range '{}' for {} (expected MIN..MAX, as in 80..150)", value, flag)),
+    }
+}
+
+fn parse_len(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or(format!("{} needs a value", flag))?;
+    match value.parse() {
+        Ok(len) if len > 0 => Ok(len),
+        _ => Err(format!("invalid length '{}' for {}", value, flag)),
+    }
+}
+
+/// Prints the synopsis of `command`, or of every command, followed by the options.
+fn print_usage(command: Option<Command>) {
+    let lines: Vec<&str> = SYNOPSIS
+        .iter()
+        .filter(|(of, _)| command.is_none() || command == Some(Command::Help) || *of == command)
+        .map(|(_, line)| *line)
+        .collect();
+    for (i, line) in lines.iter().enumerate() {
+        eprintln!("{} cargo run -- {}", if i == 0 { "Usage:" } else { "      " }, line);
+    }
+    eprintln!("Example: cargo run -- solve srps euim eahw wdzr");
+    if command.is_none() || command == Some(Command::Help) {
+        eprintln!("Note: without a command, --generate, --optimize, --check <word> and --judge <file> still");
+        eprintln!("      choose those commands, as in earlier versions");
+    }
+    eprintln!("Note: rows may be any length, but all rows must have the same number of tiles; a board in");
+    eprintln!("      one argument may separate its rows with '/', ',', spaces or newlines (\"SRPS EUIM ...\")");
+    eprintln!("Note: 'q' or 'qu' is treated as a single tile (the Qu tile); '(ll)' groups letters into one tile");
+    eprintln!("      '?' or '*' is a blank tile standing for any letter; words using one are starred");
+    eprintln!("      '#' or '-' is a blocked cell that no word passes through (quote '#' in the shell)");
+    eprintln!("  --all          print every found word, one per line");
+    eprintln!("  --by-score     rank the top words by score instead of length");
+    eprintln!("  --top N        how many of the best words the summary lists (default 10, 'all' for every word)");
+    eprintln!("  --mode MODE    boggle (default), wordsearch: words only in straight lines, or anagram: any");
+    eprintln!("                 word the board's letters make, wherever they are (no paths)");
+    eprintln!("  --algorithm A  board (default): walk the board's paths; words: search the board for each");
+    eprintln!("                 dictionary word in turn, faster for a tiny word list; auto: pick by size");
+    eprintln!("  --wrap         the board wraps around: opposite edges are adjacent");
+    eprintln!("  --hex          the board is a hexagon of cells with six neighbours each, typed as rows of");
+    eprintln!("                 3,4,5,4,3 tiles (or 4,5,6,7,6,5,4, ...); paths are axial (q,r) cells with");
+    eprintln!("                 the centre at (0,0)");
+    eprintln!("  --layer        start a layer of a 3D board, whose cells also touch the layers above and");
+    eprintln!("                 below: --layer ab cd --layer ef gh; paths are (layer,row,col) cells");
+    eprintln!("  --layered      blank lines in the --board file separate the layers of a 3D board");
+    eprintln!("  --cols N       a board typed as one run of tiles is N wide (default: the run is a square)");
+    eprintln!("  --no-diagonals words may only step up, down, left or right");
+    eprintln!("  --allow-reuse  house rule: a word may use the same cell more than once");
+    eprintln!("  --sort ORDER   order the full word list: alpha, length (default), score, rarity or paths");
+    eprintln!("  --bonus FILE   score words Boggle With Friends style: FILE is a grid shaped like the");
+    eprintln!("                 board of ., DL, TL, DW and TW cells, or L<n> and W<n> for other letter and");
+    eprintln!("                 word multipliers up to 10; letters have Scrabble values, the best path of");
+    eprintln!("                 each word is kept, and -v shows how each score adds up");
+    eprintln!("  --count-paths  count every path spelling each word, for --sort paths, --paths and a");
+    eprintln!("                 paths field in JSON (slower: every path is tallied)");
+    eprintln!("  --starts-with P, --ends-with S, --contains T");
+    eprintln!("                 keep only words matching all of the given letter filters");
+    eprintln!("  --through R,C  keep only words that can pass through the cell at row R, column C");
+    eprintln!("                 (counted from 0, as in --paths); --require-cell R,C is the same");
+    eprintln!("  --require-letter X");
+    eprintln!("                 keep only words using the letter X; repeat to require several");
+    eprintln!("  --paths        print every found word with its (row,col) path");
+    eprintln!("  --explain WORD say why WORD is or is not found: its length, the longest start of it in");
+    eprintln!("                 the dictionary, and how far a path for it gets on the board");
+    eprintln!("  --json         print the result as a JSON object (same as --format json)");
+    eprintln!("  --format F     text (default), json, or csv with one word,length,score,path row per word;");
+    eprintln!("                 ndjson streams one JSON line per word as it is found, then a line with");
+    eprintln!("                 \"type\":\"summary\" and the totals; markdown (or md) is a report");
+    eprintln!("                 of the board, its totals and its words by length, for pasting into chat");
+    eprintln!("  --output FILE  write the results to FILE instead of stdout, reporting a summary line here");
+    eprintln!("  --no-prune     load every dictionary word, even those the board's letters cannot spell");
+    eprintln!("  --dawg         minimize the dictionary into a DAWG before solving (less memory)");
+    eprintln!("  --backend B    dictionary structure to search: trie (default), dawg (same as --dawg), or");
+    eprintln!("                 sorted, a sorted word list stepped through by binary search");
+    eprintln!("  --cache        reuse a binary copy of the dictionary (<first dict>.trie), rebuilding it when stale");
+    eprintln!("  --cache-dir D  keep solve results in directory D and reuse them for the same board (or a");
+    eprintln!("                 turn or flip of it), dictionary files and settings; not for --optimize");
+    eprintln!("  --threads N    build the trie and search starting cells on N threads");
+    eprintln!("  --timeout-ms N stop searching after N ms and report the words found so far");
+    eprintln!("                 (on one thread; not with --live)");
+    eprintln!("  --generate     roll a random board with the official dice, then solve it");
+    eprintln!("  --size N       dice set for --generate: 4 (classic), 5 (Big) or 6 (Super Big)");
+    eprintln!("  --candidates N roll and solve N boards and print the best few, each with the seed that");
+    eprintln!("                 rolls it again (a progress count shows on stderr)");
+    eprintln!("  --keep K       how many of the --candidates boards to print (default 5)");
+    eprintln!("  --rank-by R    rank the --candidates boards by score (default) or words");
+    eprintln!("  --target-words MIN..MAX, --target-score MIN..MAX");
+    eprintln!("                 roll boards until one has that many words (or points), counted before");
+    eprintln!("                 any letter filter, else keep the closest; -v says how many were rolled");
+    eprintln!("  --attempts N   boards --target-words or --target-score rolls at most (default 1000)");
+    eprintln!("  --optimize     search for a high-scoring board of the --size dice's letters and print it");
+    eprintln!("  --iterations N boards --optimize tries (default 2000)");
+    eprintln!("  --seconds S    stop --optimize after S seconds; for play, the length of the game (default 180)");
+    eprintln!("  --seed N       roll the --generate or --optimize board from a fixed seed so runs can be");
+    eprintln!("                 repeated; without it the chosen seed is printed on stderr");
+    eprintln!("  --daily        roll today's puzzle (by UTC date), the same board for everyone");
+    eprintln!("  --date DATE    the day of the --daily puzzle, as YYYY-MM-DD, to play a past one");
+    eprintln!("  --salt TEXT    mixed into the --daily seed so a group gets boards of its own");
+    eprintln!("  --force        let pattern run a pattern with no fixed letter, which walks most of the dictionary");
+    eprintln!("  --listen ADDR  address serve answers on (default {}); needs the serve feature", DEFAULT_LISTEN);
+    eprintln!("  --board FILE   read the board from a file, one row per line ('#' at the start of a line");
+    eprintln!("                 or after a space starts a comment)");
+    eprintln!("  --stdin        solve one board per input line (e.g. srpseuimeahwwdzr or srps/euim/eahw/wdzr)");
+    eprintln!("  --digraphs L   comma-separated multi-letter tiles, e.g. LL,CH,RR");
+    eprintln!("  --literal-q    'q' is a plain Q tile rather than Qu (write '(qu)' for a Qu tile)");
+    eprintln!("  --locale L     uppercase the board, dictionary and typed words the default way, or by");
+    eprintln!("                 tr (i is İ, ı is I) or de (ß and ẞ are one SS tile); turns off --cache");
+    eprintln!("  --encoding E   read the word lists as utf8, latin1 or utf16; by default a byte order mark");
+    eprintln!("                 picks UTF-8 or UTF-16, and lines that are not UTF-8 are read as Latin-1");
+    eprintln!("                 with a warning; turns off --cache");
+    eprintln!("  --min-len N    shortest word to count (default 3)");
+    eprintln!("  --max-len N    longest word to count (default: no limit)");
+    eprintln!("  --dict PATH    dictionary file, plain or gzipped; repeat to merge several (default: $BOGGLE_DICT,");
+    eprintln!("                 then words.txt here or next to the binary), or one FST made by build-fst");
+    eprintln!("  --dict-name N  the dictionary named N in the config file ($BOGGLE_CONFIG, or");
+    eprintln!("                 ~/.config/boggle_solver/config.toml), with its --min-len and --max-len;");
+    eprintln!("                 --dict and the length options still override it");
+    eprintln!("  --list-dicts   list the dictionaries the config file names");
+    eprintln!("  --exclude PATH never find the words in this file, whichever dictionary has them;");
+    eprintln!("                 repeatable, and turns off --cache");
+    eprintln!("  --max-dict-words N");
+    eprintln!("                 stop with an error if the dictionary files hold more than N words, rather");
+    eprintln!("                 than loading a file far too big by mistake; turns off --cache");
+    eprintln!("  --player FILE  score a player's word list (one word per line); repeat for each player.");
+    eprintln!("                 A word more than one player found scores for nobody");
+    eprintln!("  --judge FILE   mark each word in the file OK or why it does not count, then total the");
+    eprintln!("                 valid ones; exits with 6 if any word is invalid");
+    eprintln!("  --require-words N, --require-score P");
+    eprintln!("                 exit with 7 unless the board offers at least N words and P points, counted");
+    eprintln!("                 after the length and letter filters and --exclude");
+    eprintln!("  -v, --verbose  report how many words each dictionary contributed, how many lines were");
+    eprintln!("                 skipped for non-letters or dropped by the length filter, the trie's size,");
+    eprintln!("                 and how long the solve took (all on stderr)");
+    eprintln!("  -vv            also report the node counts and how many words start at each cell");
+    eprintln!("  -q, --quiet    print only the results and errors: no warnings, seeds or progress");
+    eprintln!("  --defs FILE    show definitions from a WORD<TAB>definition file next to the top words");
+    eprintln!("  --freq FILE    read word counts from a WORD<TAB>count file, for --sort rarity and a rarity");
+    eprintln!("                 field in JSON and CSV (1000 for unlisted words, lower for common ones)");
+    eprintln!("  --live         print each word as soon as it is found, then the summary");
+    eprintln!("  --interactive  browse the words in the terminal, highlighting each one's path");
+    eprintln!("                 (up/down to select, / to filter, q to quit; needs the tui feature)");
+    eprintln!("  --hints        play against the board: shows how many words of each length are left;");
+    eprintln!("                 type a word to guess it, or a length for a hint (first letter, then two,");
+    eprintln!("                 then the words), q to give up");
+    eprintln!("  --hints-level N");
+    eprintln!("                 print every word by length with only its first N letters showing");
+    eprintln!("  --stats        report load and solve timings and search counters (on stderr)");
+    eprintln!("  --summary      add a table of word counts by length, the average length and best score");
+    eprintln!("  --heatmap      add the board with how many of the words pass through each cell");
+    eprintln!("  --start-breakdown");
+    eprintln!("                 add the board with how many words start at each cell, counted at the start");
+    eprintln!("                 of the path each was found with; with -vv also the best word from each");
+    eprintln!("  --every-start  count --start-breakdown words at every cell a path for them starts at");
+    eprintln!("  --show-path W  print the board with the cells of W's path highlighted and numbered");
+    eprintln!("  --canonical    print the board turned or flipped into the form all its symmetric copies");
+    eprintln!("                 share, with its fingerprint, instead of solving it");
+    eprintln!("  --no-color     no colors in --heatmap and --show-path (also when NO_COLOR is set);");
+    eprintln!("                 --show-path then puts each step number in brackets");
+    eprintln!("  --coverage     add the --top words covering the most tiles, and any covering every tile");
+    eprintln!("Exit codes: 0 success, 2 bad usage, 3 invalid board, 4 unreadable or unwritable file,");
+    eprintln!("            5 dictionary problem, 6 invalid --judge words, 7 below --require-words/--require-score");
+}
+
+/// Picks the dictionaries: every `--dict`, then the `--dict-name` preset's, then
+/// `$BOGGLE_DICT`, then `words.txt` in the working directory or next to the executable.
+/// On failure returns every path tried.
+fn resolve_dict(opts: &Options) -> Result<Vec<PathBuf>, Vec<PathBuf>> {
+    if !opts.dicts.is_empty() {
+        return Ok(opts.dicts.iter().map(PathBuf::from).collect());
+    }
+    if !opts.preset_dicts.is_empty() {
+        return Ok(opts.preset_dicts.clone());
+    }
+    if let Some(path) = env::var_os("BOGGLE_DICT") {
+        return Ok(vec![PathBuf::from(path)]);
+    }
 
-        for line in reader.lines() {
-            let word = line?;
-            let trimmed = word.trim();
-            if trimmed.len() >= 3 && trimmed.len() <= 16 {
-                trie.insert(&trimmed.to_uppercase());
+    let mut tried = vec![PathBuf::from("words.txt")];
+    if let Some(dir) = env::current_exe().ok().and_then(|exe| exe.parent().map(|d| d.to_path_buf())) {
+        tried.push(dir.join("words.txt"));
+    }
+    match tried.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(vec![path.clone()]),
+        None => Err(tried),
+    }
+}
+
+// The board turned and flipped into the form its symmetric copies share, for --canonical
+fn print_canonical(board: &Board, out: &mut impl Write) -> ExitCode {
+    let canonical = board.canonical_form();
+    let written = write_board(out, &canonical)
+        .and_then(|()| writeln!(out, "Canonical: {}", board_line(&canonical)))
+        .and_then(|()| writeln!(out, "Fingerprint: {:016x}", board.fingerprint()))
+        .and_then(|()| out.flush());
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => write_failed(&e),
+    }
+}
+
+fn write_board(out: &mut impl Write, board: &Board) -> io::Result<()> {
+    for (i, row) in board.tiles().iter().enumerate() {
+        // A blank line between the layers of a 3D board
+        if i > 0 && i % board.layer_rows() == 0 {
+            writeln!(out)?;
+        }
+        // Show tiles the way they are printed on the cube ("Qu", "Th"), blocks as '#'
+        let tiles: Vec<String> = row
+            .iter()
+            .map(|tile| {
+                let mut chars = tile.chars();
+                match chars.next() {
+                    Some(first) => format!("{:<2}", first.to_string() + &chars.as_str().to_lowercase()),
+                    None => "# ".to_string(),
+                }
+            })
+            .collect();
+        writeln!(out, "{}", tiles.join(" ").trim_end())?;
+    }
+    writeln!(out)
+}
+
+// (row,col) cells, axial (q,r) ones on a hex board or (layer,row,col) ones on a 3D board
+fn format_path(board: &Board, path: &[(usize, usize)]) -> String {
+    let cells: Vec<String> = path.iter().map(|&(r, c)| format_cell(board, r, c)).collect();
+    cells.join(" -> ")
+}
+
+fn format_cell(board: &Board, r: usize, c: usize) -> String {
+    if board.depth() > 1 {
+        let (layer, r, c) = board.layer_cell(r, c);
+        return format!("({},{},{})", layer, r, c);
+    }
+    let (a, b) = board.axial(r, c);
+    format!("({},{})", a, b)
+}
+
+fn parse_options(opts: &Options) -> ParseOptions {
+    ParseOptions {
+        qu_tile: !opts.literal_q,
+        digraphs: opts.digraphs.clone(),
+        topology: opts.topology,
+        layered: opts.layered,
+        cols: opts.cols,
+        locale: opts.locale,
+    }
+}
+
+// The typed rows split into layers where each --layer came
+fn parse_layers(opts: &Options, parse_opts: &ParseOptions) -> Result<Board, BoggleError> {
+    let mut starts = opts.layer_starts.clone();
+    starts.insert(0, 0);
+    starts.push(opts.rows.len());
+    let layers = starts
+        .windows(2)
+        .filter(|bounds| bounds[0] < bounds[1])
+        .map(|bounds| Board::parse_rows(&opts.rows[bounds[0]..bounds[1]], parse_opts))
+        .collect::<Result<_, _>>()?;
+    Board::stacked(layers)
+}
+
+// Words that needed a wildcard cell are starred in the text listings
+fn wildcard_mark(found: &FoundWord) -> &'static str {
+    if found.uses_wildcard() { "*" } else { "" }
+}
+
+fn print_check(solver: &BoggleSolver, word: &str) {
+    let word = solver.dictionary().normalize(word);
+    let in_dict = solver.dictionary().contains(&word);
+    match solver.find_path(&word) {
+        // Anagram mode has no path to show
+        Some(path) if in_dict && path.is_empty() => println!("{}: playable (the board has its letters)", word),
+        Some(path) if in_dict => println!("{}: {}", word, format_path(solver.board(), &path)),
+        Some(_) => println!("{}: not playable (on the board, but not in the dictionary)", word),
+        None if in_dict => println!("{}: not playable (in the dictionary, but not on the board)", word),
+        None => println!("{}: not playable (not in the dictionary and not on the board)", word),
+    }
+    if !in_dict {
+        let near = solver.dictionary().suggest(&word, 1);
+        let playable: Vec<String> = near.into_iter().filter(|near| solver.find_path(near).is_some()).take(5).collect();
+        if !playable.is_empty() {
+            println!("  did you mean: {}", playable.join(", "));
+        }
+    }
+}
+
+// The -v report with a bonus layout: how each word's score adds up along its path
+fn print_breakdowns(solver: &BoggleSolver, result: &SolveResult) {
+    eprintln!("Bonus scores:");
+    for found in &result.words {
+        if let Some(breakdown) = solver.score_breakdown(found) {
+            eprintln!("  {}: {}", found.word, breakdown);
+        }
+    }
+}
+
+/// Draws the board with `word`'s path numbered on it, or says the word is not playable.
+fn print_word_path(solver: &BoggleSolver, word: &str, color: bool) {
+    let word = solver.dictionary().normalize(word);
+    match solver.check_word(&word) {
+        Some(path) => print!("{}:\n{}", word, render_path(solver.board(), &path, color)),
+        None => println!("{}: not playable", word),
+    }
+}
+
+// Colors only for a terminal, and only when neither --no-color nor NO_COLOR asks otherwise
+fn use_color(opts: &Options, path: Option<&str>) -> bool {
+    !opts.no_color && path.is_none() && io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// Says whether `word` is playable, then how it fares against the length filter, the
+/// dictionary and the board, with how much of it the last two could match.
+fn print_explanation(solver: &BoggleSolver, word: &str) {
+    let why = explain_word(solver, word);
+    let start = |letters: usize| why.word.chars().take(letters).collect::<String>();
+    match why.verdict() {
+        Ok(()) => println!("{}: playable", why.word),
+        Err(reason) => println!("{}: not playable ({})", why.word, reason),
+    }
+
+    let filter = solver.dictionary().filter();
+    match why.length {
+        None => println!("  length:     {}, allowed", plural(why.letters, "letter")),
+        Some(Invalid::TooShort) => {
+            println!("  length:     {}, fewer than --min-len {}", plural(why.letters, "letter"), filter.min_len)
+        }
+        Some(_) => println!(
+            "  length:     {}, more than --max-len {}",
+            plural(why.letters, "letter"),
+            filter.max_len.unwrap_or_default()
+        ),
+    }
+
+    if why.in_dictionary {
+        println!("  dictionary: yes");
+    } else if why.dictionary_prefix == why.letters {
+        println!("  dictionary: no, though longer words start with it");
+    } else if why.dictionary_prefix == 0 {
+        println!("  dictionary: no; no word starts with {}", start(1));
+    } else {
+        println!("  dictionary: no; the longest start of a word in it is {}", start(why.dictionary_prefix));
+    }
+
+    if why.on_board() && why.path.is_empty() {
+        println!("  board:      yes, it has the letters");
+    } else if why.on_board() {
+        println!("  board:      yes, {}", format_path(solver.board(), &why.path));
+    } else if why.board_prefix == 0 {
+        println!("  board:      no; no tile starts it");
+    } else if why.path.is_empty() {
+        println!("  board:      no; its letters run out after {}", start(why.board_prefix));
+    } else {
+        let path = format_path(solver.board(), &why.path);
+        println!("  board:      no; a path gets as far as {}: {}", start(why.board_prefix), path);
+    }
+}
+
+// "1 word", "3 words"
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 { format!("1 {}", noun) } else { format!("{} {}s", n, noun) }
+}
+
+/// Prints each word passing the letter filters as an NDJSON line the moment the search
+/// finds it, then the summary line, flushing after every line so a reader down a pipe
+/// sees the words as they come.
+fn stream_ndjson(opts: &Options, solver: &BoggleSolver, out: &mut dyn Write, path: Option<&str>) -> ExitCode {
+    let defs = match opts.defs.as_ref().map(Definitions::from_path).transpose() {
+        Ok(defs) => defs,
+        Err(e) => return report(&e),
+    };
+    let freqs = match load_frequencies(opts) {
+        Ok(freqs) => freqs,
+        Err(e) => return report(&e),
+    };
+    let mut written = Ok(());
+    let all = solver.solve_with(|found| {
+        if written.is_ok() && opts.word_filter.matches(&found.word) {
+            let line = to_ndjson_word(found, defs.as_ref(), freqs.as_ref());
+            written = writeln!(out, "{}", line).and_then(|()| out.flush());
+        }
+    });
+    let result = all.filter(&opts.word_filter);
+    let summary = to_ndjson_summary(solver.board(), result.count(), result.total_score());
+    if let Err(e) = written.and_then(|()| writeln!(out, "{}", summary)).and_then(|()| out.flush()) {
+        return write_failed(&e);
+    }
+    if let Some(path) = path
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Wrote {} ({} points) to {}", plural(result.count(), "word"), result.total_score(), path);
+    }
+    if !meets_requirements(opts, &result) {
+        return ExitCode::from(EXIT_BELOW_REQUIREMENT);
+    }
+    ExitCode::SUCCESS
+}
+
+/// The frequency list from --freq, read only when something uses it: the rarity sort or
+/// the machine-readable formats, which report each word's rarity.
+fn load_frequencies(opts: &Options) -> Result<Option<Frequencies>, BoggleError> {
+    match &opts.freq {
+        Some(path) if opts.sort == SortOrder::Rarity || opts.format != Format::Text => {
+            Frequencies::from_path(path).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Writes `result`, which may be a filtered subset of `all`, the full solve.
+fn write_result(
+    out: &mut impl Write,
+    opts: &Options,
+    board: &Board,
+    result: &SolveResult,
+    all: &SolveResult,
+    defs: Option<&Definitions>,
+    freqs: Option<&Frequencies>,
+) -> io::Result<()> {
+    if opts.format == Format::Json {
+        writeln!(out, "{}", to_json_with(board, result, defs, freqs))?;
+    } else if opts.format == Format::Csv {
+        write!(out, "{}", to_csv(result, defs, freqs))?;
+    } else if opts.format == Format::Markdown {
+        write!(out, "{}", to_markdown(board, result))?;
+    } else if opts.show_paths {
+        for found in &result.words {
+            let paths =
+                if opts.count_paths { format!(" ({})", plural(found.paths as usize, "path")) } else { String::new() };
+            writeln!(out, "{}{}{}: {}", found.word, wildcard_mark(found), paths, format_path(board, &found.path))?;
+            for &((r, c), letter) in &found.wildcards {
+                writeln!(out, "    wildcard at {} is {}", format_cell(board, r, c), letter)?;
+            }
+        }
+    } else if opts.show_all {
+        for found in &result.words {
+            writeln!(out, "{}{}", found.word, wildcard_mark(found))?;
+        }
+    } else {
+        if opts.word_filter.is_empty() && opts.through.is_none() {
+            writeln!(out, "Total words found: {}", result.count())?;
+            writeln!(out, "Total score: {}", result.total_score())?;
+        } else {
+            writeln!(out, "Total words found: {} (of {} before filtering)", result.count(), all.count())?;
+            writeln!(out, "Total score: {} (of {} before filtering)", result.total_score(), all.total_score())?;
+        }
+        if opts.bonus.is_some() {
+            let (bonus, base) = (result.total_bonus_score(), result.total_base_score());
+            writeln!(out, "Total bonus score: {} ({} without the bonus cells)", bonus, base)?;
+        }
+        // No limit means every word
+        let n = opts.top.unwrap_or(result.count());
+        if n == 0 {
+            return Ok(());
+        }
+        let top = if opts.by_score {
+            let top = result.highest_scoring(n);
+            writeln!(out, "Highest scoring {}: {:?}", plural(top.len(), "word"), top)?;
+            top
+        } else {
+            let top = result.longest(n);
+            writeln!(out, "Longest {}: {:?}", plural(top.len(), "word"), top)?;
+            top
+        };
+        if let Some(defs) = defs {
+            for word in top {
+                if let Some(definition) = defs.get(word) {
+                    writeln!(out, "  {}: {}", word, definition)?;
+                }
             }
         }
+    }
+    Ok(())
+}
 
-        let rows = board.len() as i32;
-        let cols = board[0].len() as i32;
-        Ok(BoggleSolver { trie, board, rows, cols })
+// Always covers every word found, however few of them --top printed
+fn write_summary(out: &mut impl Write, result: &SolveResult) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "Length  Words")?;
+    for (length, count) in result.histogram() {
+        writeln!(out, "{:>6}  {:>5}", length, count)?;
     }
+    writeln!(out, "Total   {:>5}", result.count())?;
+    writeln!(out, "Average length: {:.2}", result.average_length())?;
+    writeln!(out, "Best word score: {}", result.max_score())
+}
 
-    fn solve(&self) -> (usize, Vec<String>) {
-        let mut found_words = HashSet::new();
-        let mut visited = vec![vec![false; self.cols as usize]; self.rows as usize];
+// The words covering the most cells, then those covering every open cell if any do
+fn write_coverage(out: &mut impl Write, board: &Board, result: &SolveResult, n: usize) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "Most tiles covered:")?;
+    for found in result.most_tiles(n) {
+        writeln!(out, "{:>4}  {}{}", found.tiles_used(), found.word, wildcard_mark(found))?;
+    }
+    let open = board.open_cells();
+    let full: Vec<&str> =
+        result.words.iter().filter(|f| f.tiles_used() == open).map(|f| f.word.as_str()).collect();
+    if full.is_empty() {
+        writeln!(out, "No word covers all {} tiles", open)
+    } else {
+        writeln!(out, "Covering all {} tiles: {:?}", open, full)
+    }
+}
+
+// A tile as printed on the cube ("Qu", "Th"), or '#' for a blocked cell
+fn tile_label(board: &Board, r: usize, c: usize) -> String {
+    let mut chars = board.tile(r, c).chars();
+    chars.next().map_or("#".to_string(), |first| first.to_string() + &chars.as_str().to_lowercase())
+}
+
+// Each tile with its word count underneath; with `color` the counts are shaded from
+// dark to bright red by how hot the cell is
+fn write_heatmap(out: &mut impl Write, board: &Board, result: &SolveResult, color: bool) -> io::Result<()> {
+    const SHADES: [u8; 5] = [52, 88, 124, 160, 196];
+    let counts = result.heatmap(board.rows(), board.cols());
+    let hottest = counts.iter().flatten().copied().max().unwrap_or(0);
+    let width = hottest.to_string().len().max(2);
+
+    writeln!(out)?;
+    writeln!(out, "Words through each cell:")?;
+    for (r, row) in counts.iter().enumerate() {
+        let tiles: Vec<String> = (0..board.cols())
+            .map(|c| {
+                format!("{:<width$}", tile_label(board, r, c))
+            })
+            .collect();
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(c, &count)| {
+                if board.is_blocked(r, c) {
+                    format!("{:<width$}", "-")
+                } else if color && count > 0 {
+                    let shade = SHADES[(count * SHADES.len()).div_ceil(hottest) - 1];
+                    format!("\x1b[38;5;{}m{:<width$}\x1b[0m", shade, count)
+                } else {
+                    format!("{:<width$}", count)
+                }
+            })
+            .collect();
+        writeln!(out, "{}", tiles.join(" ").trim_end())?;
+        writeln!(out, "{}", cells.join(" ").trim_end())?;
+    }
+    Ok(())
+}
+
+// Each tile with the number of words starting there underneath, then with `best` the
+// best of those words in the same layout
+fn write_start_breakdown(
+    out: &mut impl Write,
+    board: &Board,
+    breakdown: &StartBreakdown,
+    best: bool,
+) -> io::Result<()> {
+    let most = breakdown.counts.iter().flatten().copied().max().unwrap_or(0);
+    let width = most.to_string().len().max(2);
+    writeln!(out)?;
+    writeln!(out, "Words starting at each cell:")?;
+    for (r, row) in breakdown.counts.iter().enumerate() {
+        let tiles: Vec<String> = (0..board.cols()).map(|c| format!("{:<width$}", tile_label(board, r, c))).collect();
+        let counts: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(c, count)| {
+                let count = if board.is_blocked(r, c) { "-".to_string() } else { count.to_string() };
+                format!("{:<width$}", count)
+            })
+            .collect();
+        writeln!(out, "{}", tiles.join(" ").trim_end())?;
+        writeln!(out, "{}", counts.join(" ").trim_end())?;
+    }
+    if !best {
+        return Ok(());
+    }
+
+    let width = breakdown.best.iter().flatten().flatten().map(|word| word.chars().count()).max().unwrap_or(0).max(1);
+    writeln!(out)?;
+    writeln!(out, "Best word from each cell:")?;
+    for row in &breakdown.best {
+        let words: Vec<String> = row.iter().map(|word| format!("{:<width$}", word.as_deref().unwrap_or("-"))).collect();
+        writeln!(out, "{}", words.join(" ").trim_end())?;
+    }
+    Ok(())
+}
 
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                self.dfs(r, c, &self.trie, String::new(), &mut visited, &mut found_words);
+/// Where the results go: the `--output` file, or stdout when there is none or it cannot
+/// be created. The path comes back too when the file is being written.
+fn open_output(opts: &Options) -> (Box<dyn Write>, Option<&str>) {
+    if let Some(path) = &opts.output {
+        match File::create(path) {
+            Ok(file) => return (Box::new(BufWriter::new(file)), Some(path)),
+            Err(e) if opts.verbosity > Verbosity::Quiet => {
+                eprintln!("Warning: cannot write {}: {}; printing the results here instead", path, e)
             }
+            Err(_) => {}
         }
+    }
+    (Box::new(io::stdout()), None)
+}
 
-        let count = found_words.len();
-        let mut sorted_words: Vec<String> = found_words.into_iter().collect();
-        sorted_words.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
-        let longest_x = sorted_words.into_iter().take(10).collect();
+fn write_failed(e: &io::Error) -> ExitCode {
+    eprintln!("Error: cannot write the results: {}", e);
+    ExitCode::from(EXIT_FILE_IO)
+}
 
-        (count, longest_x)
+/// Solves one board per stdin line with a single dictionary load, printing
+/// `board<TAB>words<TAB>score<TAB>best word` (or one JSON object or CSV row) per board.
+/// Bad lines are reported and skipped.
+fn run_batch(opts: &Options, parse_opts: &ParseOptions, out: &mut impl Write, path: Option<&str>) -> ExitCode {
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    let cache = match open_cache(opts, &dict_paths) {
+        Ok(cache) => cache,
+        Err(e) => return report(&e),
+    };
+    let mut solver: Option<BoggleSolver> = None;
+    if opts.format == Format::Csv
+        && let Err(e) = writeln!(out, "board,words,score,best")
+    {
+        return write_failed(&e);
     }
+    let mut boards = 0;
 
-    fn dfs(
-        &self,
-        r: i32,
-        c: i32,
-        node: &TrieNode,
-        mut path: String,
-        visited: &mut Vec<Vec<bool>>,
-        found: &mut HashSet<String>,
-    ) {
-        if r < 0 || r >= self.rows || c < 0 || c >= self.cols || visited[r as usize][c as usize] {
-            return;
+    for (i, line) in io::stdin().lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Error reading stdin: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
         }
+        let board = match Board::parse(&line, parse_opts) {
+            Ok(board) => board,
+            Err(e) => {
+                eprintln!("line {}: invalid board: {}", i + 1, e);
+                continue;
+            }
+        };
+
+        let solver = match &mut solver {
+            Some(solver) => match solver.set_board(board) {
+                Ok(()) => solver,
+                // Only a bonus layout can disagree with a board that parsed
+                Err(e) => {
+                    eprintln!("line {}: {}", i + 1, e);
+                    continue;
+                }
+            },
+            None => match load_solver(opts, board, &dict_paths, false) {
+                Ok(loaded) => solver.insert(loaded),
+                Err(e) => return report(&e),
+            },
+        };
 
-        let tile = &self.board[r as usize][c as usize];
-        
-        // Traverse through all characters in the tile (handles "QU" as single tile)
-        let mut current_node = node;
-        for ch in tile.chars() {
-            if let Some(next) = current_node.children.get(&ch) {
-                current_node = next;
-            } else {
-                return; // No matching path in trie
+        let result = match solve_filtered(opts, solver, cache.as_ref()) {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("line {}: {}", i + 1, e);
+                continue;
             }
+        };
+        let best = result.longest(1).first().copied().unwrap_or("-");
+        let written = match opts.format {
+            Format::Json | Format::Ndjson => writeln!(out, "{}", to_json(solver.board(), &result)),
+            Format::Csv => writeln!(out, "{},{},{},{}", line.trim(), result.count(), result.total_score(), best),
+            // Markdown is turned away before any board is read
+            Format::Text | Format::Markdown => {
+                writeln!(out, "{}\t{}\t{}\t{}", line.trim(), result.count(), result.total_score(), best)
+            }
+        };
+        if let Err(e) = written {
+            return write_failed(&e);
+        }
+        boards += 1;
+    }
+    if let Err(e) = out.flush() {
+        return write_failed(&e);
+    }
+    if let Some(path) = path
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Wrote {} to {}", plural(boards, "board"), path);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Fails if --through names a cell off `board`, so the board need not be solved first.
+fn check_through(opts: &Options, board: &Board) -> Result<(), BoggleError> {
+    match opts.through {
+        Some((row, col)) if row >= board.rows() || col >= board.cols() => {
+            Err(BoggleError::CellOffBoard { row, col, rows: board.rows(), cols: board.cols() })
         }
+        _ => Ok(()),
+    }
+}
 
-        visited[r as usize][c as usize] = true;
-        path.push_str(tile);
+/// Solves one board of a run of them and keeps the words that pass the letter filters
+/// and --through.
+fn solve_filtered(
+    opts: &Options,
+    solver: &BoggleSolver,
+    cache: Option<&ResultCache>,
+) -> Result<SolveResult, BoggleError> {
+    check_through(opts, solver.board())?;
+    let result = solve(opts, solver, cache).filter(&opts.word_filter);
+    match opts.through {
+        Some((r, c)) => solver.words_through(&result, r, c),
+        None => Ok(result),
+    }
+}
 
-        if current_node.is_word {
-            found.insert(path.clone());
+/// Plays each board of the file at `path` as a round, all with one loaded dictionary,
+/// and prints a table of the rounds with the series' totals and best word, or with
+/// --json all of it as one document. A board that does not parse is reported in its
+/// round and the series goes on.
+fn tournament(opts: &Options, path: &str) -> ExitCode {
+    let boards = match read_series(path, &parse_options(opts)) {
+        Ok(boards) => boards,
+        Err(e) => return report(&e),
+    };
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    // Boards come from the file; this one only stands in until the first of them
+    let mut solver = match load_solver(opts, Board::from_rows(&["a"]), &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+    let cache = match open_cache(opts, &dict_paths) {
+        Ok(cache) => cache,
+        Err(e) => return report(&e),
+    };
+    let mut series = Series::default();
+    for board in boards {
+        let outcome = board
+            .and_then(|board| solver.set_board(board))
+            .and_then(|()| solve_filtered(opts, &solver, cache.as_ref()))
+            .map(|result| RoundScore::new(solver.board().clone(), &result))
+            .map_err(|e| e.to_string());
+        // The table says which rounds failed; JSON or a file needs telling here
+        if let Err(msg) = &outcome
+            && (opts.format == Format::Json || opts.output.is_some())
+            && opts.verbosity > Verbosity::Quiet
+        {
+            eprintln!("round {}: {}", series.rounds.len() + 1, msg);
         }
+        series.push(outcome);
+    }
+
+    let (mut out, out_path) = open_output(opts);
+    let written = match opts.format {
+        Format::Json => writeln!(out, "{}", to_json_series(&series)),
+        _ => write_series(&mut out, &series),
+    };
+    if let Err(e) = written.and_then(|()| out.flush()) {
+        return write_failed(&e);
+    }
+    if let Some(out_path) = out_path
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Wrote {} to {}", plural(series.rounds.len(), "round"), out_path);
+    }
+    ExitCode::SUCCESS
+}
 
-        for dr in -1..=1 {
-            for dc in -1..=1 {
-                if dr != 0 || dc != 0 {
-                    self.dfs(r + dr, c + dc, current_node, path.clone(), visited, found);
+// One row per round, then the totals and the best word of the series
+fn write_series(out: &mut impl Write, series: &Series) -> io::Result<()> {
+    let boards: Vec<String> = series
+        .rounds
+        .iter()
+        .map(|round| round.outcome.as_ref().map_or_else(|_| String::new(), |played| board_line(&played.board)))
+        .collect();
+    let width = boards.iter().map(String::len).max().unwrap_or(0).max("Board".len());
+    writeln!(out, "Round  {:<width$}  Words  Points  Best word", "Board")?;
+    for (round, board) in series.rounds.iter().zip(&boards) {
+        match &round.outcome {
+            Ok(played) => {
+                let best = played.best.as_ref().map_or("-".to_string(), |(word, n)| format!("{} ({})", word, n));
+                let (words, points) = (played.words, played.score);
+                writeln!(out, "{:>5}  {:<width$}  {:>5}  {:>6}  {}", round.number, board, words, points, best)?;
+            }
+            Err(msg) => writeln!(out, "{:>5}  invalid board: {}", round.number, msg)?,
+        }
+    }
+    let played = series.played().count();
+    let skipped = series.rounds.len() - played;
+    let skipped = if skipped > 0 { format!(" ({} invalid)", skipped) } else { String::new() };
+    writeln!(
+        out,
+        "Total: {} played{}, {}, {}",
+        plural(played, "round"),
+        skipped,
+        plural(series.words(), "word"),
+        plural(series.score() as usize, "point")
+    )?;
+    match series.best() {
+        Some((round, word, points)) => {
+            writeln!(out, "Best word: {} ({}, round {})", word, plural(points as usize, "point"), round)
+        }
+        None => writeln!(out, "Best word: none"),
+    }
+}
+
+#[cfg(feature = "embedded-dict")]
+fn embedded_dict(opts: &Options) -> Result<Dictionary, BoggleError> {
+    let load = LoadOptions { locale: opts.locale, ..LoadOptions::default() };
+    Dictionary::from_reader_with(boggle_solver::EMBEDDED_WORDS.as_bytes(), "<embedded>", opts.filter, &load)
+}
+
+#[cfg(not(feature = "embedded-dict"))]
+fn embedded_dict(_: &Options) -> Result<Dictionary, BoggleError> {
+    unreachable!("find_dict only returns no paths with the embedded-dict feature")
+}
+
+#[cfg(feature = "tui")]
+fn interactive(board: &Board, result: &SolveResult) -> ExitCode {
+    match boggle_solver::explore(board, result) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: cannot start the interactive view: {}", e);
+            ExitCode::from(EXIT_FILE_IO)
+        }
+    }
+}
+
+// parse_args refuses --interactive without the feature
+#[cfg(not(feature = "tui"))]
+fn interactive(_: &Board, _: &SolveResult) -> ExitCode {
+    unreachable!("--interactive is rejected when the tui feature is off")
+}
+
+// The words of each length, shortest first, in the result's order
+fn by_length(words: &[String]) -> BTreeMap<usize, Vec<&str>> {
+    let mut lengths: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for word in words {
+        lengths.entry(word.chars().count()).or_default().push(word);
+    }
+    lengths
+}
+
+/// The --hints-level puzzle: each length's words with their first `revealed` letters.
+fn print_hints(result: &SolveResult, revealed: usize) {
+    let words: Vec<String> = result.words.iter().map(|found| found.word.clone()).collect();
+    for (length, words) in by_length(&words) {
+        println!("{} ({}):", plural(length, "letter"), plural(words.len(), "word"));
+        for word in words {
+            println!("  {}", mask_word(word, revealed));
+        }
+    }
+}
+
+/// The --hints game: the player guesses words on stdin and asks for hints by length;
+/// each hint for a length shows one more letter of its remaining words, up to two, and
+/// then the words themselves.
+fn play_hints(result: &SolveResult, locale: Locale) -> ExitCode {
+    let mut left: Vec<String> = result.words.iter().map(|found| found.word.clone()).collect();
+    let mut revealed: HashMap<usize, usize> = HashMap::new();
+    let mut found = 0;
+    let print_counts = |left: &[String]| {
+        println!("Words left:");
+        for (length, words) in by_length(left) {
+            println!("  {}: {}", plural(length, "letter"), words.len());
+        }
+    };
+    print_counts(&left);
+    let stdin = io::stdin();
+    loop {
+        if left.is_empty() {
+            println!("All {} found!", plural(found, "word"));
+            return ExitCode::SUCCESS;
+        }
+        print!("guess, length for a hint, or q> ");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        // End of input stops the game like q does
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            break;
+        }
+        let input = locale.upper(line.trim());
+        if input.is_empty() {
+            print_counts(&left);
+        } else if input == "Q" {
+            break;
+        } else if let Ok(length) = input.parse::<usize>() {
+            let words = by_length(&left).remove(&length).unwrap_or_default();
+            if words.is_empty() {
+                println!("No {}-letter words left", length);
+                continue;
+            }
+            let level = revealed.entry(length).or_insert(0);
+            *level = (*level + 1).min(3);
+            let shown = if *level == 3 { length } else { *level };
+            for word in words {
+                println!("  {}", mask_word(word, shown));
+            }
+        } else if let Some(i) = left.iter().position(|word| *word == input) {
+            let word = left.remove(i);
+            found += 1;
+            println!("Yes! {} ({}); {} to go", word, plural(score(&word) as usize, "point"), left.len());
+        } else if result.words.iter().any(|word| word.word == input) {
+            println!("{} is already found", input);
+        } else {
+            println!("{} is not on this board", input);
+        }
+    }
+    println!("Found {} of {}", found, result.count());
+    ExitCode::SUCCESS
+}
+
+/// The timed game: the board, then guesses read from stdin and judged as they come
+/// until the time is up or input ends, then every word the board has and how the
+/// player did against it.
+fn play(opts: &Options, solver: &BoggleSolver, result: &SolveResult, out: &mut impl Write) -> ExitCode {
+    let limit = Duration::from_secs_f64(opts.seconds.unwrap_or(PLAY_SECONDS));
+    // A rolled board has already been shown
+    if opts.generate.is_none()
+        && let Err(e) = write_board(out, solver.board())
+    {
+        return write_failed(&e);
+    }
+    let secs = limit.as_secs();
+    println!("You have {}:{:02}. Type one word per line; end input to stop early.", secs / 60, secs % 60);
+
+    // The reading thread may still be waiting on a line when time runs out; it ends with
+    // the process
+    let (send, guesses) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if send.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut game = Game::new(solver);
+    let mut stdout = io::stdout();
+    if let Err(e) = play_timed(&mut game, &guesses, &mut stdout, &SystemClock::start(), limit) {
+        return write_failed(&e);
+    }
+
+    let missed = game.missed(result);
+    let best: Vec<String> = missed
+        .highest_scoring(opts.top.unwrap_or(missed.count()))
+        .iter()
+        .map(|word| format!("{} ({})", word, score(word)))
+        .collect();
+    let found = game.found().len();
+    let percent = (found * 100).checked_div(result.count()).unwrap_or(100);
+    let written = match opts.format {
+        // Every word, marking the player's, unless the formatters are asked for
+        Format::Text if !opts.show_all && !opts.show_paths => writeln!(out, "\nThe board's words:").and_then(|()| {
+            result.words.iter().try_for_each(|found| {
+                let yours = if game.found().contains(&found.word) { "  (yours)" } else { "" };
+                writeln!(out, "  {}{}{}", found.word, wildcard_mark(found), yours)
+            })
+        }),
+        _ => write_result(out, opts, solver.board(), result, result, None, None),
+    }
+    .and_then(|()| {
+        writeln!(out, "You found {} of {} ({}%),", found, plural(result.count(), "word"), percent)?;
+        writeln!(out, "scoring {} of {} points", game.score(), result.total_score())?;
+        if best.is_empty() {
+            writeln!(out, "You missed nothing!")
+        } else {
+            writeln!(out, "Best words you missed: {}", best.join(", "))
+        }
+    })
+    .and_then(|()| out.flush());
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => write_failed(&e),
+    }
+}
+
+/// The pattern listing: every dictionary word the pattern fits, alphabetically.
+fn print_matches(opts: &Options, pattern: &str) -> ExitCode {
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    // Only the dictionary is wanted; the board is never solved
+    let solver = match load_solver(opts, Board::from_rows(&["a"]), &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+    let words = solver.dictionary().matches(pattern);
+    let (mut out, path) = open_output(opts);
+    let written = words.iter().try_for_each(|word| writeln!(out, "{}", word));
+    if let Err(e) = written.and_then(|()| out.flush()) {
+        return write_failed(&e);
+    }
+    if opts.verbosity > Verbosity::Quiet {
+        if words.is_empty() {
+            eprintln!("No word matches {}", solver.dictionary().normalize(pattern));
+        } else if let Some(path) = path {
+            eprintln!("Wrote {} to {}", plural(words.len(), "word"), path);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+const REPL_HELP: &[&str] = &[
+    "board <row1> ... <rowN>  set the board to solve",
+    "solve                    solve it, printing what solve would",
+    "check <word>             whether the word is playable, and its path",
+    "top N                    the N best words, by length or with --by-score by score",
+    "quit                     stop (as does the end of input)",
+];
+
+/// Loads the dictionary once, then runs commands from stdin against one board after
+/// another. A bad command or board is reported and the loop goes on.
+fn repl(opts: &Options) -> ExitCode {
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    // Boards come later; this one only stands in until the first board command
+    let mut solver = match load_solver(opts, Board::from_rows(&["a"]), &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+    let cache = match open_cache(opts, &dict_paths) {
+        Ok(cache) => cache,
+        Err(e) => return report(&e),
+    };
+    let parse_opts = parse_options(opts);
+    let prompt = io::stdin().is_terminal();
+    let mut has_board = false;
+    // The last solve, kept for top until the board changes
+    let mut solved: Option<SolveResult> = None;
+    let mut out = io::stdout();
+    let stdin = io::stdin();
+    loop {
+        if prompt {
+            print!("> ");
+            let _ = out.flush();
+        }
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else { continue };
+        let args: Vec<&str> = words.collect();
+        let written = match (name, args.as_slice()) {
+            ("quit" | "exit", _) => break,
+            ("board", []) => {
+                eprintln!("Error: board needs its rows");
+                Ok(())
+            }
+            ("board", rows) => match Board::from_args(rows, &parse_opts).and_then(|board| solver.set_board(board)) {
+                Ok(()) => {
+                    (has_board, solved) = (true, None);
+                    write_board(&mut out, solver.board())
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(())
+                }
+            },
+            ("solve" | "check" | "top", _) if !has_board => {
+                eprintln!("Error: no board yet; set one with board <row1> ... <rowN>");
+                Ok(())
+            }
+            ("solve", []) => {
+                let result = solve(opts, &solver, cache.as_ref()).filter(&opts.word_filter);
+                let written = write_result(&mut out, opts, solver.board(), &result, &result, None, None);
+                solved = Some(result);
+                written
+            }
+            ("check", [word]) => {
+                print_check(&solver, word);
+                Ok(())
+            }
+            ("top", [n]) => match n.parse::<usize>() {
+                Ok(n) => {
+                    let result = solved
+                        .get_or_insert_with(|| solve(opts, &solver, cache.as_ref()).filter(&opts.word_filter));
+                    let top = if opts.by_score { result.highest_scoring(n) } else { result.longest(n) };
+                    let points = |word: &str| plural(score(word) as usize, "point");
+                    top.iter().try_for_each(|word| writeln!(out, "{} ({})", word, points(word)))
+                }
+                Err(_) => {
+                    eprintln!("Error: top needs a count, such as top 10");
+                    Ok(())
                 }
+            },
+            _ => {
+                eprintln!("Commands:");
+                for help in REPL_HELP {
+                    eprintln!("  {}", help);
+                }
+                Ok(())
+            }
+        };
+        if let Err(e) = written.and_then(|()| out.flush()) {
+            return write_failed(&e);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Loads the dictionary, then answers solve requests on `addr` until killed.
+#[cfg(feature = "serve")]
+fn serve(opts: &Options, addr: &str) -> ExitCode {
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    // Requests bring their own boards; this one only stands in until the first arrives
+    let solver = match load_solver(opts, Board::from_rows(&["a"]), &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+    let listener = match std::net::TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Error: cannot listen on {}: {}", addr, e);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    if opts.verbosity > Verbosity::Quiet {
+        let addr = listener.local_addr().map_or_else(|_| addr.to_string(), |a| a.to_string());
+        eprintln!("Listening on http://{} (POST /solve, GET /health)", addr);
+    }
+    boggle_solver::serve(listener, solver, parse_options(opts));
+    ExitCode::SUCCESS
+}
+
+// parse_args refuses serve without the feature
+#[cfg(not(feature = "serve"))]
+fn serve(_: &Options, _: &str) -> ExitCode {
+    unreachable!("serve is rejected when the serve feature is off")
+}
+
+/// Loads the dictionary and sets up a solver for `board`. With `prune`, only ever this
+/// board is solved and the words it cannot spell are left out of the dictionary.
+fn load_solver(
+    opts: &Options,
+    board: Board,
+    dict_paths: &[PathBuf],
+    prune: bool,
+) -> Result<BoggleSolver<'static>, BoggleError> {
+    let mut dict = if dict_paths.is_empty() {
+        embedded_dict(opts)?
+    } else if opts.cache
+        && opts.excludes.is_empty()
+        && opts.max_dict_words.is_none()
+        && opts.locale == Locale::Default
+        && opts.encoding.is_none()
+    {
+        let mut cache_path = dict_paths[0].as_os_str().to_owned();
+        cache_path.push(".trie");
+        Dictionary::from_paths_cached(dict_paths, cache_path, opts.filter)?
+    } else {
+        let pool = (prune && !opts.no_prune).then(|| LetterPool::new(&board, opts.allow_reuse));
+        Dictionary::from_paths_with(dict_paths, opts.filter, &LoadOptions {
+            pool,
+            max_words: opts.max_dict_words,
+            threads: opts.threads,
+            locale: opts.locale,
+            encoding: opts.encoding,
+        })?
+    };
+    // Asked for no encoding, a file read partly as Latin-1 was a guess worth telling
+    if opts.encoding.is_none() && opts.verbosity > Verbosity::Quiet {
+        for source in dict.sources().iter().filter(|source| source.encoding == Encoding::Latin1) {
+            eprintln!(
+                "Warning: {} is not all UTF-8; read the lines that are not as Latin-1 (--encoding sets it)",
+                source.name
+            );
+        }
+    }
+    if opts.verbosity >= Verbosity::Verbose {
+        for source in dict.sources() {
+            match source.encoding {
+                Encoding::Utf8 => eprintln!("{}: {} new words", source.name, source.new_words),
+                encoding => eprintln!("{}: {} new words, read as {}", source.name, source.new_words, encoding),
+            }
+        }
+        // A cache or an FST needs no reading, so there is nothing to say about lines
+        let load = dict.load_stats();
+        if load.lines_read > 0 {
+            eprintln!(
+                "{} read ({} words): {} skipped for non-letters, {} outside the length filter, {} loaded into {}",
+                plural(load.lines_read, "line"),
+                load.words_read,
+                load.words_skipped,
+                load.words_filtered,
+                load.words_inserted,
+                plural(dict.node_count(), "node")
+            );
+        }
+        if load.words_pruned > 0 {
+            eprintln!("{} words left out, as the board lacks their letters", load.words_pruned);
+        }
+    }
+    // A cached dictionary is already minimized and can no longer drop words, so
+    // exclusions always rebuild from the word lists
+    for path in &opts.excludes {
+        let removed = dict.exclude_path(path)?;
+        if opts.verbosity >= Verbosity::Verbose {
+            eprintln!("{}: {} words excluded", path, removed);
+        }
+    }
+    let mut solver = BoggleSolver::with_dictionary(board, dict)?;
+    solver.set_mode(opts.mode);
+    solver.set_algorithm(opts.algorithm);
+    solver.set_wrap(opts.wrap);
+    solver.set_diagonals(!opts.no_diagonals);
+    solver.set_allow_reuse(opts.allow_reuse);
+    solver.set_count_paths(opts.count_paths);
+    if let Some(path) = &opts.bonus {
+        solver.set_bonus(Some(BonusLayout::from_path(path)?))?;
+    }
+    match opts.backend {
+        Backend::Trie => {}
+        Backend::Dawg => solver.minimize(),
+        Backend::Sorted => solver.sort_words(),
+    }
+    Ok(solver)
+}
+
+/// The --cache-dir cache for solvers loaded from `dict_paths`, if there is one.
+fn open_cache(opts: &Options, dict_paths: &[PathBuf]) -> Result<Option<ResultCache>, BoggleError> {
+    let excludes: Vec<PathBuf> = opts.excludes.iter().map(PathBuf::from).collect();
+    opts.cache_dir.as_ref().map(|dir| ResultCache::new(dir, dict_paths, &excludes)).transpose()
+}
+
+fn solve(opts: &Options, solver: &BoggleSolver, cache: Option<&ResultCache>) -> SolveResult {
+    if let Some(result) = cache.and_then(|cache| cache.get(solver)) {
+        if opts.verbosity >= Verbosity::Verbose {
+            eprintln!("Found in the result cache: {}", plural(result.count(), "word"));
+        }
+        if opts.stats && matches!(opts.format, Format::Json | Format::Ndjson) {
+            eprintln!("{{\"cache\":\"hit\"}}");
+        } else if opts.stats {
+            eprintln!("Result cache:      hit (not solved again)");
+        }
+        return result.sorted_by(opts.sort);
+    }
+    if opts.live {
+        // Streaming is single-threaded and keeps no stats
+        return solver.solve_with(|found| println!("{}{}", found.word, wildcard_mark(found))).sorted_by(opts.sort);
+    }
+    let (result, stats) = match opts.timeout {
+        Some(budget) => solver.solve_with_timeout_stats(budget),
+        None if opts.threads > 1 => solver.solve_parallel_with_stats(opts.threads),
+        None => solver.solve_with_stats(),
+    };
+    if !stats.completed && opts.verbosity > Verbosity::Quiet {
+        eprintln!("Warning: stopped after the time budget; the words below are only those found so far");
+    }
+    if opts.verbosity >= Verbosity::Verbose {
+        let ms = stats.solve_time.as_secs_f64() * 1000.0;
+        eprintln!("Solved in {:.1} ms: {}", ms, plural(stats.words_found, "word"));
+    }
+    if opts.verbosity >= Verbosity::Debug {
+        print_debug(&stats);
+    }
+    if opts.stats && matches!(opts.format, Format::Json | Format::Ndjson) {
+        eprintln!("{}", to_json_stats(&stats));
+    } else if opts.stats {
+        print_stats(&stats);
+        if cache.is_some() {
+            eprintln!("Result cache:      miss");
+        }
+    }
+    // A cut-short search is missing words, so only a finished one is kept. Failing to
+    // write the entry only costs the next run a solve
+    if let Some(cache) = cache
+        && stats.completed
+        && let Err(e) = cache.put(solver, &result)
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Warning: could not write to the result cache {}: {}", cache.dir().display(), e);
+    }
+    result.sorted_by(opts.sort)
+}
+
+// The -vv report: node counts, then a grid of how many words start at each cell
+fn print_debug(stats: &SolveStats) {
+    eprintln!("{} dictionary nodes, {} cells visited", stats.dictionary_nodes, stats.nodes_visited);
+    let trie = &stats.trie;
+    eprintln!(
+        "Dictionary: {}, longest {} letters, {:.2} children per branching node, about {}",
+        plural(trie.words, "word"),
+        trie.max_depth,
+        trie.branching(),
+        format_bytes(trie.heap_bytes)
+    );
+    eprintln!("Words starting at each cell:");
+    for row in &stats.words_by_start {
+        let counts: Vec<String> = row.iter().map(|n| format!("{:>4}", n)).collect();
+        eprintln!("{}", counts.concat());
+    }
+}
+
+// "812 bytes", "3.4 KiB", "12.0 MiB"
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} bytes", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+// On stderr, so it can be combined with machine-readable or --all output
+fn print_stats(stats: &SolveStats) {
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    eprintln!("Dictionary read:   {:.1} ms", ms(stats.load.read_time));
+    match stats.load.build_threads {
+        threads @ 2.. => eprintln!("Trie build:        {:.1} ms on {} threads", ms(stats.load.build_time), threads),
+        _ => eprintln!("Trie build:        {:.1} ms", ms(stats.load.build_time)),
+    }
+    eprintln!("Solve:             {:.1} ms", ms(stats.solve_time));
+    eprintln!("Words inserted:    {}", stats.load.words_inserted);
+    eprintln!("Words pruned:      {}", stats.load.words_pruned);
+    eprintln!("Dictionary nodes:  {}", stats.dictionary_nodes);
+    eprintln!("Trie words:        {}", stats.trie.words);
+    eprintln!("Trie depth:        {}", stats.trie.max_depth);
+    eprintln!("Trie branching:    {:.2}", stats.trie.branching());
+    eprintln!("Trie memory:       {} (estimated)", format_bytes(stats.trie.heap_bytes));
+    eprintln!("DFS nodes visited: {}", stats.nodes_visited);
+    eprintln!("DFS steps pruned:  {} (every word past them already found)", stats.steps_pruned);
+    eprintln!("Words found:       {}", stats.words_found);
+    eprintln!("Search completed:  {}", if stats.completed { "yes" } else { "no" });
+}
+
+/// Resolves the dictionary path, reporting every location tried when none exists.
+/// With the `embedded-dict` feature the built-in list stands in for a missing file,
+/// signalled by an empty path list.
+fn find_dict(opts: &Options) -> Option<Vec<PathBuf>> {
+    match resolve_dict(opts) {
+        Ok(path) => Some(path),
+        Err(_) if cfg!(feature = "embedded-dict") => Some(Vec::new()),
+        Err(tried) => {
+            eprintln!("Error: no dictionary found. Tried:");
+            for path in tried {
+                eprintln!("  {}", path.display());
             }
+            eprintln!("Pass --dict <path> or set BOGGLE_DICT.");
+            None
         }
+    }
+}
 
-        visited[r as usize][c as usize] = false;
+/// Whether `result` offers the words and points `--require-words` and `--require-score`
+/// ask for, saying on stderr which it falls short of unless `-q`.
+fn meets_requirements(opts: &Options, result: &SolveResult) -> bool {
+    let mut met = true;
+    let say = opts.verbosity > Verbosity::Quiet;
+    if let Some(words) = opts.require_words
+        && result.count() < words
+    {
+        if say {
+            eprintln!("Only {} on this board; --require-words asks for {}", plural(result.count(), "word"), words);
+        }
+        met = false;
     }
+    if let Some(points) = opts.require_score
+        && result.total_score() < points
+    {
+        if say {
+            eprintln!("Only {} points on this board; --require-score asks for {}", result.total_score(), points);
+        }
+        met = false;
+    }
+    met
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+/// Builds an FST for `--dict` from the word list at `words`, keeping the words the
+/// length filter allows and leaving out any `--exclude` lists.
+fn build_fst(opts: &Options, words: &str, path: &str) -> ExitCode {
+    let limit = LoadOptions {
+        pool: None,
+        max_words: opts.max_dict_words,
+        threads: opts.threads,
+        locale: opts.locale,
+        encoding: opts.encoding,
+    };
+    let mut dict = match Dictionary::from_paths_with(&[words], opts.filter, &limit) {
+        Ok(dict) => dict,
+        Err(e) => return report(&e),
+    };
+    for exclude in &opts.excludes {
+        if let Err(e) = dict.exclude_path(exclude) {
+            return report(&e);
+        }
+    }
+    let fst = dict.to_fst();
+    if let Err(e) = fst.save(path) {
+        eprintln!("Error: cannot write {}: {}", path, e);
+        return ExitCode::from(EXIT_FILE_IO);
+    }
+    println!(
+        "Wrote {} ({} nodes, {} bytes) to {}",
+        plural(fst.word_count(), "word"),
+        fst.node_count(),
+        fst.byte_len(),
+        path
+    );
+    ExitCode::SUCCESS
+}
+
+/// Prints a user-facing message for `e` and picks the matching exit code.
+fn report(e: &BoggleError) -> ExitCode {
+    if e.is_invalid_board() {
+        eprintln!("Error: invalid board: {}", e);
+        eprintln!("Remember: 'q' or 'qu' counts as one tile (unless --literal-q), as does a group like '(ll)'.");
+        return ExitCode::from(EXIT_BAD_BOARD);
+    }
+    eprintln!("Error: {}", e);
+    match e {
+        BoggleError::BoardIo(_)
+        | BoggleError::BonusIo(_)
+        | BoggleError::DefinitionsIo(_)
+        | BoggleError::FrequenciesIo(_)
+        | BoggleError::WordListIo(_)
+        | BoggleError::ConfigIo(_) => ExitCode::from(EXIT_FILE_IO),
+        BoggleError::InvalidLengthFilter { .. }
+        | BoggleError::CellOffBoard { .. }
+        | BoggleError::BonusShape { .. }
+        | BoggleError::InvalidBonus(_)
+        | BoggleError::InvalidConfig(_) => ExitCode::from(EXIT_USAGE),
+        _ => ExitCode::from(EXIT_DICTIONARY),
+    }
+}
+
+/// Scores each `--player` list against the board, cancelling words found by more than
+/// one player, and prints every player's words and total followed by the winner.
+fn play_round(opts: &Options, solver: &BoggleSolver) -> ExitCode {
+    let players = match opts.players.iter().map(Player::from_path).collect::<Result<Vec<_>, _>>() {
+        Ok(players) => players,
+        Err(e) => return report(&e),
+    };
+    let scores = score_players(solver, &players);
+    let width = players.iter().flat_map(|p| &p.words).map(|w| w.chars().count()).max().unwrap_or(0).max(4);
+    for player in &scores {
+        println!("{}: {}", player.name, plural(player.score as usize, "point"));
+        for word in &player.valid {
+            println!("  {:<width$}  {}", word, score(word));
+        }
+        for (word, others) in &player.duplicates {
+            println!("  {:<width$}  -  duplicate, also found by {}", word, others.join(", "));
+        }
+        for (word, reason) in &player.invalid {
+            println!("  {:<width$}  -  invalid: {}", word, reason);
+        }
+        println!();
+    }
 
-    if args.len() != 5 {
-        eprintln!("Usage: cargo run -- <row1> <row2> <row3> <row4>");
-        eprintln!("Example: cargo run -- srps euim eahw wdzr");
-        eprintln!("Note: 'qu' is treated as a single tile (the Qu tile)");
-        return;
+    let best = scores.iter().map(|p| p.score).max().unwrap_or(0);
+    let winners: Vec<&str> = scores.iter().filter(|p| p.score == best).map(|p| p.name.as_str()).collect();
+    match winners[..] {
+        [winner] => println!("Winner: {} with {}", winner, plural(best as usize, "point")),
+        _ => println!("Tie between {} with {}", winners.join(", "), plural(best as usize, "point")),
     }
+    ExitCode::SUCCESS
+}
 
-    let mut board = Vec::new();
-    for i in 1..5 {
-        let row_str = args[i].to_uppercase();
-        
-        // Parse tiles: "QU" is one tile, other letters are individual tiles
-        let mut tiles: Vec<String> = Vec::new();
-        let chars: Vec<char> = row_str.chars().collect();
-        let mut j = 0;
-        while j < chars.len() {
-            if chars[j] == 'Q' && j + 1 < chars.len() && chars[j + 1] == 'U' {
-                tiles.push("QU".to_string());
-                j += 2;
-            } else {
-                tiles.push(chars[j].to_string());
-                j += 1;
+/// Prints one line per word of the list at `path`, in order, saying whether it counts,
+/// then the number and score of the valid ones. Fails with `EXIT_INVALID_WORDS` if any
+/// word does not count.
+fn judge(solver: &BoggleSolver, path: &str) -> ExitCode {
+    let words = match read_word_list(path) {
+        Ok(words) => words,
+        Err(e) => return report(&e),
+    };
+    let width = words.iter().map(|w| w.chars().count()).max().unwrap_or(0).max(4);
+    let mut valid = HashSet::new();
+    let mut invalid = 0;
+    for word in &words {
+        match judge_word(solver, word) {
+            Ok(()) if !valid.insert(word) => println!("{:<width$}  OK (already listed, counted once)", word),
+            Ok(()) => println!("{:<width$}  OK ({})", word, plural(score(word) as usize, "point")),
+            Err(reason) => {
+                invalid += 1;
+                println!("{:<width$}  {}", word, reason);
             }
         }
-        
-        if tiles.len() != 4 {
-            eprintln!("Error: Each argument must represent exactly 4 tiles (got {}).", tiles.len());
-            eprintln!("Remember: 'qu' counts as one tile.");
-            return;
+    }
+    let points: u32 = valid.iter().map(|word| score(word)).sum();
+    println!();
+    println!("{} valid, {} invalid, {}", plural(valid.len(), "word"), invalid, plural(points as usize, "point"));
+    if invalid > 0 { ExitCode::from(EXIT_INVALID_WORDS) } else { ExitCode::SUCCESS }
+}
+
+/// Rolls boards for `--target-words` or `--target-score` until one lands in the range,
+/// solving each with the whole dictionary, and returns it or else the closest one.
+fn roll_for_target(
+    opts: &Options,
+    dice: &DiceSet,
+    (metric, range): &(Rank, RangeInclusive<usize>),
+) -> Result<Board, ExitCode> {
+    let Some(dict_paths) = find_dict(opts) else { return Err(ExitCode::from(EXIT_DICTIONARY)) };
+    // Any board will do until the first one is rolled
+    let mut solver = load_solver(opts, roll(dice, &mut Rng::new(0)), &dict_paths, false).map_err(|e| report(&e))?;
+    let settings = TargetOptions {
+        metric: *metric,
+        range: range.clone(),
+        attempts: opts.attempts.unwrap_or(TargetOptions::default().attempts),
+        threads: opts.threads,
+    };
+    let targeted = roll_target(&mut solver, dice, &mut Rng::new(seed(opts)), &settings).map_err(|e| report(&e))?;
+    let (result, seed) = (&targeted.candidate.result, targeted.candidate.seed);
+    let unit = if *metric == Rank::Words { "words" } else { "points" };
+    if !targeted.hit && opts.verbosity > Verbosity::Quiet {
+        eprintln!(
+            "Warning: none of the {} rolled had {}..{} {}; this is the closest",
+            plural(targeted.attempts, "board"),
+            range.start(),
+            range.end(),
+            unit
+        );
+    }
+    if opts.verbosity >= Verbosity::Verbose {
+        eprintln!(
+            "Rolled {}; this one has {} and {} (generate --seed {} rolls it alone)",
+            plural(targeted.attempts, "board"),
+            plural(result.count(), "word"),
+            plural(result.total_score() as usize, "point"),
+            seed
+        );
+    }
+    Ok(targeted.candidate.board)
+}
+
+/// The `--seed` value, or else a fresh one from the clock, reported on stderr so the
+/// same board can be rolled again.
+fn seed(opts: &Options) -> u64 {
+    opts.seed.unwrap_or_else(|| {
+        let seed = Rng::from_time().next_u64();
+        if opts.verbosity > Verbosity::Quiet {
+            eprintln!("Seed: {} (pass --seed {} to roll the same board)", seed, seed);
+        }
+        seed
+    })
+}
+
+// The --date, or today's date in UTC
+fn daily_date(opts: &Options) -> Date {
+    opts.date.unwrap_or_else(Date::today)
+}
+
+// A board as one --stdin line: rows split by '/', multi-letter tiles in parentheses
+// except the Qu tile, and blocks as '#'
+fn board_line(board: &Board) -> String {
+    let rows: Vec<String> = board
+        .tiles()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| match tile.chars().count() {
+                    0 => "#".to_string(),
+                    1 => tile.to_lowercase(),
+                    _ if tile == "QU" => "qu".to_string(),
+                    _ => format!("({})", tile.to_lowercase()),
+                })
+                .collect()
+        })
+        .collect();
+    rows.join("/")
+}
+
+/// Rolls `count` boards, solving them all with one loaded dictionary, and prints the
+/// `--keep` best with the seed that rolls each one again.
+fn run_candidates(opts: &Options, dice: &DiceSet, count: usize, out: &mut impl Write, path: Option<&str>) -> ExitCode {
+    let mut rng = Rng::new(seed(opts));
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    // Any board will do until the first candidate is rolled
+    let mut solver = match load_solver(opts, roll(dice, &mut Rng::new(0)), &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+
+    let settings = CandidateOptions {
+        count,
+        keep: opts.keep.unwrap_or(5),
+        rank: opts.rank.unwrap_or_default(),
+        threads: opts.threads,
+    };
+    // Redrawn in place each percent, and only where someone is watching
+    let progress = io::stderr().is_terminal() && count >= 100 && opts.verbosity > Verbosity::Quiet;
+    let on_solved = |solved: usize| {
+        if progress && (solved * 100 / count != (solved - 1) * 100 / count || solved == count) {
+            eprint!("\rSolved {}/{} boards", solved, count);
         }
-        board.push(tiles);
+    };
+    let kept = match best_of(&mut solver, dice, &mut rng, &settings, on_solved) {
+        Ok(kept) => kept,
+        Err(e) => return report(&e),
+    };
+    if progress {
+        eprintln!();
     }
 
-    match BoggleSolver::new(board, "words.txt") {
-        Ok(solver) => {
-            let (total, longest) = solver.solve();
-            println!("Total words found: {}", total);
-            println!("Longest 3 words: {:?}", longest);
+    let mut written = match opts.format {
+        Format::Csv => writeln!(out, "rank,seed,board,words,score,best"),
+        _ => Ok(()),
+    };
+    for (i, candidate) in kept.iter().enumerate() {
+        let result = &candidate.result;
+        let best = result.longest(1).first().copied().unwrap_or("-");
+        written = written.and_then(|()| match opts.format {
+            Format::Json | Format::Ndjson => {
+                writeln!(out, "{{\"seed\":{},\"result\":{}}}", candidate.seed, to_json(&candidate.board, result))
+            }
+            Format::Csv => {
+                let (board, words, points) = (board_line(&candidate.board), result.count(), result.total_score());
+                writeln!(out, "{},{},{},{},{},{}", i + 1, candidate.seed, board, words, points, best)
+            }
+            Format::Text | Format::Markdown => {
+                let (points, words) = (result.total_score(), plural(result.count(), "word"));
+                writeln!(out, "#{}: {} points, {}, best word {}", i + 1, points, words, best)?;
+                writeln!(out, "Roll it again with: generate --size {} --seed {}", dice.size, candidate.seed)?;
+                write_board(out, &candidate.board)
+            }
+        });
+    }
+    if let Err(e) = written.and_then(|()| out.flush()) {
+        return write_failed(&e);
+    }
+    if let Some(path) = path
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Wrote the best {} of {} to {}", plural(kept.len(), "board"), count, path);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Hill-climbs from a rolled board towards a high-scoring one, reusing one loaded
+/// dictionary for every solve, and prints the best board found.
+fn run_optimize(opts: &Options, dice: &DiceSet, out: &mut impl Write, path: Option<&str>) -> ExitCode {
+    let mut rng = Rng::new(seed(opts));
+    let board = roll(dice, &mut rng);
+    let Some(dict_paths) = find_dict(opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+    let mut solver = match load_solver(opts, board, &dict_paths, false) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+
+    let settings = OptimizeOptions {
+        // A time limit alone runs until the time is up
+        iterations: opts.iterations.unwrap_or(if opts.seconds.is_some() { usize::MAX } else { 2000 }),
+        time_limit: opts.seconds.map(Duration::from_secs_f64),
+        threads: opts.threads,
+    };
+    let best = match optimize(&mut solver, &dice.faces(), &mut rng, &settings) {
+        Ok(best) => best,
+        Err(e) => return report(&e),
+    };
+    let written = if opts.format == Format::Json {
+        writeln!(out, "{}", to_json(&best.board, &solver.solve()))
+    } else if opts.format == Format::Markdown {
+        write!(out, "{}", to_markdown(&best.board, &solver.solve()))
+    } else {
+        write_board(out, &best.board)
+            .and_then(|()| writeln!(out, "Total words found: {}", best.words))
+            .and_then(|()| writeln!(out, "Total score: {}", best.score))
+            .and_then(|()| writeln!(out, "Boards tried: {}", best.iterations))
+    };
+    if let Err(e) = written.and_then(|()| out.flush()) {
+        return write_failed(&e);
+    }
+    if let Some(path) = path
+        && opts.verbosity > Verbosity::Quiet
+    {
+        eprintln!("Wrote a board of {} ({} points) to {}", plural(best.words, "word"), best.score, path);
+    }
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let command = env::args().nth(1).and_then(|name| Command::from_name(&name));
+    if command == Some(Command::Help) {
+        print_usage(env::args().nth(2).and_then(|name| Command::from_name(&name)));
+        return ExitCode::SUCCESS;
+    }
+    let opts = match parse_args(command) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            print_usage(command);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    if opts.help {
+        print_usage(command);
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some((words, fst)) = &opts.build_fst {
+        return build_fst(&opts, words, fst);
+    }
+    if let Some(addr) = &opts.serve {
+        return serve(&opts, addr);
+    }
+    if opts.list_dicts {
+        return list_dicts();
+    }
+    if let Some(pattern) = &opts.pattern {
+        return print_matches(&opts, pattern);
+    }
+    if opts.repl {
+        return repl(&opts);
+    }
+    if let Some(path) = &opts.tournament {
+        return tournament(&opts, path);
+    }
+
+    let no_board = opts.rows.is_empty() && opts.board_file.is_none() && !opts.batch && opts.generate.is_none();
+    if opts.optimize.is_none() && no_board {
+        print_usage(command);
+        return ExitCode::from(EXIT_USAGE);
+    }
+
+    let (mut out, path) = open_output(&opts);
+    if let Some(dice) = opts.optimize {
+        return run_optimize(&opts, dice, &mut out, path);
+    }
+    if let (Some(dice), Some(count)) = (opts.generate, opts.candidates) {
+        return run_candidates(&opts, dice, count, &mut out, path);
+    }
+    let parse_opts = parse_options(&opts);
+    if opts.batch {
+        return run_batch(&opts, &parse_opts, &mut out, path);
+    }
+
+    let board = if let Some(dice) = opts.generate {
+        if opts.daily && opts.verbosity > Verbosity::Quiet {
+            eprintln!("Daily puzzle for {}", daily_date(&opts));
+        }
+        let board = match &opts.target {
+            Some(target) => match roll_for_target(&opts, dice, target) {
+                Ok(board) => board,
+                Err(code) => return code,
+            },
+            None => roll(dice, &mut Rng::new(seed(&opts))),
+        };
+        if opts.format == Format::Text
+            && let Err(e) = write_board(&mut out, &board)
+        {
+            return write_failed(&e);
         }
-        Err(e) => eprintln!("Error loading dictionary: {}. Ensure words.txt is in the project root.", e),
+        Ok(board)
+    } else if let Some(path) = &opts.board_file {
+        Board::from_file(path, &parse_opts)
+    } else if !opts.layer_starts.is_empty() {
+        parse_layers(&opts, &parse_opts)
+    } else {
+        Board::from_args(&opts.rows, &parse_opts)
+    };
+    let board = match board {
+        Ok(board) => board,
+        Err(e) => return report(&e),
+    };
+    if opts.canonical {
+        return print_canonical(&board, &mut out);
+    }
+    if let Err(e) = check_through(&opts, &board) {
+        return report(&e);
+    }
+
+    let Some(dict_paths) = find_dict(&opts) else { return ExitCode::from(EXIT_DICTIONARY) };
+
+    // Checking and judging need the words off the board too, to tell the two apart
+    let prune = opts.check.is_none() && opts.judge.is_none() && opts.players.is_empty();
+    let solver = match load_solver(&opts, board, &dict_paths, prune) {
+        Ok(solver) => solver,
+        Err(e) => return report(&e),
+    };
+    let cache = match open_cache(&opts, &dict_paths) {
+        Ok(cache) => cache,
+        Err(e) => return report(&e),
+    };
+    if !opts.players.is_empty() {
+        return play_round(&opts, &solver);
+    }
+    if let Some(path) = &opts.judge {
+        return judge(&solver, path);
     }
-}
\ No newline at end of file
+    if let Some(word) = &opts.show_path {
+        print_word_path(&solver, word, use_color(&opts, path));
+        return ExitCode::SUCCESS;
+    }
+    match &opts.check {
+        Some(word) if opts.explain => print_explanation(&solver, word),
+        Some(word) => print_check(&solver, word),
+        None if opts.format == Format::Ndjson => return stream_ndjson(&opts, &solver, &mut out, path),
+        None => {
+            let all = solve(&opts, &solver, cache.as_ref());
+            let mut result = all.filter(&opts.word_filter);
+            if let Some((r, c)) = opts.through {
+                result = match solver.words_through(&result, r, c) {
+                    Ok(result) => result,
+                    Err(e) => return report(&e),
+                };
+            }
+            let defs = match opts.defs.as_ref().map(Definitions::from_path).transpose() {
+                Ok(defs) => defs,
+                Err(e) => return report(&e),
+            };
+            let freqs = match load_frequencies(&opts) {
+                Ok(freqs) => freqs,
+                Err(e) => return report(&e),
+            };
+            if let Some(freqs) = &freqs
+                && opts.sort == SortOrder::Rarity
+            {
+                result = result.sorted_by_rarity(freqs);
+            }
+            if opts.verbosity >= Verbosity::Verbose && solver.bonus().is_some() {
+                print_breakdowns(&solver, &result);
+            }
+            if opts.interactive {
+                return interactive(solver.board(), &result);
+            }
+            if opts.hints {
+                return play_hints(&result, opts.locale);
+            }
+            if opts.play {
+                return play(&opts, &solver, &result, &mut out);
+            }
+            if let Some(revealed) = opts.hints_level {
+                print_hints(&result, revealed);
+                return ExitCode::SUCCESS;
+            }
+            let color = use_color(&opts, path);
+            let (defs, freqs) = (defs.as_ref(), freqs.as_ref());
+            let mut written = write_result(&mut out, &opts, solver.board(), &result, &all, defs, freqs);
+            if opts.summary && opts.format == Format::Text {
+                written = written.and_then(|()| write_summary(&mut out, &result));
+            }
+            if opts.heatmap && opts.format == Format::Text {
+                written = written.and_then(|()| write_heatmap(&mut out, solver.board(), &result, color));
+            }
+            if opts.start_breakdown {
+                let breakdown = solver.start_breakdown(&result, opts.every_start);
+                let best = opts.verbosity >= Verbosity::Debug;
+                written = written.and_then(|()| write_start_breakdown(&mut out, solver.board(), &breakdown, best));
+            }
+            if opts.coverage && opts.format == Format::Text {
+                let n = opts.top.unwrap_or(result.count());
+                written = written.and_then(|()| write_coverage(&mut out, solver.board(), &result, n));
+            }
+            if let Err(e) = written.and_then(|()| out.flush()) {
+                return write_failed(&e);
+            }
+            if let Some(path) = path
+                && opts.verbosity > Verbosity::Quiet
+            {
+                eprintln!("Wrote {} ({} points) to {}", plural(result.count(), "word"), result.total_score(), path);
+            }
+            if !meets_requirements(&opts, &result) {
+                return ExitCode::from(EXIT_BELOW_REQUIREMENT);
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::board::Board;
+use crate::dice::{DiceSet, roll};
+use crate::error::BoggleError;
+use crate::rng::Rng;
+use crate::solver::{BoggleSolver, SolveResult};
+
+/// Limits for `optimize`. The search stops at whichever limit is reached first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizeOptions {
+    pub iterations: usize,
+    pub time_limit: Option<Duration>,
+    /// Worker threads per solve; 1 solves on the calling thread.
+    pub threads: usize,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        OptimizeOptions { iterations: 2000, time_limit: None, threads: 1 }
+    }
+}
+
+/// The best board `optimize` found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Optimized {
+    pub board: Board,
+    pub words: usize,
+    pub score: u32,
+    /// Boards solved, including the starting one.
+    pub iterations: usize,
+}
+
+// Early on a move that loses this many points is still often accepted; the
+// temperature falls linearly to zero so the end of the run is pure hill-climbing
+const START_TEMPERATURE: f64 = 8.0;
+
+/// Searches for a high-scoring board by simulated annealing, starting from the solver's
+/// current board. Each step replaces one cell with a random tile from `faces` and
+/// re-solves with the already loaded dictionary, unless the new board is a turn or flip
+/// of one already solved (see `Board::fingerprint`), whose score is reused. The solver
+/// is left holding the best board.
+pub fn optimize(
+    solver: &mut BoggleSolver,
+    faces: &[&str],
+    rng: &mut Rng,
+    opts: &OptimizeOptions,
+) -> Result<Optimized, BoggleError> {
+    let start = Instant::now();
+    let solve = |solver: &BoggleSolver| -> SolveResult {
+        if opts.threads > 1 { solver.solve_parallel(opts.threads) } else { solver.solve() }
+    };
+
+    let mut current = solver.board().clone();
+    let first = solve(solver);
+    let mut current_score = first.total_score();
+    let mut best = Optimized { board: current.clone(), words: first.count(), score: current_score, iterations: 1 };
+    if faces.is_empty() {
+        return Ok(best);
+    }
+
+    // Scores of the boards solved so far; a bonus layout does not turn with the board,
+    // so with one every board is solved afresh
+    let symmetric = solver.bonus().is_none();
+    let mut seen: HashMap<u64, (u32, usize)> = HashMap::new();
+    if symmetric {
+        seen.insert(current.fingerprint(), (current_score, first.count()));
+    }
+
+    let (rows, cols) = (current.rows(), current.cols());
+    let mut iterations = 1;
+    while iterations < opts.iterations {
+        let progress = match opts.time_limit {
+            Some(limit) if start.elapsed() >= limit => break,
+            Some(limit) => {
+                (iterations as f64 / opts.iterations as f64).max(start.elapsed().as_secs_f64() / limit.as_secs_f64())
+            }
+            None => iterations as f64 / opts.iterations as f64,
+        };
+        let temperature = START_TEMPERATURE * (1.0 - progress);
+
+        let mut tiles = current.tiles().to_vec();
+        tiles[rng.below(rows)][rng.below(cols)] = faces[rng.below(faces.len())].to_string();
+        let candidate = Board::new(tiles);
+        let fingerprint = candidate.fingerprint();
+        let (score, words) = match seen.get(&fingerprint) {
+            Some(&known) if symmetric => known,
+            _ => {
+                solver.set_board(candidate.clone())?;
+                let result = solve(solver);
+                let known = (result.total_score(), result.count());
+                if symmetric {
+                    seen.insert(fingerprint, known);
+                }
+                known
+            }
+        };
+        iterations += 1;
+
+        let delta = score as f64 - current_score as f64;
+        if delta >= 0.0 || (temperature > 0.0 && unit(rng) < (delta / temperature).exp()) {
+            current = candidate;
+            current_score = score;
+            if current_score > best.score {
+                best.board = current.clone();
+                best.words = words;
+                best.score = current_score;
+            }
+        }
+    }
+
+    best.iterations = iterations;
+    solver.set_board(best.board.clone())?;
+    Ok(best)
+}
+
+// A uniform value in [0, 1)
+fn unit(rng: &mut Rng) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// What `best_of` ranks boards by. Ties go to the other measure, then to the board
+/// rolled first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rank {
+    #[default]
+    Score,
+    Words,
+}
+
+impl FromStr for Rank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "score" => Ok(Rank::Score),
+            "words" => Ok(Rank::Words),
+            _ => Err(format!("unknown ranking '{}' (choose score or words)", s)),
+        }
+    }
+}
+
+/// Settings for `best_of`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateOptions {
+    /// Boards to roll and solve.
+    pub count: usize,
+    /// How many of the best to return.
+    pub keep: usize,
+    pub rank: Rank,
+    /// Worker threads per solve; 1 solves on the calling thread.
+    pub threads: usize,
+}
+
+impl Default for CandidateOptions {
+    fn default() -> Self {
+        CandidateOptions { count: 100, keep: 5, rank: Rank::default(), threads: 1 }
+    }
+}
+
+/// A board `best_of` kept, with everything needed to roll it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub board: Board,
+    /// `roll` with `Rng::new(seed)` and the same dice gives this board.
+    pub seed: u64,
+    pub result: SolveResult,
+}
+
+/// Rolls `opts.count` boards from `dice`, solves each with the solver's dictionary and
+/// rules, and returns the `opts.keep` best by `opts.rank`, best first. Every board gets
+/// its own seed drawn from `rng`, so any one of them can be rolled again on its own. A
+/// board that is a turn or flip of one rolled before is skipped, as it holds the same
+/// words, unless the solver scores by a bonus layout. `on_solved` is called with the
+/// number of boards rolled so far after each one. The solver is left holding the best
+/// board.
+pub fn best_of<F: FnMut(usize)>(
+    solver: &mut BoggleSolver,
+    dice: &DiceSet,
+    rng: &mut Rng,
+    opts: &CandidateOptions,
+    mut on_solved: F,
+) -> Result<Vec<Candidate>, BoggleError> {
+    let key = |candidate: &Candidate| {
+        let (words, score) = (candidate.result.count(), candidate.result.total_score());
+        match opts.rank {
+            Rank::Score => (score as usize, words),
+            Rank::Words => (words, score as usize),
+        }
+    };
+
+    let symmetric = solver.bonus().is_none();
+    let mut seen = HashSet::new();
+    let mut kept: Vec<Candidate> = Vec::with_capacity(opts.keep + 1);
+    for solved in 1..=opts.count {
+        let seed = rng.next_u64();
+        let board = roll(dice, &mut Rng::new(seed));
+        if symmetric && !seen.insert(board.fingerprint()) {
+            on_solved(solved);
+            continue;
+        }
+        solver.set_board(board.clone())?;
+        let result = if opts.threads > 1 { solver.solve_parallel(opts.threads) } else { solver.solve() };
+        let candidate = Candidate { board, seed, result };
+        // Kept sorted best first; a board only gets in by beating the worst kept one
+        if kept.len() < opts.keep || kept.last().is_some_and(|worst| key(&candidate) > key(worst)) {
+            let at = kept.partition_point(|other| key(other) >= key(&candidate));
+            kept.insert(at, candidate);
+            kept.truncate(opts.keep);
+        }
+        on_solved(solved);
+    }
+
+    if let Some(best) = kept.first() {
+        solver.set_board(best.board.clone())?;
+    }
+    Ok(kept)
+}
+
+/// Settings for `roll_target`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetOptions {
+    /// What the range counts: the board's words or its points.
+    pub metric: Rank,
+    /// The words or points wanted, both ends included.
+    pub range: RangeInclusive<usize>,
+    /// Boards to roll before settling for the closest.
+    pub attempts: usize,
+    /// Worker threads per solve; 1 solves on the calling thread.
+    pub threads: usize,
+}
+
+impl Default for TargetOptions {
+    fn default() -> Self {
+        TargetOptions { metric: Rank::Words, range: 0..=usize::MAX, attempts: 1000, threads: 1 }
+    }
+}
+
+/// The board `roll_target` settled on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Targeted {
+    pub candidate: Candidate,
+    /// Boards rolled, the chosen one included.
+    pub attempts: usize,
+    /// Whether the board is in the range; if not, it is the closest one rolled.
+    pub hit: bool,
+}
+
+/// Rolls boards from `dice`, each from its own seed drawn from `rng` as `best_of` does,
+/// until one's word count or score (by `opts.metric`) is in `opts.range`, or
+/// `opts.attempts` boards have been rolled. Then the closest board rolled is returned,
+/// the first of those as close. A turn or flip of a board rolled before counts as an
+/// attempt but is not solved again, unless the solver scores by a bonus layout. The
+/// solver is left holding the chosen board.
+pub fn roll_target(
+    solver: &mut BoggleSolver,
+    dice: &DiceSet,
+    rng: &mut Rng,
+    opts: &TargetOptions,
+) -> Result<Targeted, BoggleError> {
+    let (low, high) = (*opts.range.start(), *opts.range.end());
+    let distance = |result: &SolveResult| {
+        let value = match opts.metric {
+            Rank::Words => result.count(),
+            Rank::Score => result.total_score() as usize,
+        };
+        low.saturating_sub(value) + value.saturating_sub(high)
+    };
+
+    let symmetric = solver.bonus().is_none();
+    let mut seen = HashSet::new();
+    let mut closest: Option<(usize, Candidate)> = None;
+    let mut attempts = 0;
+    while attempts < opts.attempts.max(1) {
+        attempts += 1;
+        let seed = rng.next_u64();
+        let board = roll(dice, &mut Rng::new(seed));
+        if symmetric && !seen.insert(board.fingerprint()) {
+            continue;
+        }
+        solver.set_board(board.clone())?;
+        let result = if opts.threads > 1 { solver.solve_parallel(opts.threads) } else { solver.solve() };
+        let off = distance(&result);
+        if closest.as_ref().is_none_or(|(best, _)| off < *best) {
+            closest = Some((off, Candidate { board, seed, result }));
+        }
+        if off == 0 {
+            break;
+        }
+    }
+
+    // The first roll is never skipped, so there is always a closest board
+    let (off, candidate) = closest.expect("at least one board is rolled");
+    solver.set_board(candidate.board.clone())?;
+    Ok(Targeted { candidate, attempts, hit: off == 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::CLASSIC;
+
+    const WORDS: [&str; 24] = [
+        "the", "and", "are", "ear", "era", "tea", "eat", "ate", "net", "ten", "tan", "ant", "rat", "art", "tar", "sea",
+        "set", "toe", "one", "not", "ton", "tin", "sit", "its",
+    ];
+
+    fn solver() -> BoggleSolver<'static> {
+        BoggleSolver::from_words(roll(&CLASSIC, &mut Rng::new(0)), WORDS).unwrap()
+    }
+
+    fn target(range: RangeInclusive<usize>, attempts: usize) -> Targeted {
+        let opts = TargetOptions { range, attempts, ..TargetOptions::default() };
+        roll_target(&mut solver(), &CLASSIC, &mut Rng::new(98), &opts).unwrap()
+    }
+
+    #[test]
+    fn stops_at_the_first_board_in_range() {
+        // Seed 98's boards have 4, 1, 0, 1, 9, 5, 2, 3, 4, 4, 2, 2 and then 6 words
+        let targeted = target(6..=8, 1000);
+        assert!(targeted.hit);
+        assert_eq!((targeted.attempts, targeted.candidate.result.count()), (13, 6));
+        // The same seed rolls the same boards
+        assert_eq!(target(6..=8, 1000), targeted);
+    }
+
+    #[test]
+    fn settles_for_the_closest_board_when_the_range_is_out_of_reach() {
+        // Far more words than the dictionary has, so every attempt is used
+        let targeted = target(500..=600, 20);
+        assert!(!targeted.hit);
+        assert_eq!(targeted.attempts, 20);
+        assert_eq!(target(500..=600, 20), targeted);
+
+        // The closest is the first board with the most words, rolled as roll_target does
+        let mut rng = Rng::new(98);
+        let solver = solver();
+        let counts: Vec<(usize, u64)> = (0..20)
+            .map(|_| {
+                let seed = rng.next_u64();
+                (solver.with_board(roll(&CLASSIC, &mut Rng::new(seed))).unwrap().solve().count(), seed)
+            })
+            .collect();
+        let most = counts.iter().map(|&(count, _)| count).max().unwrap();
+        let first = counts.iter().find(|&&(count, _)| count == most).unwrap();
+        assert_eq!((targeted.candidate.result.count(), targeted.candidate.seed), *first);
+    }
+}
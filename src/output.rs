@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+
+use crate::board::{Board, WILDCARD};
+use crate::definitions::Definitions;
+use crate::error::BoggleError;
+use crate::frequency::Frequencies;
+use crate::json::Json;
+use crate::score::score;
+use crate::solver::{FoundWord, SolveResult, SolveStats};
+use crate::tournament::Series;
+
+fn board_json(board: &Board) -> Json {
+    Json::Array(
+        board
+            .tiles()
+            .iter()
+            .map(|row| Json::Array(row.iter().map(|tile| Json::from(tile.as_str())).collect()))
+            .collect(),
+    )
+}
+
+// The per-word fields every machine-readable format reports. `paths` is only present
+// when paths were counted, `bonus_score` with a bonus layout, `rarity` when a frequency
+// list was given, and `definition` when a definitions file was; inside it, `None`
+// means no entry.
+struct WordRow<'a> {
+    word: &'a str,
+    length: usize,
+    score: u32,
+    path: &'a [(usize, usize)],
+    wildcards: &'a [((usize, usize), char)],
+    tiles_used: usize,
+    paths: Option<u32>,
+    bonus_score: Option<u32>,
+    rarity: Option<u32>,
+    definition: Option<Option<&'a str>>,
+}
+
+impl<'a> WordRow<'a> {
+    fn new(found: &'a FoundWord, defs: Option<&'a Definitions>, freqs: Option<&Frequencies>) -> Self {
+        WordRow {
+            word: &found.word,
+            length: found.word.chars().count(),
+            score: score(&found.word),
+            path: &found.path,
+            wildcards: &found.wildcards,
+            tiles_used: found.tiles_used(),
+            paths: (found.paths > 0).then_some(found.paths),
+            bonus_score: found.bonus_score,
+            rarity: freqs.map(|freqs| freqs.rarity(&found.word)),
+            definition: defs.map(|defs| defs.get(&found.word)),
+        }
+    }
+
+    fn to_json(&self) -> Json {
+        Json::object(self.fields())
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Json)> {
+        let cell = |(r, c): (usize, usize)| Json::Array(vec![Json::from(r), Json::from(c)]);
+        let path = self.path.iter().map(|&rc| cell(rc)).collect();
+        let wildcards = self
+            .wildcards
+            .iter()
+            .map(|&(rc, letter)| Json::object(vec![("cell", cell(rc)), ("letter", Json::from(letter.to_string()))]))
+            .collect();
+        let mut fields = vec![
+            ("word", Json::from(self.word)),
+            ("length", Json::from(self.length)),
+            ("score", Json::from(self.score)),
+            ("path", Json::Array(path)),
+            ("wildcards", Json::Array(wildcards)),
+            ("tiles_used", Json::from(self.tiles_used)),
+        ];
+        if let Some(paths) = self.paths {
+            fields.push(("paths", Json::from(paths)));
+        }
+        if let Some(bonus_score) = self.bonus_score {
+            fields.push(("bonus_score", Json::from(bonus_score)));
+        }
+        if let Some(rarity) = self.rarity {
+            fields.push(("rarity", Json::from(rarity)));
+        }
+        if let Some(definition) = self.definition {
+            fields.push(("definition", Json::from(definition)));
+        }
+        fields
+    }
+
+    fn to_csv(&self) -> String {
+        let path: Vec<String> = self.path.iter().map(|(r, c)| format!("{}:{}", r, c)).collect();
+        let mut fields = vec![csv_field(self.word), self.length.to_string(), self.score.to_string(), path.join(";")];
+        if let Some(rarity) = self.rarity {
+            fields.push(rarity.to_string());
+        }
+        if let Some(definition) = self.definition {
+            fields.push(csv_field(definition.unwrap_or("")));
+        }
+        fields.join(",")
+    }
+}
+
+// Quotes a CSV field when it holds a comma, quote or line break, doubling inner quotes
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Renders the board and its solve result as a single JSON object. These field names
+/// are an interchange format and stay stable:
+///
+/// - `board`: rows of tiles, `"QU"` for the Qu tile, `"?"` a wildcard, `""` a block
+/// - `count`, `score`: number of words and their total score
+/// - `words`: objects with `word`, `length`, `score`, `path` (`[row, col]` cells from
+///   0), `wildcards` (`{"cell": [row, col], "letter": "E"}` per blank used) and
+///   `tiles_used` (distinct cells on the path), then `paths` (how many paths spell it)
+///   when paths were counted and `bonus_score` when there was a bonus layout
+///
+/// `from_json` reads it back.
+pub fn to_json(board: &Board, result: &SolveResult) -> String {
+    to_json_with(board, result, None, None)
+}
+
+/// Like `to_json`, with a `rarity` field on every word when `freqs` is given (see
+/// `Frequencies::rarity`) and a `definition` field (or `null`) when `defs` is.
+pub fn to_json_with(
+    board: &Board,
+    result: &SolveResult,
+    defs: Option<&Definitions>,
+    freqs: Option<&Frequencies>,
+) -> String {
+    Json::object(vec![
+        ("board", board_json(board)),
+        ("count", Json::from(result.count())),
+        ("score", Json::from(result.total_score())),
+        ("words", Json::Array(result.words.iter().map(|found| WordRow::new(found, defs, freqs).to_json()).collect())),
+    ])
+    .to_string()
+}
+
+/// A solve's `SolveStats` as one JSON object: the times in whole microseconds
+/// (`read_us`, `build_us`, `solve_us`), the counters, and `words_by_start` and
+/// `best_by_start` as arrays of rows, `null` for a cell no word starts at.
+pub fn to_json_stats(stats: &SolveStats) -> String {
+    let micros = |d: std::time::Duration| Json::from(d.as_micros() as u64);
+    let counts = stats.words_by_start.iter().map(|row| Json::Array(row.iter().map(|&n| Json::from(n)).collect()));
+    let trie = Json::object(vec![
+        ("nodes", Json::from(stats.trie.nodes)),
+        ("words", Json::from(stats.trie.words)),
+        ("max_depth", Json::from(stats.trie.max_depth)),
+        // JSON numbers here are whole, so the branching factor is left as its two parts
+        ("branch_nodes", Json::from(stats.trie.branch_nodes)),
+        ("edges", Json::from(stats.trie.edges)),
+        ("heap_bytes", Json::from(stats.trie.heap_bytes)),
+    ]);
+    let best = stats
+        .best_by_start
+        .iter()
+        .map(|row| Json::Array(row.iter().map(|word| Json::from(word.as_deref())).collect()));
+    Json::object(vec![
+        ("read_us", micros(stats.load.read_time)),
+        ("build_us", micros(stats.load.build_time)),
+        ("solve_us", micros(stats.solve_time)),
+        ("words_inserted", Json::from(stats.load.words_inserted)),
+        ("words_pruned", Json::from(stats.load.words_pruned)),
+        ("dictionary_nodes", Json::from(stats.dictionary_nodes)),
+        ("trie", trie),
+        ("nodes_visited", Json::from(stats.nodes_visited)),
+        ("steps_pruned", Json::from(stats.steps_pruned)),
+        ("words_found", Json::from(stats.words_found)),
+        ("completed", Json::from(stats.completed)),
+        ("words_by_start", Json::Array(counts.collect())),
+        ("best_by_start", Json::Array(best.collect())),
+    ])
+    .to_string()
+}
+
+/// One found word as a line of newline-delimited JSON, for streaming words as they are
+/// found: `"type": "word"` and then the fields `to_json_with` gives each word.
+pub fn to_ndjson_word(found: &FoundWord, defs: Option<&Definitions>, freqs: Option<&Frequencies>) -> String {
+    let mut fields = vec![("type", Json::from("word"))];
+    fields.extend(WordRow::new(found, defs, freqs).fields());
+    Json::object(fields).to_string()
+}
+
+/// The line ending an NDJSON stream: `"type": "summary"`, the `board`, and the `count`
+/// and total `score` of the words streamed before it.
+pub fn to_ndjson_summary(board: &Board, count: usize, score: u32) -> String {
+    Json::object(vec![
+        ("type", Json::from("summary")),
+        ("board", board_json(board)),
+        ("count", Json::from(count)),
+        ("score", Json::from(score)),
+    ])
+    .to_string()
+}
+
+/// A tournament as one JSON object:
+///
+/// - `rounds`: one object per round, in order, with its `round` number and either its
+///   `board`, `count` of words, `score` for all of them and `best` word (`{"word",
+///   "score"}` or `null`), or an `error` saying why the board could not be played
+/// - `played`, `count`, `score`: how many rounds were played and their totals
+/// - `best`: the series' best word as `{"word", "score", "round"}`, or `null`
+pub fn to_json_series(series: &Series) -> String {
+    let rounds = series.rounds.iter().map(|round| {
+        let mut fields = vec![("round", Json::from(round.number))];
+        match &round.outcome {
+            Ok(played) => fields.extend([
+                ("board", board_json(&played.board)),
+                ("count", Json::from(played.words)),
+                ("score", Json::from(played.score)),
+                (
+                    "best",
+                    Json::from(played.best.as_ref().map(|(word, points)| {
+                        Json::object(vec![("word", Json::from(word.as_str())), ("score", Json::from(*points))])
+                    })),
+                ),
+            ]),
+            Err(msg) => fields.push(("error", Json::from(msg.as_str()))),
+        }
+        Json::object(fields)
+    });
+    let best = series.best().map(|(round, word, points)| {
+        Json::object(vec![
+            ("word", Json::from(word)),
+            ("score", Json::from(points)),
+            ("round", Json::from(round)),
+        ])
+    });
+    Json::object(vec![
+        ("rounds", Json::Array(rounds.collect())),
+        ("played", Json::from(series.played().count())),
+        ("count", Json::from(series.words())),
+        ("score", Json::from(series.score())),
+        ("best", Json::from(best)),
+    ])
+    .to_string()
+}
+
+/// Reads the output of `to_json` back into its board and result. Only `board` and each
+/// word's `word`, `path` and `wildcards` are read; counts and scores are recomputed.
+/// Every cell must be on the board and every tile a letter, `?` or empty.
+pub fn from_json(text: &str) -> Result<(Board, SolveResult), BoggleError> {
+    let json = Json::parse(text).map_err(BoggleError::InvalidJson)?;
+    let invalid = |msg: String| BoggleError::InvalidJson(msg);
+
+    let rows = json.get("board").and_then(Json::as_array).ok_or_else(|| invalid("missing \"board\" array".into()))?;
+    let mut tiles = Vec::new();
+    for (r, row) in rows.iter().enumerate() {
+        let row = row.as_array().ok_or_else(|| invalid(format!("board row {} is not an array", r + 1)))?;
+        let row = row
+            .iter()
+            .enumerate()
+            .map(|(c, tile)| match tile.as_str() {
+                Some(t) if t == WILDCARD || t.chars().all(char::is_alphabetic) => Ok(t.to_string()),
+                _ => Err(invalid(format!("board row {}, column {}: not a tile", r + 1, c + 1))),
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        tiles.push(row);
+    }
+    let board = Board::new(tiles);
+    board.validate()?;
+
+    let cell = |value: &Json, word: &str| -> Result<(usize, usize), BoggleError> {
+        let rc = match value.as_array() {
+            Some([r, c]) => r.as_int().zip(c.as_int()),
+            _ => None,
+        };
+        match rc {
+            Some((r, c)) if r >= 0 && c >= 0 && (r as usize) < board.rows() && (c as usize) < board.cols() => {
+                Ok((r as usize, c as usize))
+            }
+            _ => Err(invalid(format!("{}: path cells must be [row, col] on the board", word))),
+        }
+    };
+    let words = json.get("words").and_then(Json::as_array).ok_or_else(|| invalid("missing \"words\" array".into()))?;
+    let mut found = Vec::with_capacity(words.len());
+    for entry in words {
+        let word = entry.get("word").and_then(Json::as_str).ok_or_else(|| invalid("a word has no \"word\"".into()))?;
+        let path = match entry.get("path").and_then(Json::as_array) {
+            Some(cells) => cells.iter().map(|rc| cell(rc, word)).collect::<Result<_, _>>()?,
+            None => return Err(invalid(format!("{}: missing \"path\"", word))),
+        };
+        let wildcards = match entry.get("wildcards").and_then(Json::as_array) {
+            Some(blanks) => blanks
+                .iter()
+                .map(|blank| {
+                    let rc = cell(blank.get("cell").unwrap_or(&Json::Null), word)?;
+                    let mut letter = blank.get("letter").and_then(Json::as_str).unwrap_or("").chars();
+                    match (letter.next(), letter.next()) {
+                        (Some(ch), None) => Ok((rc, ch)),
+                        _ => Err(invalid(format!("{}: a wildcard letter must be one character", word))),
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        let paths = entry.get("paths").and_then(Json::as_int).and_then(|n| u32::try_from(n).ok()).unwrap_or(0);
+        let bonus_score = entry.get("bonus_score").and_then(Json::as_int).and_then(|n| u32::try_from(n).ok());
+        found.push(FoundWord { word: word.to_string(), path, wildcards, paths, bonus_score });
+    }
+    Ok((board, SolveResult { words: found }))
+}
+
+/// Renders the found words as CSV: a header, then `word,length,score,path` per word with
+/// the path as `r:c` cells joined by `;`. A `rarity` column is added when `freqs` is given,
+/// and then a `definition` column when `defs` is.
+pub fn to_csv(result: &SolveResult, defs: Option<&Definitions>, freqs: Option<&Frequencies>) -> String {
+    let mut out = String::from("word,length,score,path");
+    if freqs.is_some() {
+        out.push_str(",rarity");
+    }
+    if defs.is_some() {
+        out.push_str(",definition");
+    }
+    out.push('\n');
+    for found in &result.words {
+        out.push_str(&WordRow::new(found, defs, freqs).to_csv());
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders a Markdown report of the board and its words: the board as a code block,
+/// a table of the totals and the longest word, one of the words and points at each
+/// length, and every word grouped by length inside a `<details>` block, folded away
+/// until clicked. Tiles are padded to the widest one, so `Qu` keeps the columns lined up.
+pub fn to_markdown(board: &Board, result: &SolveResult) -> String {
+    // Tiles the way they are printed on the cube ("Qu"), blocks as '#'
+    let shown: Vec<Vec<String>> = board
+        .tiles()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| {
+                    let mut chars = tile.chars();
+                    match chars.next() {
+                        Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                        None => "#".to_string(),
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let width = shown.iter().flatten().map(|tile| tile.chars().count()).max().unwrap_or(1);
+
+    let mut out = String::from("## Board\n\n```\n");
+    for (i, row) in shown.iter().enumerate() {
+        // A blank line between the layers of a 3D board
+        if i > 0 && i % board.layer_rows() == 0 {
+            out.push('\n');
+        }
+        let cells: Vec<String> = row.iter().map(|tile| format!("{:<width$}", tile)).collect();
+        out.push_str(cells.join(" ").trim_end());
+        out.push('\n');
+    }
+    out.push_str("```\n\n## Summary\n\n| | |\n|---|---:|\n");
+    out.push_str(&format!("| Total words | {} |\n", result.count()));
+    out.push_str(&format!("| Total score | {} |\n", result.total_score()));
+    let longest = match result.longest(1).first() {
+        Some(word) => format!("{} ({} letters)", word, word.chars().count()),
+        None => "-".to_string(),
+    };
+    out.push_str(&format!("| Longest word | {} |\n", longest));
+    if result.words.is_empty() {
+        return out;
+    }
+
+    let mut by_length: BTreeMap<usize, Vec<&FoundWord>> = BTreeMap::new();
+    for found in &result.words {
+        by_length.entry(found.word.chars().count()).or_default().push(found);
+    }
+    out.push_str("\n## Words by length\n\n| Letters | Words | Score |\n|---:|---:|---:|\n");
+    for (len, words) in &by_length {
+        let points: u32 = words.iter().map(|found| score(&found.word)).sum();
+        out.push_str(&format!("| {} | {} | {} |\n", len, words.len(), points));
+    }
+    out.push_str(&format!("\n<details>\n<summary>All {} words</summary>\n\n", result.count()));
+    for (len, words) in &by_length {
+        let list: Vec<&str> = words.iter().map(|found| found.word.as_str()).collect();
+        out.push_str(&format!("**{} letters:** {}\n\n", len, list.join(", ")));
+    }
+    out.push_str("</details>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{ParseOptions, Topology};
+    use crate::solver::BoggleSolver;
+
+    #[test]
+    fn results_round_trip_through_json() {
+        let opts = ParseOptions::default();
+        let tiles = [["QU", "Q", "TH"], ["", "?", "E"], ["A", "B", "C"]];
+        let special = Board::new(tiles.iter().map(|row| row.iter().map(|tile| tile.to_string()).collect()).collect());
+        let words = ["cat", "cater", "quest", "squat", "toes", "the", "theme", "bath", "bathe", "cab", "abe", "thee"];
+        for board in [
+            Board::parse_rows(&["cat", "qer", "sto"], &opts).unwrap(),
+            special,
+            Board::parse("acterso", &ParseOptions { topology: Topology::Hex, ..ParseOptions::default() }).unwrap(),
+            Board::from_text("ca\nte\n\nrs\nto\n", &ParseOptions { layered: true, ..ParseOptions::default() }).unwrap(),
+        ] {
+            let result = BoggleSolver::from_words(board.clone(), words).unwrap().solve();
+            assert!(!result.words.is_empty(), "{:?}", board);
+            let (read, read_result) = from_json(&to_json(&board, &result)).unwrap();
+            // The tiles are what is kept; the cache compares them with its own board
+            assert_eq!(read.tiles(), board.tiles());
+            assert_eq!(read_result, result, "{:?}", board);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_json_results() {
+        let err = from_json(r#"{"board": [["A", "B"], "CD"], "words": []}"#).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: board row 2 is not an array");
+        let err = from_json(r#"{"board": [["A", "B"], ["C", "1"]], "words": []}"#).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: board row 2, column 2: not a tile");
+        let err = from_json(r#"{"board": [["A", "B"], ["C"]], "words": []}"#).unwrap_err();
+        assert_eq!(err.to_string(), "row 2 has 1 tiles, expected 2 like the first row");
+        let off_board = r#"{"board": [["A", "B"], ["C", "D"]], "words": [{"word": "AB", "path": [[0, 0], [2, 0]]}]}"#;
+        let err = from_json(off_board).unwrap_err();
+        assert_eq!(err.to_string(), "invalid JSON: AB: path cells must be [row, col] on the board");
+    }
+
+    #[test]
+    fn ndjson_lines_parse_back() {
+        let board = Board::parse_rows(&["cat", "qer", "sto"], &ParseOptions::default()).unwrap();
+        let result = BoggleSolver::from_words(board.clone(), ["cat", "ace", "quest", "rote"]).unwrap().solve();
+        let mut lines: Vec<String> = result.words.iter().map(|found| to_ndjson_word(found, None, None)).collect();
+        lines.push(to_ndjson_summary(&board, result.count(), result.total_score()));
+
+        let text = |json: &Json, key| json.get(key).and_then(Json::as_str).map(str::to_string);
+        let int = |json: &Json, key| json.get(key).and_then(Json::as_int);
+        let cell = |cell: &Json| -> Vec<i64> { cell.as_array().unwrap().iter().map(|n| n.as_int().unwrap()).collect() };
+        let (summary, words) = lines.split_last().unwrap();
+        for (line, found) in words.iter().zip(&result.words) {
+            let json = Json::parse(line).unwrap();
+            assert_eq!(text(&json, "type").as_deref(), Some("word"), "{}", line);
+            assert_eq!(text(&json, "word").as_ref(), Some(&found.word), "{}", line);
+            assert_eq!(int(&json, "length"), Some(found.word.chars().count() as i64), "{}", line);
+            assert_eq!(int(&json, "score"), Some(i64::from(score(&found.word))), "{}", line);
+            let path: Vec<Vec<i64>> = json.get("path").and_then(Json::as_array).unwrap().iter().map(cell).collect();
+            let expected: Vec<Vec<i64>> = found.path.iter().map(|&(r, c)| vec![r as i64, c as i64]).collect();
+            assert_eq!(path, expected, "{}", line);
+        }
+        let json = Json::parse(summary).unwrap();
+        assert_eq!(text(&json, "type").as_deref(), Some("summary"));
+        assert_eq!((int(&json, "count"), int(&json, "score")), (Some(4), Some(5)));
+        let rows = json.get("board").and_then(Json::as_array).unwrap();
+        let rows: Vec<Vec<&str>> =
+            rows.iter().map(|row| row.as_array().unwrap().iter().map(|t| t.as_str().unwrap()).collect()).collect();
+        assert_eq!(rows, [["C", "A", "T"], ["QU", "E", "R"], ["S", "T", "O"]]);
+    }
+
+    #[test]
+    fn renders_a_markdown_report() {
+        let board = Board::parse_rows(&["cat", "qer", "sto"], &ParseOptions::default()).unwrap();
+        let words = ["cat", "cater", "ace", "quest", "squat", "rote"];
+        let result = BoggleSolver::from_words(board.clone(), words).unwrap().solve();
+        let expected = "## Board\n\
+            \n\
+            ```\n\
+            C  A  T\n\
+            Qu E  R\n\
+            S  T  O\n\
+            ```\n\
+            \n\
+            ## Summary\n\
+            \n\
+            | | |\n\
+            |---|---:|\n\
+            | Total words | 6 |\n\
+            | Total score | 9 |\n\
+            | Longest word | CATER (5 letters) |\n\
+            \n\
+            ## Words by length\n\
+            \n\
+            | Letters | Words | Score |\n\
+            |---:|---:|---:|\n\
+            | 3 | 2 | 2 |\n\
+            | 4 | 1 | 1 |\n\
+            | 5 | 3 | 6 |\n\
+            \n\
+            <details>\n\
+            <summary>All 6 words</summary>\n\
+            \n\
+            **3 letters:** ACE, CAT\n\
+            \n\
+            **4 letters:** ROTE\n\
+            \n\
+            **5 letters:** CATER, QUEST, SQUAT\n\
+            \n\
+            </details>\n";
+        assert_eq!(to_markdown(&board, &result), expected);
+
+        // With no words the report stops after the totals
+        let empty = "## Board\n\n```\nC  A  T\nQu E  R\nS  T  O\n```\n\n## Summary\n\n| | |\n|---|---:|\n\
+            | Total words | 0 |\n| Total score | 0 |\n| Longest word | - |\n";
+        assert_eq!(to_markdown(&board, &SolveResult { words: Vec::new() }), empty);
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::board::Board;
+use crate::error::BoggleError;
+use crate::json::Json;
+use crate::output::{from_json, to_json};
+use crate::solver::{BoggleSolver, FoundWord, SolveResult};
+
+// Entries written in another layout, or by another version whose solver may find
+// other words, are never read
+const VERSION: i64 = 1;
+
+/// Solve results kept on disk, one JSON file per board, so a board solved once with
+/// the same dictionary and settings need not be solved again. An entry is found by a
+/// hash of the board's `canonical_form()`, the dictionary files' contents and every
+/// solver setting that changes which words are found, so a turned or flipped board
+/// shares its entry, with the paths turned to match.
+///
+/// Entries are only hints: one that cannot be read, is damaged or was written for
+/// another key or version is treated as missing and overwritten by the next `put`.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    dir: PathBuf,
+    // A hash of the dictionary files, in order
+    dictionary: u64,
+}
+
+impl ResultCache {
+    /// A cache in `dir`, created on the first `put`, for solvers whose dictionary was
+    /// loaded from `dict_paths` (and had the words in `exclude_paths` dropped). No paths
+    /// means the embedded word list, which changes only with the version.
+    pub fn new<P: AsRef<Path>>(
+        dir: impl Into<PathBuf>,
+        dict_paths: &[P],
+        exclude_paths: &[P],
+    ) -> Result<Self, BoggleError> {
+        let mut hash = Fnv::new();
+        for (tag, paths) in [(b'd', dict_paths), (b'x', exclude_paths)] {
+            for path in paths {
+                let path = path.as_ref();
+                let read = |e: io::Error| {
+                    BoggleError::DictionaryIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+                };
+                let mut file = fs::File::open(path).map_err(read)?;
+                let mut buf = vec![0; 64 * 1024];
+                let mut len: u64 = 0;
+                hash.add(&[tag]);
+                loop {
+                    let n = file.read(&mut buf).map_err(read)?;
+                    if n == 0 {
+                        break;
+                    }
+                    hash.add(&buf[..n]);
+                    len += n as u64;
+                }
+                // The length marks where one file ends and the next begins
+                hash.add(&len.to_le_bytes());
+            }
+        }
+        Ok(ResultCache { dir: dir.into(), dictionary: hash.0 })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The result stored for the solver's board and settings, with its paths on this
+    /// board, or `None` if there is no usable entry. Words are in the order `solve`
+    /// returns them.
+    pub fn get(&self, solver: &BoggleSolver) -> Option<SolveResult> {
+        let (key, canonical, from) = self.key(solver);
+        let text = fs::read_to_string(self.entry(&key)).ok()?;
+        let json = Json::parse(&text).ok()?;
+        if json.get("version").and_then(Json::as_int) != Some(VERSION)
+            || json.get("key").and_then(Json::as_str) != Some(key.as_str())
+        {
+            return None;
+        }
+        let (board, result) = from_json(&json.get("result")?.to_string()).ok()?;
+        if board.tiles() != canonical.tiles() {
+            return None;
+        }
+        let place = |(r, c): (usize, usize)| from[r][c];
+        let words = result
+            .words
+            .into_iter()
+            .map(|found| FoundWord {
+                path: found.path.into_iter().map(place).collect(),
+                wildcards: found.wildcards.into_iter().map(|(cell, ch)| (place(cell), ch)).collect(),
+                ..found
+            })
+            .collect();
+        Some(SolveResult { words })
+    }
+
+    /// Stores `result`, a complete solve of the solver's board with its settings,
+    /// replacing any entry already there. The file is written whole and then renamed
+    /// into place, so a reader never sees half of it.
+    pub fn put(&self, solver: &BoggleSolver, result: &SolveResult) -> io::Result<()> {
+        let (key, canonical, from) = self.key(solver);
+        // Paths are stored on the canonical board, where every turn of it finds them
+        let mut to = vec![vec![(0, 0); canonical.cols()]; canonical.rows()];
+        for (r, row) in from.iter().enumerate() {
+            for (c, &(from_r, from_c)) in row.iter().enumerate() {
+                to[from_r][from_c] = (r, c);
+            }
+        }
+        let place = |(r, c): (usize, usize)| to[r][c];
+        let words = result
+            .words
+            .iter()
+            .map(|found| FoundWord {
+                path: found.path.iter().copied().map(place).collect(),
+                wildcards: found.wildcards.iter().map(|&(cell, ch)| (place(cell), ch)).collect(),
+                ..found.clone()
+            })
+            .collect();
+        let stored = Json::parse(&to_json(&canonical, &SolveResult { words }))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let entry = Json::object(vec![
+            ("version", Json::Int(VERSION)),
+            ("key", Json::from(key.as_str())),
+            ("result", stored),
+        ]);
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.entry(&key);
+        let temp = path.with_extension(format!("tmp{}", process::id()));
+        fs::write(&temp, entry.to_string())?;
+        fs::rename(&temp, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+    }
+
+    // The full key, kept in the entry to tell apart boards whose hashes collide, with
+    // the canonical board and where its cells are on the solver's board
+    fn key(&self, solver: &BoggleSolver) -> (String, Board, Vec<Vec<(usize, usize)>>) {
+        let (canonical, from) = solver.board().canonical_cells();
+        let filter = solver.dictionary().filter();
+        let rows: Vec<String> = canonical.tiles().iter().map(|row| row.join(",")).collect();
+        let encodings: Vec<String> = solver.dictionary().sources().iter().map(|s| s.encoding.to_string()).collect();
+        let key = format!(
+            "{} dict={:016x} encodings={} locale={:?} len={}-{} mode={:?} topology={:?} depth={} wrap={} \
+             diagonals={} reuse={} paths={} board={}",
+            env!("CARGO_PKG_VERSION"),
+            self.dictionary,
+            encodings.join(","),
+            solver.dictionary().locale(),
+            filter.min_len,
+            filter.max_len.map_or("".to_string(), |n| n.to_string()),
+            solver.mode(),
+            canonical.topology(),
+            canonical.depth(),
+            solver.wrap(),
+            solver.diagonals(),
+            solver.allow_reuse(),
+            solver.count_paths(),
+            rows.join("/")
+        );
+        (key, canonical, from)
+    }
+
+    fn entry(&self, key: &str) -> PathBuf {
+        let mut hash = Fnv::new();
+        hash.add(key.as_bytes());
+        self.dir.join(format!("{:016x}.json", hash.0))
+    }
+}
+
+// 64-bit FNV-1a, as `Board::fingerprint` uses
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn add(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
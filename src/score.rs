@@ -0,0 +1,50 @@
+/// Official Boggle points for a word, counting letters (so the Qu tile counts as two).
+pub fn score(word: &str) -> u32 {
+    match word.chars().count() {
+        0..=2 => 0,
+        3 | 4 => 1,
+        5 => 2,
+        6 => 3,
+        7 => 5,
+        _ => 11,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::solver::BoggleSolver;
+
+    #[test]
+    fn scores_each_length_bracket() {
+        let cases = [
+            ("", 0),
+            ("AT", 0),
+            ("CAT", 1),
+            ("CATS", 1),
+            ("CATER", 2),
+            ("CATERS", 3),
+            ("CRATERS", 5),
+            ("CRATERED", 11),
+            ("CHARACTERS", 11),
+        ];
+        for (word, points) in cases {
+            assert_eq!(score(word), points, "{}", word);
+        }
+    }
+
+    #[test]
+    fn qu_scores_as_two_letters() {
+        // QUA takes two tiles but is three letters long, and QUOTE five letters on four
+        let board = Board::from_rows(&["qa", "to", "ex"]);
+        let solver = BoggleSolver::from_words(board, ["qua", "quote"]).unwrap();
+        let result = solver.solve();
+        let mut tiles: Vec<(&str, usize)> = result.words.iter().map(|f| (f.word.as_str(), f.path.len())).collect();
+        tiles.sort();
+        assert_eq!(tiles, [("QUA", 2), ("QUOTE", 4)]);
+        assert_eq!(score("QUA"), 1);
+        assert_eq!(score("QUOTE"), 2);
+        assert_eq!(result.total_score(), 3);
+    }
+}
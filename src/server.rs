@@ -0,0 +1,221 @@
+//! A small HTTP/1.1 service answering solve requests, for a web app on the same machine
+//! to call. The dictionary is loaded once, before the first request; each connection is
+//! served on its own thread and closed after one response.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use crate::board::{Board, ParseOptions};
+use crate::json::Json;
+use crate::output::to_json;
+use crate::solver::{BoggleSolver, SolveResult};
+
+// The largest request body read, far more than any board needs
+const MAX_BODY: usize = 64 * 1024;
+
+// A status code and the JSON body sent with it
+type Response = (u16, String);
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+/// Answers requests on `listener` for as long as the process runs:
+///
+/// - `GET /health`: `{"status": "ok", "words": N}`, N being the dictionary's size
+/// - `POST /solve`: a body of `{"board": ["SRPS", "EUIM", ...], "min_len": 3}`, with
+///   `min_len` optional, answered with the result as `to_json` writes it
+///
+/// Boards are parsed with `opts` and solved with `solver`'s dictionary and settings. A
+/// bad board or body gets a 400 with `{"error": "..."}`. Requests are answered side by
+/// side, each board by its own solver borrowing the one dictionary (`with_board`).
+pub fn serve(listener: TcpListener, solver: BoggleSolver<'static>, opts: ParseOptions) {
+    let solver = Arc::new(solver);
+    let opts = Arc::new(opts);
+    // A failed accept, such as running out of file descriptors, drops only that client
+    for stream in listener.incoming().flatten() {
+        let (solver, opts) = (Arc::clone(&solver), Arc::clone(&opts));
+        thread::spawn(move || {
+            // A client that hangs up mid-request only loses its own answer
+            let _ = handle(stream, &solver, &opts);
+        });
+    }
+}
+
+fn handle(stream: TcpStream, solver: &BoggleSolver, opts: &ParseOptions) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (status, body) = match read_request(&mut reader)? {
+        Ok(request) => route(&request, solver, opts),
+        Err(response) => response,
+    };
+    respond(stream, status, &body)
+}
+
+// The request line, the headers up to the blank line, and a body of Content-Length bytes
+fn read_request(reader: &mut impl BufRead) -> io::Result<Result<Request, Response>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Err(error(400, "malformed request line")));
+    };
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            let Ok(n) = value.trim().parse() else {
+                return Ok(Err(error(400, "malformed Content-Length")));
+            };
+            length = n;
+        }
+    }
+    if length > MAX_BODY {
+        return Ok(Err(error(413, "request body is too large")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    // The query string plays no part in routing
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    Ok(Ok(Request { method: method.to_string(), path, body }))
+}
+
+fn route(request: &Request, solver: &BoggleSolver, opts: &ParseOptions) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => {
+            let words = solver.dictionary().load_stats().words_inserted;
+            (200, Json::object(vec![("status", Json::from("ok")), ("words", Json::from(words))]).to_string())
+        }
+        ("POST", "/solve") => solve(&request.body, solver, opts).unwrap_or_else(|msg| error(400, &msg)),
+        (_, "/health") | (_, "/solve") => error(405, "method not allowed"),
+        _ => error(404, "not found"),
+    }
+}
+
+fn solve(body: &[u8], solver: &BoggleSolver, opts: &ParseOptions) -> Result<Response, String> {
+    let text = std::str::from_utf8(body).map_err(|_| "the body is not UTF-8".to_string())?;
+    let json = Json::parse(text).map_err(|e| format!("invalid JSON: {}", e))?;
+    let rows = json.get("board").and_then(Json::as_array).ok_or("expected an object with a \"board\" array")?;
+    let rows: Vec<&str> = rows.iter().map(Json::as_str).collect::<Option<_>>().ok_or("board rows must be strings")?;
+    let board = Board::parse_rows(&rows, opts).map_err(|e| e.to_string())?;
+
+    // Shorter words were never loaded, so min_len can only raise the dictionary's minimum
+    let loaded = solver.dictionary().filter().min_len;
+    let min_len = match json.get("min_len") {
+        None => loaded,
+        Some(n) => n
+            .as_int()
+            .and_then(|n| usize::try_from(n).ok())
+            .filter(|&n| n >= loaded)
+            .ok_or(format!("min_len must be a whole number of at least {}", loaded))?,
+    };
+    let result = solver.with_board(board.clone()).map_err(|e| e.to_string())?.solve();
+    let words = result.words.into_iter().filter(|found| found.word.chars().count() >= min_len).collect();
+    Ok((200, to_json(&board, &SolveResult { words })))
+}
+
+fn error(status: u16, msg: &str) -> Response {
+    (status, Json::object(vec![("error", Json::from(msg))]).to_string())
+}
+
+fn respond(mut stream: TcpStream, status: u16, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Unknown",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::SocketAddr;
+
+    fn start() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let words = ["cat", "cater", "ace", "quest", "squat", "rote"];
+        let solver = BoggleSolver::from_words(Board::new(vec![vec!["A".to_string()]]), words).unwrap();
+        thread::spawn(move || serve(listener, solver, ParseOptions::default()));
+        addr
+    }
+
+    // The status line and body of the answer to `request`
+    fn send(addr: SocketAddr, request: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut answer = String::new();
+        stream.read_to_string(&mut answer).unwrap();
+        let (head, body) = answer.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        assert!(head.contains(&format!("Content-Length: {}\r\n", body.len())), "{}", head);
+        (status, body.to_string())
+    }
+
+    fn post(addr: SocketAddr, path: &str, body: &str) -> (String, String) {
+        send(addr, &format!("POST {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", path, body.len(), body))
+    }
+
+    fn answer(status: &str, body: &str) -> (String, String) {
+        (format!("HTTP/1.1 {}", status), body.to_string())
+    }
+
+    #[test]
+    fn answers_each_route() {
+        let addr = start();
+        assert_eq!(send(addr, "GET /health HTTP/1.1\r\n\r\n"), answer("200 OK", r#"{"status":"ok","words":6}"#));
+
+        let board = Board::parse_rows(&["cat", "qer", "sto"], &ParseOptions::default()).unwrap();
+        let solver = BoggleSolver::from_words(board.clone(), ["cat", "cater", "ace", "quest", "squat", "rote"]);
+        let mut result = solver.unwrap().solve();
+        let body = r#"{"board": ["cat", "qer", "sto"]}"#;
+        assert_eq!(post(addr, "/solve?pretty", body), answer("200 OK", &to_json(&board, &result)));
+        result.words.retain(|found| found.word.chars().count() >= 5);
+        let body = r#"{"board": ["cat", "qer", "sto"], "min_len": 5}"#;
+        assert_eq!(post(addr, "/solve", body), answer("200 OK", &to_json(&board, &result)));
+    }
+
+    #[test]
+    fn refuses_bad_requests() {
+        let addr = start();
+        let huge = format!("POST /solve HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        let cases = [
+            (post(addr, "/solve", r#"{"board": ["c4t"]}"#), "400 Bad Request", "row 1, column 2: '4' is not a letter"),
+            (
+                post(addr, "/solve", r#"{"board": ["cat"], "min_len": 2}"#),
+                "400 Bad Request",
+                "min_len must be a whole number of at least 3",
+            ),
+            (post(addr, "/solve", "{"), "400 Bad Request", "invalid JSON: expected a string key at byte 1"),
+            (send(addr, &huge), "413 Payload Too Large", "request body is too large"),
+            (send(addr, "GET /solve HTTP/1.1\r\n\r\n"), "405 Method Not Allowed", "method not allowed"),
+            (post(addr, "/health", ""), "405 Method Not Allowed", "method not allowed"),
+            (send(addr, "GET /words HTTP/1.1\r\n\r\n"), "404 Not Found", "not found"),
+            (send(addr, "nonsense\r\n\r\n"), "400 Bad Request", "malformed request line"),
+        ];
+        for (got, status, msg) in cases {
+            assert_eq!(got, answer(status, &format!(r#"{{"error":"{}"}}"#, msg)));
+        }
+    }
+}
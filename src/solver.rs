@@ -0,0 +1,2399 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Topology, WILDCARD};
+use crate::bonus::{BonusLayout, ScoreBreakdown, base_score, score_breakdown};
+use crate::dictionary::{Dictionary, LetterPool, Lexicon, LoadStats, TrieStats};
+use crate::error::BoggleError;
+use crate::frequency::Frequencies;
+use crate::score::score;
+use crate::sorted::SortedWords;
+use crate::trie::{WordIndex, WordNode};
+
+// Each found word mapped to the first path that spelled it with the fewest wildcards
+type Found = HashMap<String, FoundWord>;
+
+/// A board and the dictionary to find its words in, with the rules to find them by.
+///
+/// The solver either owns its dictionary (`new`, `with_dictionary`), making it a
+/// `BoggleSolver<'static>` that can be moved anywhere, or borrows one for `'d`
+/// (`Dictionary::solver`, `with_board`), so any number of boards can be solved from one
+/// loaded trie, on as many threads as like, since searching only reads it. Borrowing
+/// rather than sharing an `Arc` keeps reference counting out of the search. The few
+/// calls that change the dictionary (`minimize`, `sort_words`) copy a borrowed one first.
+pub struct BoggleSolver<'d> {
+    dict: Cow<'d, Dictionary>,
+    max_len: usize,
+    board: Board,
+    rows: i32,
+    cols: i32,
+    mode: Mode,
+    wrap: bool,
+    diagonals: bool,
+    allow_reuse: bool,
+    count_paths: bool,
+    bonus: Option<BonusLayout>,
+    algorithm: Algorithm,
+    prune_found: bool,
+    // The words `update_cell` keeps up to date, once it has solved the board
+    tracked: Option<SolveResult>,
+}
+
+/// How words may run across the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Any path through adjacent cells, each cell used at most once per word.
+    #[default]
+    Boggle,
+    /// Straight lines only, in any of the 8 directions (6 on a hex board), as in a
+    /// word-search puzzle.
+    WordSearch,
+    /// Any word the board's letters make, wherever the tiles are, as in a letters round:
+    /// each tile is used at most once, a Qu tile giving both its letters. Words found
+    /// this way have no path, only the wildcard cells they used.
+    Anagram,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "boggle" => Ok(Mode::Boggle),
+            "wordsearch" => Ok(Mode::WordSearch),
+            "anagram" => Ok(Mode::Anagram),
+            _ => Err(format!("unknown mode '{}' (choose boggle, wordsearch or anagram)", s)),
+        }
+    }
+}
+
+/// How a solve looks for words. Both ways find the same words, with the same paths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Follow every path on the board, stepping through the dictionary alongside and
+    /// giving up where no word goes on.
+    #[default]
+    Board,
+    /// Take the dictionary a word at a time and search the board for just that word,
+    /// skipping words the board lacks the letters for (see `LetterPool`). Faster when
+    /// the word list is tiny, such as a themed list of a few hundred words.
+    Words,
+    /// `Words` when the dictionary has few words for the size of the board, else `Board`.
+    Auto,
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "board" => Ok(Algorithm::Board),
+            "words" => Ok(Algorithm::Words),
+            "auto" => Ok(Algorithm::Auto),
+            _ => Err(format!("unknown algorithm '{}' (choose board, words or auto)", s)),
+        }
+    }
+}
+
+// Below this many dictionary words per board cell, `Algorithm::Auto` searches word by word
+const AUTO_WORDS_PER_CELL: usize = 40;
+
+/// A word found on the board and one legal path of `(row, col)` cells that spells it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundWord {
+    pub word: String,
+    pub path: Vec<(usize, usize)>,
+    /// The wildcard cells on the path and the letter each one stood for. A path using
+    /// real letters is preferred, so this is empty unless a wildcard was needed.
+    pub wildcards: Vec<((usize, usize), char)>,
+    /// How many distinct paths spell the word, when the solver counts them (see
+    /// `BoggleSolver::set_count_paths`); 0 when it does not.
+    pub paths: u32,
+    /// The score along `path` when the solver has a bonus layout (see
+    /// `BoggleSolver::set_bonus`), which then picks the path scoring the most.
+    pub bonus_score: Option<u32>,
+}
+
+impl FoundWord {
+    pub fn uses_wildcard(&self) -> bool {
+        !self.wildcards.is_empty()
+    }
+
+    /// Distinct cells on the path. This is the word's letter count only when every tile
+    /// is one letter; a Qu or digraph tile covers one cell for two letters, and with
+    /// `allow_reuse` a cell walked twice counts once.
+    pub fn tiles_used(&self) -> usize {
+        let mut cells = self.path.clone();
+        cells.sort_unstable();
+        cells.dedup();
+        cells.len()
+    }
+}
+
+// The state of one depth-first search: the cells in use, the word, path and wildcard
+// letters so far, everything found, and who to tell about each new word
+struct Search<'a, V> {
+    visited: V,
+    // One buffer for the whole search: tiles are pushed on the way down and truncated
+    // off on the way back, so no step allocates
+    word: String,
+    letters: usize,
+    cells: Vec<(usize, usize)>,
+    wildcards: Vec<((usize, usize), char)>,
+    found: Found,
+    visits: u64,
+    on_found: Option<&'a mut dyn FnMut(&FoundWord)>,
+    deadline: Option<Instant>,
+    // Set once the deadline passes; every loop then unwinds without searching further
+    timed_out: bool,
+    through: Option<Through>,
+    // Whether every path to a word is counted, not just the best one kept
+    count_paths: bool,
+    // With a bonus layout, each cell's letter value and word multiplier
+    bonus: Option<Vec<Vec<(u32, u32)>>>,
+    // Whether to stop at a trie node all of whose words are settled, found with a path
+    // that cannot be bettered: the node of each cell on the path, and by node index, how
+    // many words below each node are settled
+    prune: bool,
+    trail: Vec<Option<usize>>,
+    settled: Vec<u32>,
+    pruned: u64,
+}
+
+// Set when only words whose path passes one cell are wanted: that cell, how many steps
+// each cell is from it, and the longest word, so a path too short to still get there
+// is cut off
+struct Through {
+    cell: usize,
+    distance: Vec<usize>,
+    longest: usize,
+}
+
+impl<V: Visited> Search<'_, V> {
+    fn push_wildcard(&mut self, r: i32, c: i32, ch: char) {
+        self.word.push(ch);
+        self.letters += 1;
+        self.wildcards.push(((r as usize, c as usize), ch));
+    }
+
+    // Marks the cell used and records the word spelled so far if it is one
+    fn arrive<N: WordNode>(&mut self, cell: usize, r: i32, c: i32, node: N) {
+        self.visited.insert(cell);
+        self.cells.push((r as usize, c as usize));
+        if self.prune {
+            self.trail.push(node.subtree().map(|(index, _)| index));
+        }
+
+        if node.is_word() && self.passes_through() {
+            self.record();
+        }
+    }
+
+    // Records the word spelled so far, keeping the better of its paths
+    fn record(&mut self) {
+        let paths = self.found.get(&self.word).map_or(0, |known| known.paths) + u32::from(self.count_paths);
+        let bonus_score = self.bonus_score();
+        if improves(&self.found, &self.word, self.wildcards.len(), bonus_score) {
+            let word = FoundWord {
+                word: self.word.clone(),
+                path: self.cells.clone(),
+                wildcards: self.wildcards.clone(),
+                paths,
+                bonus_score,
+            };
+            if let Some(on_found) = &mut self.on_found
+                && !self.found.contains_key(&word.word)
+            {
+                on_found(&word);
+            }
+            // Only a fewer-wildcards path replaces one, so a path with none is final. A
+            // word settles once, and the nodes of its path are distinct, so no node's
+            // count passes the words below it.
+            if self.prune && word.wildcards.is_empty() {
+                for &index in self.trail.iter().flatten() {
+                    if index >= self.settled.len() {
+                        self.settled.resize(index + 1, 0);
+                    }
+                    self.settled[index] += 1;
+                }
+            }
+            self.found.insert(word.word.clone(), word);
+        } else if let Some(known) = self.found.get_mut(&self.word) {
+            known.paths = paths;
+        }
+    }
+
+    // The letters' values on the path so far, summed, times the word multipliers, plus
+    // the word's plain score, as `score_breakdown` adds them up
+    fn bonus_score(&self) -> Option<u32> {
+        let cells = self.bonus.as_ref()?;
+        let (mut letters, mut multiplier) = (0, 1);
+        for &(r, c) in &self.cells {
+            let (value, word) = cells[r][c];
+            letters += value;
+            multiplier *= word;
+        }
+        Some(letters * multiplier + score(&self.word))
+    }
+
+    // Whether every word below `node` is settled, so stepping onto it finds nothing new
+    fn exhausted<N: WordNode>(&self, node: N) -> bool {
+        self.prune && node.subtree().is_some_and(|(index, words)| self.settled.get(index) == Some(&words))
+    }
+
+    // Undoes `arrive` and the letters pushed for the cell
+    fn leave(&mut self, cell: usize, len: usize, letters: usize, wildcard: bool) {
+        self.cells.pop();
+        if self.prune {
+            self.trail.pop();
+        }
+        self.visited.remove(cell);
+        self.word.truncate(len);
+        self.letters = letters;
+        if wildcard {
+            self.wildcards.pop();
+        }
+    }
+
+    fn new(rows: i32, cols: i32) -> Self {
+        Search {
+            visited: V::with_cells((rows * cols) as usize),
+            word: String::new(),
+            letters: 0,
+            cells: Vec::new(),
+            wildcards: Vec::new(),
+            found: HashMap::new(),
+            visits: 0,
+            on_found: None,
+            deadline: None,
+            timed_out: false,
+            through: None,
+            count_paths: false,
+            bonus: None,
+            prune: false,
+            trail: Vec::new(),
+            settled: Vec::new(),
+            pruned: 0,
+        }
+    }
+
+    // Whether the path so far is one to record: always, unless it must pass a cell it
+    // has not been to
+    fn passes_through(&self) -> bool {
+        self.through.as_ref().is_none_or(|through| self.visited.contains(through.cell))
+    }
+
+    // Whether a path stepping onto `cell` can no longer reach the cell it must pass
+    // before it grows longer than any word
+    fn out_of_reach(&self, cell: usize) -> bool {
+        self.through.as_ref().is_some_and(|through| {
+            !self.visited.contains(through.cell)
+                && self.letters.saturating_add(1).saturating_add(through.distance[cell]) > through.longest
+        })
+    }
+
+    // Counts a step onto a cell. The clock is only read every 1024 steps, which keeps
+    // the deadline check out of the profile.
+    fn visit(&mut self) {
+        self.visits += 1;
+        if self.visits.is_multiple_of(1024)
+            && let Some(deadline) = self.deadline
+        {
+            self.timed_out = Instant::now() >= deadline;
+        }
+    }
+}
+
+// The cells used by the current path, indexed by `r * cols + c`. Boards of up to 64 or
+// 128 cells use a plain bitmask; bigger ones fall back to a flag per cell.
+trait Visited {
+    fn with_cells(cells: usize) -> Self;
+    fn contains(&self, cell: usize) -> bool;
+    fn insert(&mut self, cell: usize);
+    fn remove(&mut self, cell: usize);
+}
+
+impl Visited for u64 {
+    fn with_cells(_: usize) -> Self {
+        0
+    }
+    fn contains(&self, cell: usize) -> bool {
+        self & (1 << cell) != 0
+    }
+    fn insert(&mut self, cell: usize) {
+        *self |= 1 << cell;
+    }
+    fn remove(&mut self, cell: usize) {
+        *self &= !(1 << cell);
+    }
+}
+
+impl Visited for u128 {
+    fn with_cells(_: usize) -> Self {
+        0
+    }
+    fn contains(&self, cell: usize) -> bool {
+        self & (1 << cell) != 0
+    }
+    fn insert(&mut self, cell: usize) {
+        *self |= 1 << cell;
+    }
+    fn remove(&mut self, cell: usize) {
+        *self &= !(1 << cell);
+    }
+}
+
+impl Visited for Vec<bool> {
+    fn with_cells(cells: usize) -> Self {
+        vec![false; cells]
+    }
+    fn contains(&self, cell: usize) -> bool {
+        self[cell]
+    }
+    fn insert(&mut self, cell: usize) {
+        self[cell] = true;
+    }
+    fn remove(&mut self, cell: usize) {
+        self[cell] = false;
+    }
+}
+
+// A move from one cell to another: layers, rows and columns, with the layer always 0
+// off a layered board
+type Step = (i32, i32, i32);
+
+// The 8 neighbours of a cell, row by row, and the 4 of them sharing an edge
+const NEIGHBORS: [Step; 8] =
+    [(0, -1, -1), (0, -1, 0), (0, -1, 1), (0, 0, -1), (0, 0, 1), (0, 1, -1), (0, 1, 0), (0, 1, 1)];
+const ORTHOGONAL: [Step; 4] = [(0, -1, 0), (0, 0, -1), (0, 0, 1), (0, 1, 0)];
+// The same, followed by staying on the cell, for when cells may be reused
+const NEIGHBORS_AND_SELF: [Step; 9] =
+    [(0, -1, -1), (0, -1, 0), (0, -1, 1), (0, 0, -1), (0, 0, 1), (0, 1, -1), (0, 1, 0), (0, 1, 1), (0, 0, 0)];
+const ORTHOGONAL_AND_SELF: [Step; 5] = [(0, -1, 0), (0, 0, -1), (0, 0, 1), (0, 1, 0), (0, 0, 0)];
+// The 6 neighbours of a hex cell in the axial grid: the square ones less the diagonal
+// through the two blocked corners
+const HEX_NEIGHBORS: [Step; 6] = [(0, -1, 0), (0, -1, 1), (0, 0, -1), (0, 0, 1), (0, 1, -1), (0, 1, 0)];
+const HEX_NEIGHBORS_AND_SELF: [Step; 7] =
+    [(0, -1, 0), (0, -1, 1), (0, 0, -1), (0, 0, 1), (0, 1, -1), (0, 1, 0), (0, 0, 0)];
+// The 26 neighbours of a cell on a layered board, layer by layer, and the 6 of them
+// sharing a face; again with staying put last for reused cells
+const LAYER_NEIGHBORS: [Step; 26] = layer_steps();
+const LAYER_ORTHOGONAL: [Step; 6] = [(-1, 0, 0), (0, -1, 0), (0, 0, -1), (0, 0, 1), (0, 1, 0), (1, 0, 0)];
+const LAYER_NEIGHBORS_AND_SELF: [Step; 27] = layer_steps();
+const LAYER_ORTHOGONAL_AND_SELF: [Step; 7] =
+    [(-1, 0, 0), (0, -1, 0), (0, 0, -1), (0, 0, 1), (0, 1, 0), (1, 0, 0), (0, 0, 0)];
+
+// Every step to the 3 x 3 x 3 block around a cell but the cell itself, in order; a 27th
+// entry is left as staying put
+const fn layer_steps<const N: usize>() -> [Step; N] {
+    let mut steps = [(0, 0, 0); N];
+    let (mut n, mut i) = (0, 0);
+    while n < 27 {
+        if n != 13 {
+            steps[i] = (n / 9 - 1, n / 3 % 3 - 1, n % 3 - 1);
+            i += 1;
+        }
+        n += 1;
+    }
+    steps
+}
+
+// One cell on the current path: the dictionary node reached through it, the word length
+// to restore when leaving it, and the next neighbour to try. A wildcard cell also holds
+// the letters it has yet to stand for.
+struct Frame<N> {
+    r: i32,
+    c: i32,
+    cell: usize,
+    node: N,
+    len: usize,
+    letters: usize,
+    neighbor: usize,
+    wildcard: bool,
+    choices: Vec<(char, N)>,
+}
+
+/// Where the time went in a solve, and how much work the search did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveStats {
+    pub load: LoadStats,
+    /// Nodes in the trie or DAWG that was searched.
+    pub dictionary_nodes: usize,
+    /// The searched dictionary's size and shape (see `Dictionary::stats`).
+    pub trie: TrieStats,
+    pub solve_time: Duration,
+    /// Board cells the DFS stepped onto, including ones that led nowhere.
+    pub nodes_visited: u64,
+    /// Steps onto a cell the DFS did not take because every word past it had already
+    /// been found (see `BoggleSolver::set_prune_found`).
+    pub steps_pruned: u64,
+    pub words_found: usize,
+    /// How many of the found words start at each cell, by row then column. A word is
+    /// counted once, at the start of the path it was reported with: the first path the
+    /// search found, unless a later one needed fewer wildcards or scored more.
+    pub words_by_start: Vec<Vec<usize>>,
+    /// The best of the words counted at each cell (see `StartBreakdown`).
+    pub best_by_start: Vec<Vec<Option<String>>>,
+    /// False when a time budget ran out first; the words found so far are still correct.
+    pub completed: bool,
+}
+
+/// How many found words start at each cell, by row then column, and the best of them:
+/// the one scoring the most (by its bonus score when there is one), ties going to the
+/// first alphabetically. From `BoggleSolver::start_breakdown`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartBreakdown {
+    pub counts: Vec<Vec<usize>>,
+    pub best: Vec<Vec<Option<String>>>,
+}
+
+impl StartBreakdown {
+    // Counts each word at each cell it is paired with
+    fn tally<'a>(rows: usize, cols: usize, starts: impl Iterator<Item = (&'a FoundWord, (usize, usize))>) -> Self {
+        let mut breakdown = StartBreakdown { counts: vec![vec![0; cols]; rows], best: vec![vec![None; cols]; rows] };
+        let mut best_points = vec![vec![0; cols]; rows];
+        for (found, (r, c)) in starts {
+            breakdown.counts[r][c] += 1;
+            let points = found.bonus_score.unwrap_or_else(|| score(&found.word));
+            let best = &mut breakdown.best[r][c];
+            let beats = best.as_ref().is_none_or(|word| {
+                points > best_points[r][c] || (points == best_points[r][c] && found.word < *word)
+            });
+            if beats {
+                *best = Some(found.word.clone());
+                best_points[r][c] = points;
+            }
+        }
+        breakdown
+    }
+}
+
+// Whether a path for `word` using `wildcards` blanks beats the one already recorded: by
+// scoring more when there is a bonus layout, otherwise by needing fewer blanks
+fn improves(found: &Found, word: &str, wildcards: usize, bonus_score: Option<u32>) -> bool {
+    found.get(word).is_none_or(|known| match (bonus_score, known.bonus_score) {
+        (Some(score), Some(best)) => score > best,
+        _ => wildcards < known.wildcards.len(),
+    })
+}
+
+// A bonus layout must cover the board cell for cell
+fn check_bonus(board: &Board, layout: &BonusLayout) -> Result<(), BoggleError> {
+    if (layout.rows(), layout.cols()) != (board.rows(), board.cols()) {
+        return Err(BoggleError::BonusShape {
+            expected: (board.rows(), board.cols()),
+            found: (layout.rows(), layout.cols()),
+        });
+    }
+    Ok(())
+}
+
+/// How the words on a board changed when `BoggleSolver::update_cell` changed one of its
+/// tiles, each list sorted by length descending then alphabetically.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveDelta {
+    /// Words the board did not have before, with a path through the changed cell.
+    pub added: Vec<FoundWord>,
+    /// Words the board no longer has, with the paths they had.
+    pub removed: Vec<FoundWord>,
+}
+
+/// Words found on a board, sorted by length descending then alphabetically unless
+/// re-sorted with `sorted_by`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveResult {
+    pub words: Vec<FoundWord>,
+}
+
+/// How to order a result's word list. Ties always break alphabetically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    Alpha,
+    /// Most letters first.
+    #[default]
+    Length,
+    /// Highest Boggle score first.
+    Score,
+    /// Rarest first, by a frequency list; see `sorted_by_rarity`.
+    Rarity,
+    /// Most paths first, as counted with `BoggleSolver::set_count_paths`.
+    Paths,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "alpha" => Ok(SortOrder::Alpha),
+            "length" => Ok(SortOrder::Length),
+            "score" => Ok(SortOrder::Score),
+            "rarity" => Ok(SortOrder::Rarity),
+            "paths" => Ok(SortOrder::Paths),
+            _ => Err(format!("unknown sort order '{}' (choose alpha, length, score, rarity or paths)", s)),
+        }
+    }
+}
+
+/// Conditions a found word must meet to be kept by `SolveResult::filter`. Every
+/// condition set must hold; comparisons ignore case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordFilter {
+    prefix: Option<String>,
+    suffix: Option<String>,
+    infix: Option<String>,
+    // Letters the word must use somewhere, as in a Spelling Bee's centre letter
+    letters: Vec<char>,
+}
+
+impl WordFilter {
+    pub fn new() -> Self {
+        WordFilter::default()
+    }
+
+    pub fn starts_with(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_uppercase());
+        self
+    }
+
+    pub fn ends_with(mut self, suffix: &str) -> Self {
+        self.suffix = Some(suffix.to_uppercase());
+        self
+    }
+
+    pub fn contains(mut self, infix: &str) -> Self {
+        self.infix = Some(infix.to_uppercase());
+        self
+    }
+
+    /// Also requires the letter `ch`; each letter required this way must appear.
+    pub fn requires_letter(mut self, ch: char) -> Self {
+        self.letters.extend(ch.to_uppercase());
+        self
+    }
+
+    /// The required prefix, if any; a search can start from that subtree instead of the root.
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Whether the filter keeps every word.
+    pub fn is_empty(&self) -> bool {
+        self.prefix.is_none() && self.suffix.is_none() && self.infix.is_none() && self.letters.is_empty()
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        self.prefix.as_ref().is_none_or(|p| word.starts_with(p.as_str()))
+            && self.suffix.as_ref().is_none_or(|s| word.ends_with(s.as_str()))
+            && self.infix.as_ref().is_none_or(|i| word.contains(i.as_str()))
+            && self.letters.iter().all(|&ch| word.contains(ch))
+    }
+}
+
+impl SolveResult {
+    pub fn count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// The words that pass `filter`, in the same order. The original result keeps the
+    /// unfiltered totals.
+    pub fn filter(&self, filter: &WordFilter) -> SolveResult {
+        SolveResult { words: self.words.iter().filter(|f| filter.matches(&f.word)).cloned().collect() }
+    }
+
+    /// The `n` longest words, ties broken alphabetically, whatever order the list is in.
+    pub fn longest(&self, n: usize) -> Vec<&str> {
+        let mut words: Vec<&str> = self.words.iter().map(|f| f.word.as_str()).collect();
+        words.sort_by_cached_key(|w| (Reverse(w.chars().count()), *w));
+        words.truncate(n);
+        words
+    }
+
+    /// Sorts the words by `order`. Without a frequency list every word is as rare as any
+    /// other, so `Rarity` sorts alphabetically; `sorted_by_rarity` takes the list.
+    pub fn sorted_by(mut self, order: SortOrder) -> Self {
+        match order {
+            SortOrder::Alpha | SortOrder::Rarity => self.words.sort_by(|a, b| a.word.cmp(&b.word)),
+            // Length in letters, not bytes, so accented words sort correctly
+            SortOrder::Length => {
+                self.words.sort_by_cached_key(|f| (Reverse(f.word.chars().count()), f.word.clone()))
+            }
+            SortOrder::Score => self.words.sort_by_cached_key(|f| (Reverse(score(&f.word)), f.word.clone())),
+            SortOrder::Paths => self.words.sort_by_cached_key(|f| (Reverse(f.paths), f.word.clone())),
+        }
+        self
+    }
+
+    /// Sorts the words rarest first by `freqs`, words it does not list leading.
+    pub fn sorted_by_rarity(mut self, freqs: &Frequencies) -> Self {
+        self.words.sort_by_cached_key(|f| (Reverse(freqs.rarity(&f.word)), f.word.clone()));
+        self
+    }
+
+    pub fn total_score(&self) -> u32 {
+        self.words.iter().map(|f| score(&f.word)).sum()
+    }
+
+    /// The words' bonus scores added up, for a result solved with a bonus layout.
+    pub fn total_bonus_score(&self) -> u32 {
+        self.words.iter().filter_map(|f| f.bonus_score).sum()
+    }
+
+    /// What `total_bonus_score` would be if no cell had a bonus (see `base_score`).
+    pub fn total_base_score(&self) -> u32 {
+        self.words.iter().map(base_score).sum()
+    }
+
+    /// How many words there are of each length in letters, shortest first.
+    pub fn histogram(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for found in &self.words {
+            *counts.entry(found.word.chars().count()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Mean word length in letters, or 0 when nothing was found.
+    pub fn average_length(&self) -> f64 {
+        if self.words.is_empty() {
+            return 0.0;
+        }
+        let letters: usize = self.words.iter().map(|f| f.word.chars().count()).sum();
+        letters as f64 / self.words.len() as f64
+    }
+
+    /// For each cell of a `rows` x `cols` board, how many words pass through it. Each word
+    /// counts along the one path recorded for it, even if the board spells it another
+    /// way too, and counts a cell once however often the path visits it.
+    pub fn heatmap(&self, rows: usize, cols: usize) -> Vec<Vec<usize>> {
+        let mut counts = vec![vec![0; cols]; rows];
+        let mut seen = vec![vec![false; cols]; rows];
+        for found in &self.words {
+            for &(r, c) in &found.path {
+                if r < rows && c < cols && !std::mem::replace(&mut seen[r][c], true) {
+                    counts[r][c] += 1;
+                }
+            }
+            for &(r, c) in &found.path {
+                if r < rows && c < cols {
+                    seen[r][c] = false;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Score of the best single word, or 0 when nothing was found.
+    pub fn max_score(&self) -> u32 {
+        self.words.iter().map(|f| score(&f.word)).max().unwrap_or(0)
+    }
+
+    /// The `n` words covering the most cells (see `FoundWord::tiles_used`), ties broken
+    /// alphabetically.
+    pub fn most_tiles(&self, n: usize) -> Vec<&FoundWord> {
+        let mut words: Vec<&FoundWord> = self.words.iter().collect();
+        words.sort_by_cached_key(|f| (Reverse(f.tiles_used()), f.word.clone()));
+        words.truncate(n);
+        words
+    }
+
+    /// The `n` highest-scoring words, ties broken alphabetically.
+    pub fn highest_scoring(&self, n: usize) -> Vec<&str> {
+        let mut words: Vec<&str> = self.words.iter().map(|f| f.word.as_str()).collect();
+        words.sort_by(|a, b| score(b).cmp(&score(a)).then(a.cmp(b)));
+        words.truncate(n);
+        words
+    }
+}
+
+impl BoggleSolver<'static> {
+    pub fn new<P: AsRef<Path>>(board: Board, dict_path: P) -> Result<Self, BoggleError> {
+        BoggleSolver::with_dictionary(board, Dictionary::from_path(dict_path)?)
+    }
+
+    /// Builds a solver from an in-memory word list instead of a dictionary file.
+    pub fn from_words<I, S>(board: Board, words: I) -> Result<Self, BoggleError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        BoggleSolver::with_dictionary(board, Dictionary::from_words(words))
+    }
+
+    pub fn with_dictionary(board: Board, dict: Dictionary) -> Result<Self, BoggleError> {
+        BoggleSolver::with_words(board, Cow::Owned(dict))
+    }
+}
+
+impl<'d> BoggleSolver<'d> {
+    // A solver with the default rules
+    pub(crate) fn with_words(board: Board, dict: Cow<'d, Dictionary>) -> Result<Self, BoggleError> {
+        board.validate()?;
+        let rows = board.rows() as i32;
+        let cols = board.cols() as i32;
+        let max_len = dict.filter().max_len.unwrap_or(usize::MAX);
+        Ok(BoggleSolver {
+            dict,
+            max_len,
+            board,
+            rows,
+            cols,
+            mode: Mode::default(),
+            wrap: false,
+            diagonals: true,
+            allow_reuse: false,
+            count_paths: false,
+            bonus: None,
+            algorithm: Algorithm::default(),
+            prune_found: true,
+            tracked: None,
+        })
+    }
+
+    /// Another solver for `board` searching the same dictionary, borrowed rather than
+    /// copied, under the same rules and bonus layout. The tracked words of `update_cell`
+    /// stay behind.
+    pub fn with_board(&self, board: Board) -> Result<BoggleSolver<'_>, BoggleError> {
+        let mut solver = BoggleSolver::with_words(board, Cow::Borrowed(&*self.dict))?;
+        solver.mode = self.mode;
+        solver.wrap = self.wrap;
+        solver.diagonals = self.diagonals;
+        solver.allow_reuse = self.allow_reuse;
+        solver.count_paths = self.count_paths;
+        solver.algorithm = self.algorithm;
+        solver.prune_found = self.prune_found;
+        solver.set_bonus(self.bonus.clone())?;
+        Ok(solver)
+    }
+
+    /// Replaces the trie with its minimized DAWG. Results are unchanged; memory use drops.
+    pub fn minimize(&mut self) {
+        self.dict.to_mut().minimize();
+    }
+
+    /// Replaces the dictionary structure with a sorted word list; see `Dictionary::sort_words`.
+    pub fn sort_words(&mut self) {
+        self.dict.to_mut().sort_words();
+    }
+
+    /// Number of nodes in the dictionary structure currently used for the search.
+    pub fn node_count(&self) -> usize {
+        self.dict.node_count()
+    }
+
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dict
+    }
+
+    /// Gives back the loaded dictionary, dropping the board; a borrowed one is copied.
+    pub fn into_dictionary(self) -> Dictionary {
+        self.dict.into_owned()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.tracked = None;
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Chooses how `solve` and its variants look for words. The words found are the
+    /// same either way, so only the time taken changes.
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    // Whether to look for the dictionary's words one at a time
+    fn word_major(&self) -> bool {
+        match self.algorithm {
+            Algorithm::Board => false,
+            Algorithm::Words => true,
+            Algorithm::Auto => {
+                let cells = self.board.rows() * self.board.cols();
+                self.dict.load_stats().words_inserted < AUTO_WORDS_PER_CELL.saturating_mul(cells)
+            }
+        }
+    }
+
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Makes the board a torus: the left and right edges are adjacent, and so are the top
+    /// and bottom. Paths then step straight across an edge to the opposite side. A hex
+    /// board never wraps.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.tracked = None;
+    }
+
+    pub fn diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    /// With `false`, words may only step to the 4 cells sharing an edge, not diagonally.
+    /// Every neighbour of a hex cell shares an edge, so hex boards ignore this.
+    pub fn set_diagonals(&mut self, diagonals: bool) {
+        self.diagonals = diagonals;
+        self.tracked = None;
+    }
+
+    pub fn allow_reuse(&self) -> bool {
+        self.allow_reuse
+    }
+
+    /// A house rule: with `true`, a word may use the same cell any number of times, even
+    /// twice running, so one E spells the EE of PEEP. Only Boggle mode is affected; a
+    /// word-search line is straight and never comes back to a cell anyway.
+    pub fn set_allow_reuse(&mut self, allow_reuse: bool) {
+        self.allow_reuse = allow_reuse;
+        self.tracked = None;
+    }
+
+    pub fn count_paths(&self) -> bool {
+        self.count_paths
+    }
+
+    /// With `true`, each found word's `paths` counts every distinct path that spells it.
+    /// The search already walks every path, so this only adds a count per path found,
+    /// plus a check on wrapped boards that two steps do not land on the same cell.
+    pub fn set_count_paths(&mut self, count_paths: bool) {
+        self.count_paths = count_paths;
+        self.tracked = None;
+    }
+
+    pub fn prune_found(&self) -> bool {
+        self.prune_found
+    }
+
+    /// With `true`, the default, a Boggle search through the trie stops at a trie node
+    /// once every word below it has been found with a path needing no wildcard, which
+    /// no later path can better. The words and paths found are the same either way; on
+    /// a board of repeated letters far fewer cells are visited. Counting paths or scoring
+    /// a bonus layout needs every path, so those searches never stop early.
+    pub fn set_prune_found(&mut self, prune_found: bool) {
+        self.prune_found = prune_found;
+    }
+
+    pub fn bonus(&self) -> Option<&BonusLayout> {
+        self.bonus.as_ref()
+    }
+
+    /// Scores words by the bonuses of the cells they pass, as `ScoreBreakdown` adds them
+    /// up, and keeps the highest-scoring path of each word; `None` goes back to plain
+    /// scoring. Fails unless the layout has the board's rows and columns, and boards
+    /// swapped in later must keep that shape.
+    pub fn set_bonus(&mut self, bonus: Option<BonusLayout>) -> Result<(), BoggleError> {
+        if let Some(layout) = &bonus {
+            check_bonus(&self.board, layout)?;
+        }
+        self.bonus = bonus;
+        self.tracked = None;
+        Ok(())
+    }
+
+    /// How `found`'s bonus score adds up along its path, when there is a bonus layout.
+    pub fn score_breakdown(&self, found: &FoundWord) -> Option<ScoreBreakdown> {
+        Some(score_breakdown(&self.board, self.bonus.as_ref()?, found))
+    }
+
+    fn directions(&self) -> &'static [Step] {
+        if self.board.topology() == Topology::Hex {
+            return &HEX_NEIGHBORS;
+        }
+        match (self.board.depth() > 1, self.diagonals) {
+            (false, true) => &NEIGHBORS,
+            (false, false) => &ORTHOGONAL,
+            (true, true) => &LAYER_NEIGHBORS,
+            (true, false) => &LAYER_ORTHOGONAL,
+        }
+    }
+
+    // The moves a Boggle path may make from a cell: to a neighbour or, when cells may
+    // be reused, to stay put
+    fn steps(&self) -> &'static [Step] {
+        if self.board.topology() == Topology::Hex {
+            return if self.allow_reuse { &HEX_NEIGHBORS_AND_SELF } else { &HEX_NEIGHBORS };
+        }
+        if self.board.depth() > 1 {
+            return match (self.diagonals, self.allow_reuse) {
+                (true, false) => &LAYER_NEIGHBORS,
+                (false, false) => &LAYER_ORTHOGONAL,
+                (true, true) => &LAYER_NEIGHBORS_AND_SELF,
+                (false, true) => &LAYER_ORTHOGONAL_AND_SELF,
+            };
+        }
+        match (self.diagonals, self.allow_reuse) {
+            (true, false) => &NEIGHBORS,
+            (false, false) => &ORTHOGONAL,
+            (true, true) => &NEIGHBORS_AND_SELF,
+            (false, true) => &ORTHOGONAL_AND_SELF,
+        }
+    }
+
+    // The most letters a Boggle path may spell. Reused cells take away the bound the
+    // board's size gives, so the dictionary's longest word caps the search instead
+    fn letter_limit(&self) -> usize {
+        if self.allow_reuse { self.max_len.min(self.dict.longest_word()) } else { self.max_len }
+    }
+
+    // The cell at (r, c) if it is on the board, after wrapping around the edges if enabled
+    fn on_board(&self, r: i32, c: i32) -> Option<(i32, i32)> {
+        if self.wrap && self.board.topology() == Topology::Square && self.board.depth() == 1 {
+            Some((r.rem_euclid(self.rows), c.rem_euclid(self.cols)))
+        } else if r >= 0 && r < self.rows && c >= 0 && c < self.cols {
+            Some((r, c))
+        } else {
+            None
+        }
+    }
+
+    // The cell one `step` away from (r, c), if it is on the board. Layers are stacked
+    // in the grid's rows, so a step up or down never leaves its layer, and a layered
+    // board does not wrap
+    fn step(&self, r: i32, c: i32, (dz, dr, dc): Step) -> Option<(i32, i32)> {
+        if self.board.depth() == 1 {
+            return self.on_board(r + dr, c + dc);
+        }
+        let layer_rows = self.board.layer_rows() as i32;
+        let (z, r) = (r / layer_rows + dz, r % layer_rows + dr);
+        let c = c + dc;
+        let inside = z >= 0 && z < self.board.depth() as i32 && r >= 0 && r < layer_rows && c >= 0 && c < self.cols;
+        inside.then_some((z * layer_rows + r, c))
+    }
+
+    /// Swaps in a new board while keeping the loaded dictionary, so many boards can be
+    /// solved without rebuilding the trie.
+    pub fn set_board(&mut self, board: Board) -> Result<(), BoggleError> {
+        board.validate()?;
+        if let Some(layout) = &self.bonus {
+            check_bonus(&board, layout)?;
+        }
+        self.rows = board.rows() as i32;
+        self.cols = board.cols() as i32;
+        self.board = board;
+        self.tracked = None;
+        Ok(())
+    }
+
+    /// Puts `tile` (a letter or letters such as `QU`, `?` for a wildcard, or `""` for a
+    /// block) in cell (`row`, `col`) and reports which words that adds and removes. The
+    /// first call solves the board; after that only paths through the changed cell are
+    /// searched, since every other path spells what it did before. The words stay the
+    /// same as a fresh `solve()` would find, though a word may keep another of its paths.
+    /// Word-search and anagram modes, reused cells, counted paths and bonus layouts solve
+    /// the whole board each time instead.
+    pub fn update_cell(&mut self, row: usize, col: usize, tile: &str) -> Result<SolveDelta, BoggleError> {
+        let mut board = self.board.clone();
+        board.set_tile(row, col, self.dict.locale().upper(tile))?;
+        let before = match self.tracked.take() {
+            Some(before) => before,
+            None => self.solve(),
+        };
+        self.board = board;
+
+        let after = if self.mode != Mode::Boggle || self.allow_reuse || self.count_paths || self.bonus.is_some() {
+            self.solve()
+        } else {
+            let mut words: Found = HashMap::new();
+            let through = self.search_through(row, col);
+            for found in before.words.iter() {
+                if !found.path.contains(&(row, col)) {
+                    words.insert(found.word.clone(), found.clone());
+                } else if through.get(&found.word).is_none_or(|through| through.uses_wildcard())
+                    && let Some(path) = self.find_plainest_path(&found.word)
+                {
+                    // No path through the cell any more, or only one needing a wildcard: the
+                    // word keeps a path needing as few wildcards as any
+                    let wildcards = self.wildcards_on(&found.word, &path);
+                    let found = FoundWord { word: found.word.clone(), path, wildcards, paths: 0, bonus_score: None };
+                    words.insert(found.word.clone(), found);
+                }
+            }
+            for (word, found) in through {
+                if improves(&words, &word, found.wildcards.len(), found.bonus_score) {
+                    words.insert(word, found);
+                }
+            }
+            Self::collect(words)
+        };
+
+        let had: HashMap<&str, &FoundWord> = before.words.iter().map(|f| (f.word.as_str(), f)).collect();
+        let has: HashMap<&str, &FoundWord> = after.words.iter().map(|f| (f.word.as_str(), f)).collect();
+        let added = after.words.iter().filter(|f| !had.contains_key(f.word.as_str())).cloned().collect();
+        let removed = before.words.iter().filter(|f| !has.contains_key(f.word.as_str())).cloned().collect();
+        self.tracked = Some(after);
+        Ok(SolveDelta { added, removed })
+    }
+
+    /// The words on the board as `update_cell` has kept them up to date, or None before
+    /// its first call and after any change to the board or the rules.
+    pub fn tracked_words(&self) -> Option<&SolveResult> {
+        self.tracked.as_ref()
+    }
+
+    // Every word with a path through (row, col); searches from the other cells give up
+    // on paths too long to still reach it
+    fn search_through(&self, row: usize, col: usize) -> Found {
+        match self.rows * self.cols {
+            0..=64 => self.run_through::<u64>(row, col),
+            65..=128 => self.run_through::<u128>(row, col),
+            _ => self.run_through::<Vec<bool>>(row, col),
+        }
+    }
+
+    fn run_through<V: Visited>(&self, row: usize, col: usize) -> Found {
+        let mut search = Search::<V>::new(self.rows, self.cols);
+        search.prune = self.prunes();
+        search.through = Some(Through {
+            cell: row * self.cols as usize + col,
+            distance: self.distances_from(row, col),
+            longest: self.max_len.min(self.dict.longest_word()),
+        });
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                self.search_start(r, c, &mut search);
+            }
+        }
+        search.found
+    }
+
+    // How many steps each cell is from (row, col) going around blocked cells, by cell
+    // index; usize::MAX for a cell it cannot reach
+    fn distances_from(&self, row: usize, col: usize) -> Vec<usize> {
+        let mut distance = vec![usize::MAX; (self.rows * self.cols) as usize];
+        distance[row * self.cols as usize + col] = 0;
+        let mut queue = VecDeque::from([(row as i32, col as i32)]);
+        while let Some((r, c)) = queue.pop_front() {
+            let next = distance[(r * self.cols + c) as usize] + 1;
+            for &step in self.steps() {
+                if let Some((r, c)) = self.step(r, c, step)
+                    && !self.board.is_blocked(r as usize, c as usize)
+                    && distance[(r * self.cols + c) as usize] == usize::MAX
+                {
+                    distance[(r * self.cols + c) as usize] = next;
+                    queue.push_back((r, c));
+                }
+            }
+        }
+        distance
+    }
+
+    pub fn solve(&self) -> SolveResult {
+        self.solve_with_stats().0
+    }
+
+    /// Same as `solve()`, also reporting timings and search counters.
+    pub fn solve_with_stats(&self) -> (SolveResult, SolveStats) {
+        self.search_all(None, None)
+    }
+
+    /// Same as `solve()`, but gives up once `budget` has passed and returns the words
+    /// found by then, along with whether the search finished. The deadline is checked
+    /// every thousand or so cells visited, so it is overshot by well under a millisecond.
+    pub fn solve_with_timeout(&self, budget: Duration) -> (SolveResult, bool) {
+        let (result, stats) = self.solve_with_timeout_stats(budget);
+        (result, stats.completed)
+    }
+
+    pub fn solve_with_timeout_stats(&self, budget: Duration) -> (SolveResult, SolveStats) {
+        self.search_all(None, Some(Instant::now() + budget))
+    }
+
+    /// Same as `solve()`, but calls `on_found` with each word the moment it is first
+    /// discovered, so results can be shown before the search finishes. Every word is
+    /// reported once. The returned result can differ only in a wildcard word's path, as a
+    /// path found later that needs fewer wildcards still replaces the first one.
+    pub fn solve_with<F: FnMut(&FoundWord)>(&self, mut on_found: F) -> SolveResult {
+        self.search_all(Some(&mut on_found), None).0
+    }
+
+    // Picks the smallest visited set that holds every cell of the board
+    fn search_all(
+        &self,
+        on_found: Option<&mut dyn FnMut(&FoundWord)>,
+        deadline: Option<Instant>,
+    ) -> (SolveResult, SolveStats) {
+        match self.rows * self.cols {
+            0..=64 => self.run::<u64>(on_found, deadline),
+            65..=128 => self.run::<u128>(on_found, deadline),
+            _ => self.run::<Vec<bool>>(on_found, deadline),
+        }
+    }
+
+    fn run<V: Visited>(
+        &self,
+        on_found: Option<&mut dyn FnMut(&FoundWord)>,
+        deadline: Option<Instant>,
+    ) -> (SolveResult, SolveStats) {
+        let start = Instant::now();
+        let mut search = Search::<V>::new(self.rows, self.cols);
+        search.on_found = on_found;
+        search.deadline = deadline;
+        search.count_paths = self.count_paths;
+        search.bonus = self.bonus.as_ref().map(|layout| layout.cell_scores(&self.board));
+        search.prune = self.prunes();
+        if self.mode == Mode::Anagram {
+            self.search_anagrams(&mut search);
+        } else if self.word_major() {
+            self.search_words(self.dict.words(), &mut search);
+        } else {
+            'cells: for r in 0..self.rows {
+                for c in 0..self.cols {
+                    if search.timed_out {
+                        break 'cells;
+                    }
+                    self.search_start(r, c, &mut search);
+                }
+            }
+        }
+        let (nodes_visited, steps_pruned, completed) = (search.visits, search.pruned, !search.timed_out);
+        let result = Self::collect(search.found);
+        let stats = SolveStats { completed, steps_pruned, ..self.stats(start.elapsed(), nodes_visited, &result) };
+        (result, stats)
+    }
+
+    /// Same as `solve()`, but splits the starting cells across `threads` worker threads.
+    /// Each cell runs its own DFS; results are merged in cell order so the output is identical.
+    /// Searching word by word (`Algorithm::Words`) splits the dictionary's words instead.
+    pub fn solve_parallel(&self, threads: usize) -> SolveResult {
+        self.solve_parallel_with_stats(threads).0
+    }
+
+    pub fn solve_parallel_with_stats(&self, threads: usize) -> (SolveResult, SolveStats) {
+        // There are no starting cells to split up in anagram mode
+        if self.mode == Mode::Anagram {
+            return self.solve_with_stats();
+        }
+        let start = Instant::now();
+        let starts: Vec<(i32, i32)> =
+            (0..self.rows).flat_map(|r| (0..self.cols).map(move |c| (r, c))).collect();
+        // Word by word, each job is a run of words; a few per thread evens out the load
+        let words = self.word_major().then(|| self.dict.words());
+        let shares: Option<Vec<&[String]>> =
+            words.map(|words| words.chunks(words.len().div_ceil(threads.max(1) * 4).max(1)).collect());
+        let jobs = shares.as_ref().map_or(starts.len(), Vec::len);
+        let threads = threads.clamp(1, jobs.max(1));
+
+        let mut per_cell: Vec<(usize, (Found, u64, u64))> = thread::scope(|s| {
+            let handles: Vec<_> = (0..threads)
+                .map(|t| {
+                    let (starts, shares) = (&starts, &shares);
+                    s.spawn(move || {
+                        // Interleave cells across threads to balance the load
+                        (t..jobs)
+                            .step_by(threads)
+                            .map(|i| match shares {
+                                Some(shares) => (i, self.search_share(shares[i])),
+                                None => (i, self.search_from(starts[i].0, starts[i].1)),
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles.into_iter().flat_map(|h| h.join().expect("solver thread panicked")).collect()
+        });
+        per_cell.sort_by_key(|(i, _)| *i);
+
+        let mut found_words = HashMap::new();
+        let (mut nodes_visited, mut steps_pruned) = (0, 0);
+        for (_, (found, visits, pruned)) in per_cell {
+            nodes_visited += visits;
+            steps_pruned += pruned;
+            for (word, hit) in found {
+                // Each start cell's paths are its own, so the counts add up
+                let paths = found_words.get(&word).map_or(0, |known: &FoundWord| known.paths) + hit.paths;
+                if improves(&found_words, &word, hit.wildcards.len(), hit.bonus_score) {
+                    found_words.insert(word, FoundWord { paths, ..hit });
+                } else if let Some(known) = found_words.get_mut(&word) {
+                    known.paths = paths;
+                }
+            }
+        }
+        let result = Self::collect(found_words);
+        let stats = SolveStats { steps_pruned, ..self.stats(start.elapsed(), nodes_visited, &result) };
+        (result, stats)
+    }
+
+    fn stats(&self, solve_time: Duration, nodes_visited: u64, result: &SolveResult) -> SolveStats {
+        let starts = self.start_breakdown(result, false);
+        SolveStats {
+            load: self.dict.load_stats(),
+            dictionary_nodes: self.dict.node_count(),
+            trie: self.dict.stats(),
+            solve_time,
+            nodes_visited,
+            steps_pruned: 0,
+            words_found: result.count(),
+            words_by_start: starts.counts,
+            best_by_start: starts.best,
+            completed: true,
+        }
+    }
+
+    // Whether the search may stop at trie nodes whose words are all settled; see
+    // `set_prune_found`
+    fn prunes(&self) -> bool {
+        self.prune_found && !self.count_paths && self.bonus.is_none()
+    }
+
+    // One starting cell's words and visit and pruned step counts, for the parallel solve
+    fn search_from(&self, r: i32, c: i32) -> (Found, u64, u64) {
+        match self.rows * self.cols {
+            0..=64 => self.search_cell::<u64>(r, c),
+            65..=128 => self.search_cell::<u128>(r, c),
+            _ => self.search_cell::<Vec<bool>>(r, c),
+        }
+    }
+
+    fn search_cell<V: Visited>(&self, r: i32, c: i32) -> (Found, u64, u64) {
+        let mut search = Search::<V>::new(self.rows, self.cols);
+        search.count_paths = self.count_paths;
+        search.bonus = self.bonus.as_ref().map(|layout| layout.cell_scores(&self.board));
+        search.prune = self.prunes();
+        self.search_start(r, c, &mut search);
+        (search.found, search.visits, search.pruned)
+    }
+
+    // One run of the dictionary's words and the cells visited finding them, for the
+    // parallel word-by-word solve
+    fn search_share(&self, words: &[String]) -> (Found, u64, u64) {
+        match self.rows * self.cols {
+            0..=64 => self.share::<u64>(words),
+            65..=128 => self.share::<u128>(words),
+            _ => self.share::<Vec<bool>>(words),
+        }
+    }
+
+    fn share<V: Visited>(&self, words: &[String]) -> (Found, u64, u64) {
+        let mut search = Search::<V>::new(self.rows, self.cols);
+        search.count_paths = self.count_paths;
+        search.bonus = self.bonus.as_ref().map(|layout| layout.cell_scores(&self.board));
+        self.search_words(words, &mut search);
+        (search.found, search.visits, search.pruned)
+    }
+
+    // `Algorithm::Words`: the same search as from each cell, but through a dictionary of
+    // one word at a time, so paths are tried in the same order and the same one is kept
+    fn search_words<V: Visited>(&self, words: &[String], search: &mut Search<V>) {
+        let pool = LetterPool::new(&self.board, self.allow_reuse);
+        for word in words.iter().filter(|word| pool.can_spell(word)) {
+            let index = SortedWords::new([word.as_str()]);
+            for r in 0..self.rows {
+                for c in 0..self.cols {
+                    if search.timed_out {
+                        return;
+                    }
+                    self.search_index(r, c, &index, search);
+                }
+            }
+        }
+    }
+
+    fn search_start<V: Visited>(&self, r: i32, c: i32, search: &mut Search<V>) {
+        match &self.dict.lexicon {
+            Lexicon::Trie(trie) => self.search_index(r, c, trie, search),
+            Lexicon::Dawg(dawg) => self.search_index(r, c, dawg, search),
+            Lexicon::Sorted(words) => self.search_index(r, c, words, search),
+            Lexicon::Fst(fst) => self.search_index(r, c, fst, search),
+        }
+    }
+
+    fn search_index<I: WordIndex, V: Visited>(&self, r: i32, c: i32, index: &I, search: &mut Search<V>) {
+        match self.mode {
+            Mode::Boggle => self.dfs(r, c, index.root(), search),
+            Mode::WordSearch => self.lines(r, c, index.root(), search),
+            // Not searched from cells; see `search_anagrams`
+            Mode::Anagram => {}
+        }
+    }
+
+    // Anagram mode: the dictionary walked with a budget of the board's letters, each
+    // step spending one, or a wildcard once that letter runs out
+    fn search_anagrams<V: Visited>(&self, search: &mut Search<V>) {
+        let mut letters: Vec<(char, u32)> = Vec::new();
+        let mut blanks = Vec::new();
+        for r in 0..self.board.rows() {
+            for c in 0..self.board.cols() {
+                let tile = self.board.tile(r, c);
+                if tile == WILDCARD {
+                    blanks.push((r, c));
+                    continue;
+                }
+                for ch in tile.chars() {
+                    match letters.iter_mut().find(|(letter, _)| *letter == ch) {
+                        Some((_, count)) => *count += 1,
+                        None => letters.push((ch, 1)),
+                    }
+                }
+            }
+        }
+        letters.sort_unstable();
+        match &self.dict.lexicon {
+            Lexicon::Trie(trie) => self.spend(trie.root(), &mut letters, &blanks, search),
+            Lexicon::Dawg(dawg) => self.spend(dawg.root(), &mut letters, &blanks, search),
+            Lexicon::Sorted(words) => self.spend(words.root(), &mut letters, &blanks, search),
+            Lexicon::Fst(fst) => self.spend(fst.root(), &mut letters, &blanks, search),
+        }
+    }
+
+    // Every word below `node` the letters left can finish. A real letter is always
+    // spent before a wildcard, which leaves each word the fewest wildcards it can need;
+    // allowing reuse, nothing is ever used up.
+    fn spend<N: WordNode, V: Visited>(
+        &self,
+        node: N,
+        letters: &mut [(char, u32)],
+        blanks: &[(usize, usize)],
+        search: &mut Search<V>,
+    ) {
+        search.visit();
+        if search.timed_out || search.letters >= self.max_len {
+            return;
+        }
+        let blank = match blanks.first() {
+            Some(&first) if self.allow_reuse => Some(first),
+            _ => blanks.get(search.wildcards.len()).copied(),
+        };
+        let (len, letters_so_far) = (search.word.len(), search.letters);
+        let step = |ch: char, next: N, letters: &mut [(char, u32)], search: &mut Search<V>| {
+            let left = letters.iter().position(|&(letter, count)| letter == ch && count > 0);
+            match (left, blank) {
+                (Some(i), _) => letters[i].1 -= u32::from(!self.allow_reuse),
+                (None, Some(cell)) => search.wildcards.push((cell, ch)),
+                (None, None) => return,
+            }
+            search.word.push(ch);
+            search.letters += 1;
+            if next.is_word() {
+                search.record();
+            }
+            self.spend(next, letters, blanks, search);
+            search.word.truncate(len);
+            search.letters = letters_so_far;
+            match left {
+                Some(i) => letters[i].1 += u32::from(!self.allow_reuse),
+                None => drop(search.wildcards.pop()),
+            }
+        };
+        if blank.is_some() {
+            for (ch, next) in node.children() {
+                step(ch, next, letters, search);
+            }
+        } else {
+            // Without a wildcard only the letters still in hand can follow
+            for i in 0..letters.len() {
+                let (ch, count) = letters[i];
+                if count > 0
+                    && let Some(next) = node.child(ch)
+                {
+                    step(ch, next, letters, search);
+                }
+            }
+        }
+    }
+
+    // Word-search mode: every straight line starting at (r, c)
+    fn lines<N: WordNode, V: Visited>(&self, r: i32, c: i32, root: N, search: &mut Search<V>) {
+        let directions = self.directions();
+        for (i, &step) in directions.iter().enumerate() {
+            // Two directions that agree on the first step trace the same line
+            if !(search.count_paths && self.repeats_step(r, c, directions, i)) {
+                self.line(r, c, step, root, search);
+            }
+        }
+    }
+
+    // Whether `steps[i]` from (r, c) lands on the cell an earlier step does, which only
+    // happens when a wrapped board is too small for its neighbours all to differ
+    fn repeats_step(&self, r: i32, c: i32, steps: &[Step], i: usize) -> bool {
+        let target = self.step(r, c, steps[i]);
+        target.is_some() && steps[..i].iter().any(|&earlier| self.step(r, c, earlier) == target)
+    }
+
+    // Matches (r, c) and then the cells beyond it in the direction `step`. A line never
+    // crosses itself, so the recursion is only as deep as the board is wide.
+    fn line<N: WordNode, V: Visited>(&self, r: i32, c: i32, step: Step, node: N, search: &mut Search<V>) {
+        let Some((r, c)) = self.on_board(r, c) else {
+            return;
+        };
+        let cell = (r * self.cols + c) as usize;
+        if search.visited.contains(cell) {
+            return; // On a wrapped board the line came back around to its own start
+        }
+        search.visit();
+        if search.timed_out {
+            return;
+        }
+        let tile = self.board.tile(r as usize, c as usize);
+        if tile.is_empty() {
+            return; // Blocked cell
+        }
+        let (len, letters) = (search.word.len(), search.letters);
+
+        if tile == WILDCARD {
+            for (ch, next) in node.children() {
+                search.push_wildcard(r, c, ch);
+                search.arrive(cell, r, c, next);
+                if search.letters < self.max_len
+                    && let Some((r, c)) = self.step(r, c, step)
+                {
+                    self.line(r, c, step, next, search);
+                }
+                search.leave(cell, len, letters, true);
+            }
+            return;
+        }
+
+        let mut current_node = node;
+        let mut tile_letters = 0;
+        for ch in tile.chars() {
+            match current_node.child(ch) {
+                Some(next) => current_node = next,
+                None => return,
+            }
+            tile_letters += 1;
+        }
+        search.word.push_str(tile);
+        search.letters += tile_letters;
+        search.arrive(cell, r, c, current_node);
+        if search.letters < self.max_len
+            && let Some((r, c)) = self.step(r, c, step)
+        {
+            self.line(r, c, step, current_node, search);
+        }
+        search.leave(cell, len, letters, false);
+    }
+
+    fn collect(found_words: Found) -> SolveResult {
+        SolveResult { words: found_words.into_values().collect() }.sorted_by(SortOrder::Length)
+    }
+
+    /// A legal path for `word` if it is in the dictionary and can be traced on the board.
+    pub fn check_word(&self, word: &str) -> Option<Vec<(usize, usize)>> {
+        if !self.dict.contains(word) {
+            return None;
+        }
+        self.find_path(word)
+    }
+
+    /// A legal path that spells `word` on the board (a straight line in word-search mode),
+    /// ignoring the dictionary. Only cells matching the word's next letters are explored.
+    pub fn find_path(&self, word: &str) -> Option<Vec<(usize, usize)>> {
+        self.find_path_via(word, None, usize::MAX)
+    }
+
+    // A path for `word` with as few wildcards as any, the one a whole-board solve keeps
+    fn find_plainest_path(&self, word: &str) -> Option<Vec<(usize, usize)>> {
+        let path = self.find_path(word)?;
+        let used = self.wildcards_on(word, &path).len();
+        (0..used).find_map(|most| self.find_path_via(word, None, most)).or(Some(path))
+    }
+
+    /// The longest start of `word` (any case) that can be traced on the board, ignoring
+    /// the dictionary: how many letters it has and the path spelling them. When the whole
+    /// word can be traced, this is `find_path`'s path.
+    pub fn longest_traceable(&self, word: &str) -> (usize, Vec<(usize, usize)>) {
+        let word = self.dict.normalize(word);
+        if let Some(path) = self.find_path(&word) {
+            return (word.chars().count(), path);
+        }
+        if self.mode == Mode::Anagram {
+            let pool = LetterPool::new(&self.board, self.allow_reuse);
+            let starts = word.char_indices().map(|(i, _)| &word[..i]).chain([word.as_str()]);
+            return (starts.filter(|start| pool.can_spell(start)).count().saturating_sub(1), Vec::new());
+        }
+        // The fewest bytes of the word left unspelled so far, and the path that got there
+        let mut best = (word.len(), Vec::new());
+        let mut visited = vec![vec![false; self.cols as usize]; self.rows as usize];
+        let mut cells = Vec::new();
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.mode == Mode::WordSearch {
+                    self.deepest_line(r, c, &word, &mut best);
+                } else {
+                    self.deepest(r, c, &word, &mut visited, &mut cells, &mut best);
+                }
+            }
+        }
+        (word[..word.len() - best.0].chars().count(), best.1)
+    }
+
+    // `trace` without a goal: follows every path spelling a start of `rest`, keeping the
+    // one that leaves the least of it
+    fn deepest(
+        &self,
+        r: i32,
+        c: i32,
+        rest: &str,
+        visited: &mut Vec<Vec<bool>>,
+        cells: &mut Vec<(usize, usize)>,
+        best: &mut (usize, Vec<(usize, usize)>),
+    ) {
+        let Some((r, c)) = self.on_board(r, c) else {
+            return;
+        };
+        if visited[r as usize][c as usize] && !self.allow_reuse {
+            return;
+        }
+        let Some(rest) = self.strip_tile(r, c, rest) else {
+            return;
+        };
+
+        visited[r as usize][c as usize] = true;
+        cells.push((r as usize, c as usize));
+        if rest.len() < best.0 {
+            *best = (rest.len(), cells.clone());
+        }
+        if !rest.is_empty() {
+            for &step in self.steps() {
+                if let Some((r, c)) = self.step(r, c, step) {
+                    self.deepest(r, c, rest, visited, cells, best);
+                }
+            }
+        }
+        cells.pop();
+        visited[r as usize][c as usize] = false;
+    }
+
+    // Word-search mode: the straight line from (r, c) spelling the most of `word`
+    fn deepest_line(&self, r: i32, c: i32, word: &str, best: &mut (usize, Vec<(usize, usize)>)) {
+        for &step in self.directions() {
+            let (mut next, mut rest) = (self.on_board(r, c), word);
+            let mut cells: Vec<(usize, usize)> = Vec::new();
+            while let Some((r2, c2)) = next
+                && !cells.contains(&(r2 as usize, c2 as usize))
+                && let Some(remaining) = self.strip_tile(r2, c2, rest)
+            {
+                cells.push((r2 as usize, c2 as usize));
+                if remaining.len() < best.0 {
+                    *best = (remaining.len(), cells.clone());
+                }
+                (next, rest) = (self.step(r2, c2, step), remaining);
+            }
+        }
+    }
+
+    /// The words of `result` that can be traced through cell (`row`, `col`), counted from
+    /// 0 as in printed paths. A word whose recorded path misses the cell is searched for
+    /// again, anchored there, so it is kept if any of its paths passes through, and then
+    /// carries that path.
+    pub fn words_through(&self, result: &SolveResult, row: usize, col: usize) -> Result<SolveResult, BoggleError> {
+        if row >= self.board.rows() || col >= self.board.cols() {
+            return Err(BoggleError::CellOffBoard { row, col, rows: self.board.rows(), cols: self.board.cols() });
+        }
+        let mut words = Vec::new();
+        for found in &result.words {
+            if found.path.contains(&(row, col)) {
+                words.push(found.clone());
+            } else if let Some(path) = self.find_path_via(&found.word, Some((row, col)), usize::MAX) {
+                let wildcards = self.wildcards_on(&found.word, &path);
+                let mut through =
+                    FoundWord { word: found.word.clone(), path, wildcards, paths: found.paths, bonus_score: None };
+                through.bonus_score = self.score_breakdown(&through).map(|breakdown| breakdown.total());
+                words.push(through);
+            }
+        }
+        Ok(SolveResult { words })
+    }
+
+    /// How many of `result`'s words start at each cell, and the best of them. Each word
+    /// counts at the start of the path it was found with, or with `every_start` at
+    /// every cell some path for it starts at, so one word may count at several cells.
+    pub fn start_breakdown(&self, result: &SolveResult, every_start: bool) -> StartBreakdown {
+        let (rows, cols) = (self.board.rows(), self.board.cols());
+        if !every_start {
+            let firsts = result.words.iter().filter_map(|found| Some((found, *found.path.first()?)));
+            return StartBreakdown::tally(rows, cols, firsts);
+        }
+        let mut visited = vec![vec![false; cols]; rows];
+        let mut starts = Vec::new();
+        for found in &result.words {
+            for (r, c) in (0..rows).flat_map(|r| (0..cols).map(move |c| (r, c))) {
+                let (r2, c2) = (r as i32, c as i32);
+                let starts_here = if self.mode == Mode::WordSearch {
+                    self.trace_line(r2, c2, &found.word, None, usize::MAX).is_some()
+                } else {
+                    let mut cells = Vec::new();
+                    let traced = self.trace(r2, c2, &found.word, (None, usize::MAX), &mut visited, &mut cells);
+                    // A successful trace leaves its cells marked
+                    for &(r, c) in &cells {
+                        visited[r][c] = false;
+                    }
+                    traced
+                };
+                if starts_here {
+                    starts.push((found, (r, c)));
+                }
+            }
+        }
+        StartBreakdown::tally(rows, cols, starts.into_iter())
+    }
+
+    // The letter each wildcard cell on `path` stands for in `word`
+    fn wildcards_on(&self, word: &str, path: &[(usize, usize)]) -> Vec<((usize, usize), char)> {
+        let mut rest = word;
+        let mut wildcards = Vec::new();
+        for &(r, c) in path {
+            if self.board.is_wildcard(r, c) {
+                let Some(ch) = rest.chars().next() else { break };
+                wildcards.push(((r, c), ch));
+                rest = &rest[ch.len_utf8()..];
+            } else {
+                rest = rest.get(self.board.tile(r, c).len()..).unwrap_or_default();
+            }
+        }
+        wildcards
+    }
+
+    // `find_path`, optionally only accepting a path that passes through the cell `through`,
+    // and using at most `wildcards` wildcard cells
+    fn find_path_via(
+        &self,
+        word: &str,
+        through: Option<(usize, usize)>,
+        wildcards: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let word = self.dict.normalize(word);
+        if word.is_empty() {
+            return None;
+        }
+        // A word the letters make has an empty path, and none passes any one cell
+        if self.mode == Mode::Anagram {
+            let spelled = through.is_none() && LetterPool::new(&self.board, self.allow_reuse).can_spell(&word);
+            return spelled.then(Vec::new);
+        }
+        let mut visited = vec![vec![false; self.cols as usize]; self.rows as usize];
+        let mut cells = Vec::new();
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                if self.mode == Mode::WordSearch {
+                    if let Some(cells) = self.trace_line(r, c, &word, through, wildcards) {
+                        return Some(cells);
+                    }
+                } else if self.trace(r, c, &word, (through, wildcards), &mut visited, &mut cells) {
+                    return Some(cells);
+                }
+            }
+        }
+        None
+    }
+
+    // What is left of `rest` once the tile at on-board cell (r, c) is matched against its start
+    fn strip_tile<'w>(&self, r: i32, c: i32, rest: &'w str) -> Option<&'w str> {
+        if self.board.is_blocked(r as usize, c as usize) {
+            return None;
+        }
+        if self.board.is_wildcard(r as usize, c as usize) {
+            // A wildcard covers whatever letter comes next
+            let mut chars = rest.chars();
+            chars.next()?;
+            return Some(chars.as_str());
+        }
+        rest.strip_prefix(self.board.tile(r as usize, c as usize))
+    }
+
+    fn trace(
+        &self,
+        r: i32,
+        c: i32,
+        rest: &str,
+        (through, wildcards): (Option<(usize, usize)>, usize),
+        visited: &mut Vec<Vec<bool>>,
+        cells: &mut Vec<(usize, usize)>,
+    ) -> bool {
+        let Some((r, c)) = self.on_board(r, c) else {
+            return false;
+        };
+        if visited[r as usize][c as usize] && !self.allow_reuse {
+            return false;
+        }
+        let wildcard = self.board.is_wildcard(r as usize, c as usize);
+        if wildcard && wildcards == 0 {
+            return false;
+        }
+        let wildcards = wildcards - usize::from(wildcard);
+        let Some(rest) = self.strip_tile(r, c, rest) else {
+            return false;
+        };
+
+        visited[r as usize][c as usize] = true;
+        cells.push((r as usize, c as usize));
+        if rest.is_empty() && through.is_none_or(|cell| cells.contains(&cell)) {
+            return true;
+        }
+        for &step in self.steps() {
+            if !rest.is_empty()
+                && let Some((r, c)) = self.step(r, c, step)
+                && self.trace(r, c, rest, (through, wildcards), visited, cells)
+            {
+                return true;
+            }
+        }
+        cells.pop();
+        visited[r as usize][c as usize] = false;
+        false
+    }
+
+    // Word-search mode: the cells spelling `word` in a straight line from (r, c) through
+    // at most `wildcards` wildcards, if any
+    fn trace_line(
+        &self,
+        r: i32,
+        c: i32,
+        word: &str,
+        through: Option<(usize, usize)>,
+        wildcards: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        for &step in self.directions() {
+            let (mut next, mut rest, mut spare) = (self.on_board(r, c), word, wildcards);
+            let mut cells: Vec<(usize, usize)> = Vec::new();
+            while let Some((r2, c2)) = next
+                && !cells.contains(&(r2 as usize, c2 as usize))
+                && let Some(remaining) = self.strip_tile(r2, c2, rest)
+            {
+                if self.board.is_wildcard(r2 as usize, c2 as usize) {
+                    if spare == 0 {
+                        break;
+                    }
+                    spare -= 1;
+                }
+                cells.push((r2 as usize, c2 as usize));
+                if remaining.is_empty() {
+                    if through.is_none_or(|cell| cells.contains(&cell)) {
+                        return Some(cells);
+                    }
+                    break;
+                }
+                (next, rest) = (self.step(r2, c2, step), remaining);
+            }
+        }
+        None
+    }
+
+    /// Every word on the board, sorted by length descending then alphabetically.
+    pub fn solve_all(&self) -> Vec<String> {
+        self.solve().words.into_iter().map(|f| f.word).collect()
+    }
+
+    // Depth-first search from (r, c) on an explicit stack, so a long word on a big board
+    // cannot overflow the call stack. Cells and wildcard letters are tried in the same
+    // order a recursive search would use, so the first path found for a word is the same.
+    fn dfs<N: WordNode, V: Visited>(&self, r: i32, c: i32, root: N, search: &mut Search<V>) {
+        let mut stack: Vec<Frame<N>> = Vec::new();
+        stack.extend(self.enter(r, c, root, search));
+
+        let directions = self.steps();
+        let limit = self.letter_limit();
+        while let Some(top) = stack.last_mut() {
+            // Out of time: the words recorded so far stand, the rest of the path is dropped
+            if search.timed_out {
+                return;
+            }
+            // Nothing longer than the limit is in the dictionary, so stop descending there
+            if search.letters < limit && top.neighbor < directions.len() {
+                let step = directions[top.neighbor];
+                top.neighbor += 1;
+                if search.count_paths && self.repeats_step(top.r, top.c, directions, top.neighbor - 1) {
+                    continue;
+                }
+                if let Some((r, c)) = self.step(top.r, top.c, step) {
+                    let node = top.node;
+                    stack.extend(self.enter(r, c, node, search));
+                }
+                continue;
+            }
+
+            // Every neighbour tried: take this cell's letters back off the path
+            search.leave(top.cell, top.len, top.letters, top.wildcard);
+            // A wildcard then tries its next letter from the same cell
+            match top.choices.pop() {
+                Some((ch, next)) => {
+                    top.node = next;
+                    top.neighbor = 0;
+                    search.push_wildcard(top.r, top.c, ch);
+                    search.arrive(top.cell, top.r, top.c, next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    // Steps onto (r, c) from `node` if the cell is free and its tile continues a word in
+    // the dictionary, returning the new top of the stack
+    fn enter<N: WordNode, V: Visited>(&self, r: i32, c: i32, node: N, search: &mut Search<V>) -> Option<Frame<N>> {
+        let (r, c) = self.on_board(r, c)?;
+        let cell = (r * self.cols + c) as usize;
+        if !self.allow_reuse && search.visited.contains(cell) || search.out_of_reach(cell) {
+            return None;
+        }
+        search.visit();
+        if search.timed_out {
+            return None;
+        }
+
+        let tile = self.board.tile(r as usize, c as usize);
+        if tile.is_empty() {
+            return None; // Blocked cell
+        }
+
+        // Truncating back to a byte length taken before the push is always on a char
+        // boundary, so multi-byte letters come off whole
+        let (len, letters) = (search.word.len(), search.letters);
+
+        // A wildcard branches only into letters the trie can continue with, so its
+        // fan-out is bounded by the dictionary rather than the alphabet
+        if tile == WILDCARD {
+            let mut choices = node.children();
+            let before = choices.len();
+            choices.retain(|&(_, next)| !search.exhausted(next));
+            search.pruned += (before - choices.len()) as u64;
+            choices.reverse(); // Popped from the back, so the first letter comes first
+            let (ch, next) = choices.pop()?;
+            search.push_wildcard(r, c, ch);
+            search.arrive(cell, r, c, next);
+            return Some(Frame { r, c, cell, node: next, len, letters, neighbor: 0, wildcard: true, choices });
+        }
+
+        // Traverse through all characters in the tile (handles "QU" as single tile)
+        let mut current_node = node;
+        let mut tile_letters = 0;
+        for ch in tile.chars() {
+            current_node = current_node.child(ch)?; // No matching path in trie
+            tile_letters += 1;
+        }
+        if search.exhausted(current_node) {
+            search.pruned += 1;
+            return None;
+        }
+
+        search.word.push_str(tile);
+        search.letters += tile_letters;
+        search.arrive(cell, r, c, current_node);
+        Some(Frame { r, c, cell, node: current_node, len, letters, neighbor: 0, wildcard: false, choices: Vec::new() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::ParseOptions;
+    use crate::dictionary::{LengthFilter, LoadOptions};
+    use crate::rng::Rng;
+
+    // A few letters, so random words share their prefixes and random boards spell many
+    const LETTERS: [&str; 7] = ["A", "E", "R", "S", "T", "N", "QU"];
+
+    fn random_words(rng: &mut Rng, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| (0..3 + rng.below(5)).map(|_| LETTERS[rng.below(LETTERS.len())]).collect())
+            .collect()
+    }
+
+    // A board of the letters above with up to `wildcards` blanks and the odd block
+    fn random_board(rng: &mut Rng, rows: usize, cols: usize, wildcards: usize) -> Board {
+        let mut tiles: Vec<Vec<String>> = (0..rows)
+            .map(|_| (0..cols).map(|_| LETTERS[rng.below(LETTERS.len())].to_string()).collect())
+            .collect();
+        for _ in 0..rng.below(wildcards + 1) {
+            tiles[rng.below(rows)][rng.below(cols)] = WILDCARD.to_string();
+        }
+        if rng.below(4) == 0 {
+            tiles[rng.below(rows)][rng.below(cols)] = String::new();
+        }
+        Board::new(tiles)
+    }
+
+    fn words(result: &SolveResult) -> Vec<(&str, bool)> {
+        let mut words: Vec<(&str, bool)> = result.words.iter().map(|f| (f.word.as_str(), f.uses_wildcard())).collect();
+        words.sort();
+        words
+    }
+
+    // The "C A T / Qu E R / S T O" board
+    fn small_solver<'d>(words: &[&str]) -> BoggleSolver<'d> {
+        BoggleSolver::from_words(Board::from_rows(&["cat", "qer", "sto"]), words).unwrap()
+    }
+
+    #[test]
+    fn solves_a_small_board_exactly() {
+        // TOT and QUEER would need a cell twice, QAT a plain Q, AT is too short and DOG
+        // is not on the board
+        let dict = ["cat", "cater", "ace", "quest", "squat", "rote", "tot", "queer", "qat", "at", "dog"];
+        let result = small_solver(&dict).solve();
+        let mut found: Vec<&str> = result.words.iter().map(|f| f.word.as_str()).collect();
+        found.sort();
+        assert_eq!(found, ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT"]);
+        assert_eq!(result.count(), 6);
+        assert_eq!(result.total_score(), 1 + 1 + 2 + 2 + 1 + 2);
+
+        let quest = result.words.iter().find(|f| f.word == "QUEST").unwrap();
+        assert_eq!(quest.path, [(1, 0), (1, 1), (2, 0), (2, 1)]);
+        assert_eq!(quest.tiles_used(), 4);
+        assert!(!quest.uses_wildcard());
+        // Longest first, then alphabetically
+        assert_eq!(result.words[0].word, "CATER");
+    }
+
+    #[test]
+    fn reuse_finds_the_words_needing_a_cell_twice() {
+        let mut solver = small_solver(&["tot", "queer", "cat", "otto"]);
+        solver.set_allow_reuse(true);
+        // QUEER steps from the E back onto it
+        assert_eq!(words(&solver.solve()), [("CAT", false), ("OTTO", false), ("QUEER", false), ("TOT", false)]);
+    }
+
+    #[test]
+    fn wildcards_stand_for_any_letter() {
+        let board = Board::from_rows(&["c?", "ts"]);
+        let result = BoggleSolver::from_words(board, ["cat", "cats", "acts", "scat", "cut", "cc"]).unwrap().solve();
+        assert_eq!(words(&result), [("ACTS", true), ("CAT", true), ("CATS", true), ("CUT", true), ("SCAT", true)]);
+        let cat = result.words.iter().find(|f| f.word == "CAT").unwrap();
+        assert_eq!(cat.wildcards, [((0, 1), 'A')]);
+    }
+
+    #[test]
+    fn two_wildcards_find_the_words_either_or_both_complete() {
+        let dict = ["cat", "cater", "ace", "quest", "squat", "rote", "rest", "its", "zoo"];
+        let plain = BoggleSolver::from_words(Board::from_rows(&["cat", "qer", "xtx"]), dict).unwrap().solve();
+        assert_eq!(words(&plain), [("ACE", false), ("CAT", false), ("CATER", false)]);
+
+        // The bottom corners stand for S in QUEST, SQUAT and REST, O in ROTE, and both at
+        // once in ITS
+        let result = BoggleSolver::from_words(Board::from_rows(&["cat", "qer", "?t?"]), dict).unwrap().solve();
+        let expected = [
+            ("ACE", false),
+            ("CAT", false),
+            ("CATER", false),
+            ("ITS", true),
+            ("QUEST", true),
+            ("REST", true),
+            ("ROTE", true),
+            ("SQUAT", true),
+        ];
+        assert_eq!(words(&result), expected);
+        let its = result.words.iter().find(|f| f.word == "ITS").unwrap();
+        // Either corner can be the I, so only the pair is fixed
+        let (mut cells, mut letters): (Vec<_>, Vec<_>) = its.wildcards.iter().copied().unzip();
+        cells.sort();
+        letters.sort();
+        assert_eq!((cells, letters), (vec![(2, 0), (2, 2)], vec!['I', 'S']));
+    }
+
+    #[test]
+    fn the_dawg_finds_the_tries_words() {
+        // CATER and RATER, ROTE and TOTE share endings, which the DAWG stores once; EROS is not on the board
+        let dict = ["cat", "cater", "rater", "ace", "quest", "squat", "rote", "tote", "toes", "eros", "zebra"];
+        let trie = small_solver(&dict);
+        let mut dawg = small_solver(&dict);
+        dawg.minimize();
+        assert!(dawg.node_count() < trie.node_count());
+        let expected = ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT", "TOES"].map(|word| (word, false));
+        assert_eq!(words(&trie.solve()), expected);
+        assert_eq!(words(&dawg.solve()), expected);
+        assert_eq!(dawg.solve(), trie.solve());
+
+        let mut rng = Rng::new(12);
+        for _ in 0..50 {
+            let board = random_board(&mut rng, 4, 4, 2);
+            let trie = BoggleSolver::from_words(board, random_words(&mut rng, 300)).unwrap();
+            let mut dawg = trie.with_board(trie.board().clone()).unwrap();
+            dawg.minimize();
+            assert_eq!(words(&dawg.solve()), words(&trie.solve()), "{:?}", trie.board());
+        }
+    }
+
+    #[test]
+    fn a_timed_out_search_stops_near_the_deadline_with_real_words() {
+        // Every word of A and E up to 12 letters, on a board of blanks that may be reused:
+        // far more paths than any solve could walk
+        let (mut dict, mut level) = (Vec::new(), vec![String::new()]);
+        for _ in 0..12 {
+            level = level.iter().flat_map(|word| [word.clone() + "A", word.clone() + "E"]).collect();
+            dict.extend(level.iter().cloned());
+        }
+        let board = Board::new(vec![vec![WILDCARD.to_string(); 6]; 6]);
+        let mut solver = BoggleSolver::from_words(board, &dict).unwrap();
+        solver.set_allow_reuse(true);
+
+        let budget = Duration::from_millis(50);
+        let start = Instant::now();
+        let (result, completed) = solver.solve_with_timeout(budget);
+        let elapsed = start.elapsed();
+        assert!(!completed);
+        // Generous for a slow debug build; a search left to run would take hours
+        assert!(elapsed < budget + Duration::from_millis(500), "{:?}", elapsed);
+        assert!(!result.words.is_empty());
+        for found in &result.words {
+            assert!(solver.dictionary().contains(&found.word), "{}", found.word);
+            assert_eq!(found.path.len(), found.word.len(), "{}", found.word);
+            for pair in found.path.windows(2) {
+                let ((r1, c1), (r2, c2)) = (pair[0], pair[1]);
+                assert!(r1.abs_diff(r2) <= 1 && c1.abs_diff(c2) <= 1, "{}: {:?}", found.word, found.path);
+            }
+        }
+
+        // A board small enough finishes well within the budget, with every word
+        let (result, completed) = small_solver(&["cat", "cater", "quest"]).solve_with_timeout(Duration::from_secs(10));
+        assert!(completed);
+        assert_eq!(result.count(), 3);
+    }
+
+    #[test]
+    fn pruned_and_unpruned_solves_agree() {
+        let mut rng = Rng::new(95);
+        for round in 0..200 {
+            let size = 3 + rng.below(2);
+            let reuse = rng.below(3) == 0;
+            let board = random_board(&mut rng, size, size, if reuse { 1 } else { 2 });
+            let list = random_words(&mut rng, 1500);
+            let filter = LengthFilter::new(3, reuse.then_some(6)).unwrap();
+
+            let dict = Dictionary::from_words_with(&list, filter);
+            let mut solver = BoggleSolver::with_dictionary(board.clone(), dict).unwrap();
+            solver.set_allow_reuse(reuse);
+            solver.set_wrap(rng.below(4) == 0);
+            solver.set_diagonals(rng.below(4) != 0);
+            solver.set_prune_found(false);
+            let full = solver.solve();
+            solver.set_prune_found(true);
+            let pruned = solver.solve_with_stats();
+            assert_eq!(pruned.0, full, "round {}: {:?}", round, board);
+
+            // Leaving out the words the board's letters cannot spell loses nothing either
+            let pool = LoadOptions { pool: Some(LetterPool::new(&board, reuse)), ..LoadOptions::default() };
+            let text = list.join("\n");
+            let Ok(sifted) = Dictionary::from_reader_with(text.as_bytes(), "random", filter, &pool) else {
+                assert!(full.words.is_empty(), "round {}: {:?}", round, board);
+                continue;
+            };
+            let mut pooled = solver.with_board(board.clone()).unwrap();
+            pooled.dict = Cow::Owned(sifted);
+            assert_eq!(words(&pooled.solve()), words(&full), "round {}: {:?}", round, board);
+        }
+    }
+
+    #[test]
+    fn pruning_skips_steps_on_a_board_of_one_letter() {
+        let board = Board::from_rows(&["aaaa", "aaaa", "aaaa", "aaaa"]);
+        let mut solver = BoggleSolver::from_words(board, ["aaa", "aaaa", "aaaaa"]).unwrap();
+        let (result, stats) = solver.solve_with_stats();
+        assert_eq!(result.count(), 3);
+        assert!(stats.steps_pruned > 0);
+        solver.set_prune_found(false);
+        let (full, stats) = solver.solve_with_stats();
+        assert_eq!(full, result);
+        assert_eq!(stats.steps_pruned, 0);
+    }
+
+    #[test]
+    fn finds_umlauts_and_enye() {
+        // GRÜN is five bytes but four letters, so a four-letter cap keeps it and drops GRÜNE
+        let dict = Dictionary::from_words_with(["grün", "grüne", "über"], LengthFilter::new(4, Some(4)).unwrap());
+        let german = Board::from_rows(&["grü", "xne", "xxx"]);
+        let result = BoggleSolver::with_dictionary(german, dict).unwrap().solve();
+        assert_eq!(words(&result), [("GRÜN", false)]);
+        assert_eq!(result.words[0].path, [(0, 0), (0, 1), (0, 2), (1, 1)]);
+
+        let dict = Dictionary::from_words_with(["año", "niño", "ano"], LengthFilter::new(3, Some(3)).unwrap());
+        let spanish = Board::from_rows(&["añ", "ox"]);
+        let result = BoggleSolver::with_dictionary(spanish, dict).unwrap().solve();
+        assert_eq!(words(&result), [("AÑO", false)]);
+    }
+
+    #[test]
+    fn a_digraph_tile_spells_both_its_letters() {
+        // CALLO needs the LL tile; CALO would need half of it
+        let opts = ParseOptions { digraphs: vec!["LL".to_string()], ..ParseOptions::default() };
+        let board = Board::parse("ca/llo", &opts).unwrap();
+        let dict = Dictionary::from_words_with(["callo", "calo"], LengthFilter::new(4, Some(5)).unwrap());
+        let result = BoggleSolver::with_dictionary(board, dict.clone()).unwrap().solve();
+        assert_eq!(words(&result), [("CALLO", false)]);
+        assert_eq!(result.words[0].path, [(0, 0), (0, 1), (1, 0), (1, 1)]);
+
+        let plain = Board::from_rows(&["ca", "lo"]);
+        let result = BoggleSolver::with_dictionary(plain, dict).unwrap().solve();
+        assert_eq!(words(&result), [("CALO", false)]);
+    }
+
+    #[test]
+    fn a_snake_word_covers_every_tile() {
+        // Along each row and back along the next, ending on Qu: 17 letters on 16 tiles
+        let board = Board::from_rows(&["abcd", "hgfe", "ijkl", "qonm"]);
+        let result = BoggleSolver::from_words(board, ["abcdefghijklmnoqu", "abcd", "fgh"]).unwrap().solve();
+        let most: Vec<(&str, usize)> = result.most_tiles(2).iter().map(|f| (f.word.as_str(), f.tiles_used())).collect();
+        assert_eq!(most, [("ABCDEFGHIJKLMNOQU", 16), ("ABCD", 4)]);
+        let snake = &result.words[0];
+        assert_eq!(snake.word.chars().count(), 17);
+        assert_eq!(snake.path[..5], [(0, 0), (0, 1), (0, 2), (0, 3), (1, 3)]);
+        assert_eq!(snake.path[15], (3, 0));
+    }
+
+    #[test]
+    fn counts_every_path_once() {
+        // Any three of the four cells, in any order, spell AAA: 4 * 3 * 2 paths
+        let mut solver = BoggleSolver::from_words(Board::from_rows(&["aa", "aa"]), ["aaa", "aaaa"]).unwrap();
+        assert!(solver.solve().words.iter().all(|f| f.paths == 0));
+        solver.set_count_paths(true);
+        let paths: Vec<(String, u32)> = solver.solve().words.into_iter().map(|f| (f.word, f.paths)).collect();
+        assert_eq!(paths, [("AAAA".to_string(), 24), ("AAA".to_string(), 24)]);
+
+        // AAC starts on either end A or twice on the middle one; AAA runs along the row
+        // either way; CAB and BAD pass through whichever A touches both ends
+        let board = Board::from_rows(&["aaa", "bcd"]);
+        let mut solver = BoggleSolver::from_words(board, ["aac", "aaa", "cab", "bad"]).unwrap();
+        solver.set_count_paths(true);
+        let result = solver.solve().sorted_by(SortOrder::Paths);
+        let paths: Vec<(&str, u32)> = result.words.iter().map(|f| (f.word.as_str(), f.paths)).collect();
+        assert_eq!(paths, [("AAC", 4), ("AAA", 2), ("CAB", 2), ("BAD", 1)]);
+    }
+
+    #[test]
+    fn profiles_the_small_board() {
+        let result = small_solver(&["cat", "cater", "ace", "quest", "squat", "rote"]).solve();
+        // ACE and CAT; ROTE; CATER, QUEST and SQUAT
+        assert_eq!(result.histogram(), BTreeMap::from([(3, 2), (4, 1), (5, 3)]));
+        assert_eq!(result.average_length(), 25.0 / 6.0);
+        assert_eq!(result.max_score(), 2);
+        assert!(SolveResult { words: Vec::new() }.histogram().is_empty());
+    }
+
+    #[test]
+    fn heatmap_counts_the_words_through_each_cell() {
+        let result = small_solver(&["cat", "cater", "ace", "quest", "squat", "rote"]).solve();
+        // A is on every word but QUEST and ROTE, and O only on ROTE
+        assert_eq!(result.heatmap(3, 3), [[3, 4, 3], [2, 4, 2], [2, 2, 1]]);
+
+        // TAT goes back to the T it started on, which still counts once
+        let mut solver = small_solver(&["tat"]);
+        solver.set_allow_reuse(true);
+        let result = solver.solve();
+        assert_eq!(result.words[0].path, [(0, 2), (0, 1), (0, 2)]);
+        assert_eq!(result.heatmap(3, 3), [[0, 1, 1], [0, 0, 0], [0, 0, 0]]);
+    }
+
+    #[test]
+    fn sorts_each_way_breaking_ties_alphabetically() {
+        let result = small_solver(&["rote", "cat", "squat", "ace", "cater", "quest"]).solve();
+        let order = |order| -> Vec<String> {
+            let sorted = result.clone().sorted_by(order);
+            sorted.words.into_iter().map(|f| f.word).collect()
+        };
+        assert_eq!(order(SortOrder::Alpha), ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT"]);
+        assert_eq!(order(SortOrder::Length), ["CATER", "QUEST", "SQUAT", "ROTE", "ACE", "CAT"]);
+        // The five-letter words score 2 each, while ROTE and the three-letter words all
+        // score 1 and so fall back to alphabetical order
+        assert_eq!(order(SortOrder::Score), ["CATER", "QUEST", "SQUAT", "ACE", "CAT", "ROTE"]);
+    }
+
+    #[test]
+    fn stacked_layers_join_a_word_across_them() {
+        // C and T sit in the bottom layer, too far apart for a path of their own, and A
+        // in the layer above between them
+        let layers = vec![Board::from_rows(&["cx", "xt"]), Board::from_rows(&["xa", "xx"])];
+        let board = Board::stacked(layers).unwrap();
+        let flat = Board::from_rows(&["cx", "xt", "xa", "xx"]);
+        assert!(BoggleSolver::from_words(flat, ["cat"]).unwrap().solve().words.is_empty());
+
+        let result = BoggleSolver::from_words(board.clone(), ["cat"]).unwrap().solve();
+        assert_eq!(result.words.len(), 1);
+        let path: Vec<_> = result.words[0].path.iter().map(|&(r, c)| board.layer_cell(r, c)).collect();
+        assert_eq!(path, [(0, 0, 0), (1, 0, 1), (0, 1, 1)]);
+    }
+
+    #[test]
+    fn hex_cells_touch_three_to_six_others() {
+        let rows = ["abc", "defg", "hijkl", "mnop", "qrs"];
+        let board = Board::hex(rows.iter().map(|row| row.chars().map(String::from).collect()).collect()).unwrap();
+        let solver = BoggleSolver::from_words(board, ["cab"]).unwrap();
+        let touching = |r: i32, c: i32| {
+            let on_hexagon = |(r, c): (i32, i32)| !solver.board.tile(r as usize, c as usize).is_empty();
+            if !on_hexagon((r, c)) {
+                return 0;
+            }
+            let neighbours = solver.directions().iter().filter_map(|&step| solver.step(r, c, step));
+            neighbours.filter(|&cell| on_hexagon(cell)).count()
+        };
+        let counts: Vec<Vec<usize>> = (0..5).map(|r| (0..5).map(|c| touching(r, c)).collect()).collect();
+        // The six corners touch 3, the rest of the rim 4 and everything inside 6
+        let expected = [[0, 0, 3, 4, 3], [0, 4, 6, 6, 4], [3, 6, 6, 6, 3], [4, 6, 6, 4, 0], [3, 4, 3, 0, 0]];
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn without_diagonals_only_orthogonal_words_remain() {
+        // SET runs corner to corner through E, while CAT and ROT only step sideways or down
+        let mut solver = small_solver(&["set", "cat", "rot"]);
+        let result = solver.solve();
+        assert_eq!(words(&result), [("CAT", false), ("ROT", false), ("SET", false)]);
+
+        solver.set_diagonals(false);
+        assert_eq!(words(&solver.solve()), [("CAT", false), ("ROT", false)]);
+    }
+
+    #[test]
+    fn wrapping_joins_the_left_and_right_edges() {
+        // The T of CAT is only next to the A across the edge
+        let board = Board::from_rows(&["txxca", "xxxxx", "xxxxx"]);
+        let mut solver = BoggleSolver::from_words(board, ["cat"]).unwrap();
+        assert!(solver.solve().words.is_empty());
+
+        solver.set_wrap(true);
+        let result = solver.solve();
+        assert_eq!(result.words.len(), 1);
+        assert_eq!(result.words[0].word, "CAT");
+        assert_eq!(result.words[0].path, [(0, 3), (0, 4), (0, 0)]);
+    }
+
+    #[test]
+    fn a_donut_board_keeps_words_around_its_hole() {
+        // SCAT and DOGE go around the blocked middle; SUE and CUE would cross it
+        let dict = ["scat", "doge", "sue", "cue"];
+        let filled = Board::parse("cat/sue/dog", &ParseOptions::default()).unwrap();
+        let result = BoggleSolver::from_words(filled, dict).unwrap().solve();
+        assert_eq!(words(&result), [("CUE", false), ("DOGE", false), ("SCAT", false), ("SUE", false)]);
+
+        let donut = Board::parse("cat/s#e/dog", &ParseOptions::default()).unwrap();
+        let result = BoggleSolver::from_words(donut, dict).unwrap().solve();
+        assert_eq!(words(&result), [("DOGE", false), ("SCAT", false)]);
+        assert_eq!(result.heatmap(3, 3), [[1, 1, 1], [1, 0, 1], [1, 1, 1]]);
+    }
+
+    #[test]
+    fn anagram_mode_ignores_adjacency_but_not_tile_counts() {
+        // TACO and QUOTE jump between distant tiles, and TOTS takes both Ts; TOOT needs
+        // a second O and CATTLE an L
+        let mut solver = small_solver(&["cat", "ace", "taco", "quote", "tots", "toot", "cattle"]);
+        assert_eq!(words(&solver.solve()), [("ACE", false), ("CAT", false)]);
+
+        solver.set_mode(Mode::Anagram);
+        let result = solver.solve();
+        let expected = [("ACE", false), ("CAT", false), ("QUOTE", false), ("TACO", false), ("TOTS", false)];
+        assert_eq!(words(&result), expected);
+        assert!(result.words.iter().all(|f| f.path.is_empty()));
+    }
+
+    #[test]
+    fn wordsearch_finds_only_straight_lines() {
+        // SET runs up the anti-diagonal and TAC backwards along the top row, while ACE,
+        // QUEST and ROTE bend
+        let mut solver = small_solver(&["cat", "tac", "set", "ace", "quest", "rote"]);
+        let result = solver.solve();
+        let boggle: Vec<&str> = words(&result).into_iter().map(|(w, _)| w).collect();
+        assert_eq!(boggle, ["ACE", "CAT", "QUEST", "ROTE", "SET", "TAC"]);
+
+        solver.set_mode(Mode::WordSearch);
+        let result = solver.solve();
+        let straight: Vec<&str> = words(&result).into_iter().map(|(w, _)| w).collect();
+        assert_eq!(straight, ["CAT", "SET", "TAC"]);
+        let set = result.words.iter().find(|f| f.word == "SET").unwrap();
+        assert_eq!(set.path, [(2, 0), (1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn wordsearch_never_finds_a_word_boggle_misses() {
+        // Each line is also a Boggle path, so the other direction only narrows: reusing
+        // cells or wrapping lets Boggle find more, never fewer
+        let mut rng = Rng::new(31);
+        for round in 0..100 {
+            let (rows, cols) = (3 + rng.below(3), 3 + rng.below(3));
+            let board = random_board(&mut rng, rows, cols, 1);
+            let list = random_words(&mut rng, 300);
+            let mut solver = BoggleSolver::from_words(board.clone(), &list).unwrap();
+            solver.set_allow_reuse(rng.below(3) == 0);
+            solver.set_wrap(rng.below(3) == 0);
+            solver.set_diagonals(rng.below(4) != 0);
+            let boggle = solver.solve();
+            solver.set_mode(Mode::WordSearch);
+            for (word, _) in words(&solver.solve()) {
+                assert!(boggle.words.iter().any(|f| f.word == word), "round {}, {}: {:?}", round, word, board);
+            }
+        }
+    }
+
+    #[test]
+    fn board_and_words_algorithms_agree() {
+        let mut rng = Rng::new(80);
+        for round in 0..150 {
+            let (rows, cols) = (3 + rng.below(3), 3 + rng.below(3));
+            let reuse = rng.below(4) == 0;
+            let board = random_board(&mut rng, rows, cols, usize::from(!reuse));
+            let list = random_words(&mut rng, 300);
+            let dict = Dictionary::from_words_with(&list, LengthFilter::new(3, Some(7)).unwrap());
+            let mut solver = BoggleSolver::with_dictionary(board.clone(), dict).unwrap();
+            solver.set_allow_reuse(reuse);
+            solver.set_wrap(rng.below(4) == 0);
+            solver.set_diagonals(rng.below(4) != 0);
+            if rng.below(4) == 0 {
+                solver.set_mode(Mode::WordSearch);
+            }
+
+            solver.set_algorithm(Algorithm::Board);
+            let by_board = solver.solve();
+            for algorithm in [Algorithm::Words, Algorithm::Auto] {
+                solver.set_algorithm(algorithm);
+                assert_eq!(words(&solver.solve()), words(&by_board), "round {}, {:?}: {:?}", round, algorithm, board);
+                assert_eq!(words(&solver.solve_parallel(3)), words(&by_board), "round {}: {:?}", round, board);
+            }
+        }
+    }
+
+    #[test]
+    fn random_edits_match_a_fresh_solve() {
+        let mut rng = Rng::new(70);
+        for round in 0..20 {
+            let size = 3 + rng.below(3);
+            let board = random_board(&mut rng, size, size, 1);
+            let dict = Dictionary::from_words(random_words(&mut rng, 2000));
+            let mut solver = BoggleSolver::with_dictionary(board, dict).unwrap();
+            solver.set_wrap(rng.below(4) == 0);
+            solver.set_diagonals(rng.below(4) != 0);
+            let mut before: Vec<String> = Vec::new();
+            for edit in 0..25 {
+                let (r, c) = (rng.below(size), rng.below(size));
+                let tile = match rng.below(10) {
+                    0 => WILDCARD,
+                    1 => "",
+                    _ => LETTERS[rng.below(LETTERS.len())],
+                };
+                let delta = solver.update_cell(r, c, tile).unwrap();
+                let fresh = solver.with_board(solver.board().clone()).unwrap().solve();
+                let tracked = solver.tracked_words().unwrap();
+                assert_eq!(words(tracked), words(&fresh), "round {}, edit {}: {:?}", round, edit, solver.board());
+
+                // The first edit solves the whole board; after that the delta is the change
+                let after: Vec<String> = tracked.words.iter().map(|f| f.word.clone()).collect();
+                if edit > 0 {
+                    let mut expected: Vec<String> = before
+                        .iter()
+                        .filter(|word| !delta.removed.iter().any(|f| &f.word == *word))
+                        .cloned()
+                        .chain(delta.added.iter().map(|f| f.word.clone()))
+                        .collect();
+                    expected.sort();
+                    let mut after = after.clone();
+                    after.sort();
+                    assert_eq!(after, expected, "round {}, edit {}", round, edit);
+                }
+                before = after;
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::trie::{WordIndex, WordNode};
+
+/// A dictionary kept as one sorted list of words, with no nodes at all: a node is the
+/// run of words that start with the letters so far, and each step narrows it by binary
+/// search. It builds in a single sort, at the price of a logarithmic step where a trie
+/// follows a link.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortedWords {
+    words: Vec<String>,
+}
+
+/// A node of a `SortedWords`: the words sharing the first `depth` bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct SortedNode<'a> {
+    words: &'a [String],
+    depth: usize,
+}
+
+impl SortedWords {
+    /// Sorts `words` and drops repeats; the words are taken as they are, so they should
+    /// already be uppercase.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut words: Vec<String> = words.into_iter().map(Into::into).collect();
+        words.sort_unstable();
+        words.dedup();
+        SortedWords { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Bytes held by the list and its words, spare capacity included.
+    pub fn heap_bytes(&self) -> usize {
+        self.words.capacity() * std::mem::size_of::<String>() + self.words.iter().map(String::capacity).sum::<usize>()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.binary_search_by(|w| w.as_str().cmp(word)).is_ok()
+    }
+
+    pub fn root(&self) -> SortedNode<'_> {
+        SortedNode { words: &self.words, depth: 0 }
+    }
+}
+
+// The letter after the first `depth` bytes, or None for a word that ends there. Byte
+// order and letter order agree in UTF-8, so within a run these are sorted too
+fn next_letter(word: &str, depth: usize) -> Option<char> {
+    word[depth..].chars().next()
+}
+
+impl WordNode for SortedNode<'_> {
+    fn child(self, ch: char) -> Option<Self> {
+        let start = self.words.partition_point(|w| next_letter(w, self.depth) < Some(ch));
+        let len = self.words[start..].partition_point(|w| next_letter(w, self.depth) == Some(ch));
+        (len > 0).then(|| SortedNode { words: &self.words[start..start + len], depth: self.depth + ch.len_utf8() })
+    }
+
+    // The word that is just the letters so far sorts first in its run
+    fn is_word(self) -> bool {
+        self.words.first().is_some_and(|w| w.len() == self.depth)
+    }
+
+    fn children(self) -> Vec<(char, Self)> {
+        let mut children = Vec::new();
+        let mut rest = &self.words[usize::from(self.is_word())..];
+        while let Some(ch) = rest.first().and_then(|w| next_letter(w, self.depth)) {
+            let len = rest.partition_point(|w| next_letter(w, self.depth) == Some(ch));
+            children.push((ch, SortedNode { words: &rest[..len], depth: self.depth + ch.len_utf8() }));
+            rest = &rest[len..];
+        }
+        children
+    }
+}
+
+impl WordIndex for SortedWords {
+    type Node<'a> = SortedNode<'a>;
+
+    fn root(&self) -> SortedNode<'_> {
+        SortedWords::root(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::solver::BoggleSolver;
+
+    // A solver on `rows` with `words`, searching a sorted list when `sorted`
+    fn solve(rows: &[&str], words: &[&str], sorted: bool) -> Vec<String> {
+        let mut solver = BoggleSolver::from_words(Board::from_rows(rows), words).unwrap();
+        if sorted {
+            solver.sort_words();
+        }
+        let mut found: Vec<String> = solver.solve().words.into_iter().map(|f| f.word).collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn finds_the_same_words_as_the_trie() {
+        let words = ["cat", "cater", "caters", "ace", "quest", "squat", "rote", "toes", "tot", "zebra", "q"];
+        let expected = solve(&["cat", "qer", "sto"], &words, false);
+        assert_eq!(expected, ["ACE", "CAT", "CATER", "QUEST", "ROTE", "SQUAT", "TOES"]);
+        assert_eq!(solve(&["cat", "qer", "sto"], &words, true), expected);
+
+        // Letters of two and three bytes, where a byte-wise run must still split by letter
+        let words = ["café", "éta", "fée", "face", "ça", "cafés", "façe"];
+        let expected = solve(&["caf", "sçé", "tae"], &words, false);
+        assert_eq!(expected, ["CAFÉ", "FAÇE", "FÉE"]);
+        assert_eq!(solve(&["caf", "sçé", "tae"], &words, true), expected);
+    }
+
+    #[test]
+    fn walks_runs_of_words_like_trie_nodes() {
+        let sorted = SortedWords::new(["CAT", "CATER", "CAR", "ÉTÉ", "CAT"]);
+        assert_eq!(sorted.len(), 4);
+        assert!(sorted.contains("CAR") && sorted.contains("ÉTÉ") && !sorted.contains("CA"));
+
+        let ca = sorted.root().child('C').and_then(|c| c.child('A')).unwrap();
+        assert!(!ca.is_word());
+        assert_eq!(ca.children().into_iter().map(|(ch, _)| ch).collect::<String>(), "RT");
+        assert!(ca.child('T').unwrap().is_word());
+        assert!(ca.child('X').is_none());
+        let letters: String = sorted.root().children().into_iter().map(|(ch, _)| ch).collect();
+        assert_eq!(letters, "CÉ");
+        assert!(sorted.root().child('É').and_then(|e| e.child('T')).and_then(|t| t.child('É')).unwrap().is_word());
+    }
+}
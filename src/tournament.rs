@@ -0,0 +1,106 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::board::{Board, ParseOptions, strip_comment};
+use crate::error::BoggleError;
+use crate::score::score;
+use crate::solver::SolveResult;
+
+/// How one round of a series went: the board and what it offered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundScore {
+    pub board: Board,
+    pub words: usize,
+    /// The points for finding every word.
+    pub score: u32,
+    /// The highest-scoring word and its points, ties broken alphabetically.
+    pub best: Option<(String, u32)>,
+}
+
+impl RoundScore {
+    pub fn new(board: Board, result: &SolveResult) -> Self {
+        let best = result.highest_scoring(1).first().map(|&word| (word.to_string(), score(word)));
+        RoundScore { board, words: result.count(), score: result.total_score(), best }
+    }
+}
+
+/// A round numbered from 1 in the order the series lists it, with its score or why its
+/// board could not be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub number: usize,
+    pub outcome: Result<RoundScore, String>,
+}
+
+/// The rounds of a tournament, in order, and their totals over the rounds that were
+/// played.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Series {
+    pub rounds: Vec<Round>,
+}
+
+impl Series {
+    /// Adds the next round.
+    pub fn push(&mut self, outcome: Result<RoundScore, String>) {
+        self.rounds.push(Round { number: self.rounds.len() + 1, outcome });
+    }
+
+    /// The rounds that were played, with their numbers.
+    pub fn played(&self) -> impl Iterator<Item = (usize, &RoundScore)> {
+        self.rounds.iter().filter_map(|round| round.outcome.as_ref().ok().map(|score| (round.number, score)))
+    }
+
+    pub fn words(&self) -> usize {
+        self.played().map(|(_, round)| round.words).sum()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.played().map(|(_, round)| round.score).sum()
+    }
+
+    /// The highest-scoring word of the whole series, with its round and points. Ties go
+    /// to the earlier round.
+    pub fn best(&self) -> Option<(usize, &str, u32)> {
+        self.played()
+            .filter_map(|(number, round)| round.best.as_ref().map(|(word, points)| (number, word.as_str(), *points)))
+            .fold(None, |best, round| match best {
+                Some((_, _, points)) if points >= round.2 => best,
+                _ => Some(round),
+            })
+    }
+}
+
+/// Splits a series file into its boards, in file order. When the file has blank lines
+/// they separate the boards, each one or more rows; otherwise every line is a board of
+/// its own, as `Board::parse` reads it. Comments are as in `Board::from_text`. A board that does not
+/// parse keeps its place, so the rounds after it keep their numbers.
+pub fn parse_series(text: &str, opts: &ParseOptions) -> Vec<Result<Board, BoggleError>> {
+    let lines: Vec<&str> = text.lines().map(|line| strip_comment(line).trim()).collect();
+    // A comment-only line is not a separator
+    let separated = text.lines().zip(&lines).any(|(raw, line)| raw.trim().is_empty() && line.is_empty());
+    if !separated {
+        return lines.iter().filter(|line| !line.is_empty()).map(|line| Board::parse(line, opts)).collect();
+    }
+    let mut boards: Vec<Vec<&str>> = vec![Vec::new()];
+    for (raw, &line) in text.lines().zip(&lines) {
+        match boards.last_mut() {
+            Some(rows) if raw.trim().is_empty() && !rows.is_empty() => boards.push(Vec::new()),
+            Some(rows) if !line.is_empty() => rows.push(line),
+            _ => {}
+        }
+    }
+    boards.retain(|rows| !rows.is_empty());
+    boards.iter().map(|rows| Board::from_args(rows, opts)).collect()
+}
+
+/// Reads a series file; see `parse_series`.
+pub fn read_series<P: AsRef<Path>>(
+    path: P,
+    opts: &ParseOptions,
+) -> Result<Vec<Result<Board, BoggleError>>, BoggleError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .map_err(|e| BoggleError::BoardIo(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+    Ok(parse_series(&text, opts))
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use crate::dawg::Dawg;
+
+/// The traversal the search needs from a dictionary node: step by one letter, and
+/// whether the letters so far spell a word. A node is a cheap copyable handle, such as
+/// `&TrieNode` or an index into an `ArenaTrie`; the trie types and the DAWG implement it.
+pub trait WordNode: Copy {
+    fn child(self, ch: char) -> Option<Self>;
+    fn is_word(self) -> bool;
+    /// Every outgoing letter and its node, in letter order (a wildcard cell tries them all).
+    fn children(self) -> Vec<(char, Self)>;
+
+    /// For a structure that is a true tree, this node's index, unique among its nodes,
+    /// and how many words its subtree holds, its own included. The search then stops
+    /// at a node once it has found all of them. `None`, the default, where nodes are
+    /// shared by several prefixes, as in a DAWG, or have no index.
+    fn subtree(self) -> Option<(usize, u32)> {
+        None
+    }
+}
+
+/// A whole dictionary structure the search can walk: it hands out its root, and the
+/// nodes do the rest. The search is generic over this, so a new backend needs only
+/// these two traits.
+pub trait WordIndex {
+    type Node<'a>: WordNode
+    where
+        Self: 'a;
+
+    fn root(&self) -> Self::Node<'_>;
+}
+
+#[derive(Default)]
+pub struct TrieNode {
+    pub(crate) children: HashMap<char, TrieNode>,
+    pub(crate) is_word: bool,
+}
+
+impl TrieNode {
+    /// Adds `word`, returning whether it was not already present.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = self;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        !std::mem::replace(&mut node.is_word, true)
+    }
+
+    /// Removes `word`, pruning branches that no longer lead to any word. Returns whether
+    /// it was present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        // Returns (removed, whether this node can now be dropped by its parent)
+        fn go(node: &mut TrieNode, mut letters: std::str::Chars) -> (bool, bool) {
+            let removed = match letters.next() {
+                None => std::mem::replace(&mut node.is_word, false),
+                Some(ch) => {
+                    let Some(child) = node.children.get_mut(&ch) else { return (false, false) };
+                    let (removed, empty) = go(child, letters);
+                    if empty {
+                        node.children.remove(&ch);
+                    }
+                    removed
+                }
+            };
+            (removed, !node.is_word && node.children.is_empty())
+        }
+        go(self, word.chars()).0
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut node = self;
+        for c in word.chars() {
+            match node.children.get(&c) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        node.is_word
+    }
+
+    /// Number of nodes in this subtree, including this one.
+    pub fn node_count(&self) -> usize {
+        1 + self.children.values().map(TrieNode::node_count).sum::<usize>()
+    }
+
+    /// Builds the minimized automaton with identical suffix subtrees merged.
+    pub fn minimize(&self) -> Dawg {
+        Dawg::from_trie(self)
+    }
+}
+
+impl WordIndex for TrieNode {
+    type Node<'a> = &'a TrieNode;
+
+    fn root(&self) -> &TrieNode {
+        self
+    }
+}
+
+impl WordNode for &TrieNode {
+    fn child(self, ch: char) -> Option<Self> {
+        self.children.get(&ch)
+    }
+
+    fn is_word(self) -> bool {
+        self.is_word
+    }
+
+    fn children(self) -> Vec<(char, Self)> {
+        let mut children: Vec<(char, Self)> = self.children.iter().map(|(&ch, node)| (ch, node)).collect();
+        children.sort_by_key(|&(ch, _)| ch);
+        children
+    }
+}
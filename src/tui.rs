@@ -0,0 +1,327 @@
+//! A full-screen browser for a solve's words that highlights the selected word's path
+//! on the board. The terminal is driven with ANSI escapes and put into raw mode with
+//! `stty`, so this needs a Unix terminal; it is restored on exit and on a panic.
+
+use std::io::{self, IsTerminal, Read, Write};
+use std::panic;
+use std::process::{Command, Stdio};
+
+use crate::board::Board;
+use crate::score::score;
+use crate::solver::{FoundWord, SolveResult, WordFilter};
+
+// Lines taken by everything but the word list: the header, the gap under the board
+// and the footer
+const CHROME_LINES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Escape,
+    Char(char),
+}
+
+/// Shows `result` in an interactive view until the user quits. Up and down (or `k`
+/// and `j`) select a word, `/` edits a filter on the letters a word contains, and `q`
+/// quits.
+pub fn explore(board: &Board, result: &SolveResult) -> io::Result<()> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other("the interactive view needs a terminal"));
+    }
+    let terminal = Terminal::enter()?;
+    let mut view = View::new(board, &result.words);
+    let mut input = io::stdin().lock();
+    // Redrawn after each key press, which is also when a resize is seen
+    loop {
+        let (height, _) = terminal.size();
+        view.scroll(height.saturating_sub(board.rows() + CHROME_LINES).max(1));
+        terminal.draw(&view.render())?;
+        let key = loop {
+            if let Some(key) = read_key(&mut input)? {
+                break key;
+            }
+        };
+        if !view.handle(key) {
+            return Ok(());
+        }
+    }
+}
+
+// Raw mode for as long as this lives. The panic hook restores the terminal before the
+// message is printed, which would otherwise be lost on the alternate screen
+struct Terminal {
+    saved: String,
+    out: io::Stdout,
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        // Reads give up after a tenth of a second, so a lone Escape is not taken as
+        // the start of an arrow key forever
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        let previous = panic::take_hook();
+        let restore = saved.clone();
+        panic::set_hook(Box::new(move |info| {
+            leave(&restore);
+            previous(info);
+        }));
+        let mut out = io::stdout();
+        // Alternate screen, hidden cursor
+        out.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        Ok(Terminal { saved, out })
+    }
+
+    // Rows and columns, falling back to 24 x 80 when `stty` cannot tell
+    fn size(&self) -> (usize, usize) {
+        let size = stty(&["size"]).unwrap_or_default();
+        let mut parts = size.split_whitespace().map(|n| n.parse::<usize>().ok());
+        match (parts.next().flatten(), parts.next().flatten()) {
+            (Some(rows), Some(cols)) if rows > 0 && cols > 0 => (rows, cols),
+            _ => (24, 80),
+        }
+    }
+
+    fn draw(&self, screen: &str) -> io::Result<()> {
+        let mut out = self.out.lock();
+        out.write_all(b"\x1b[H\x1b[2J")?;
+        out.write_all(screen.as_bytes())?;
+        out.flush()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        leave(&self.saved);
+        drop(panic::take_hook());
+    }
+}
+
+// Back to the normal screen and the saved terminal settings
+fn leave(saved: &str) {
+    let mut out = io::stdout();
+    let _ = out.write_all(b"\x1b[?25h\x1b[?1049l");
+    let _ = out.flush();
+    let _ = stty(&[saved]);
+}
+
+// Runs `stty` on the terminal behind stdin and returns what it printed
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).stderr(Stdio::null()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed; is this a terminal?"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    Ok((input.read(&mut byte)? == 1).then_some(byte[0]))
+}
+
+// The next key press, or None when nothing was typed before the read timed out
+fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(first) = read_byte(input)? else { return Ok(None) };
+    let key = match first {
+        0x1b => match read_byte(input)? {
+            Some(b'[') | Some(b'O') => match read_byte(input)? {
+                Some(b'A') => Key::Up,
+                Some(b'B') => Key::Down,
+                Some(b'H') => Key::Home,
+                Some(b'F') => Key::End,
+                Some(code @ b'1'..=b'8') => {
+                    // "ESC [ 5 ~" and friends; skip to the closing '~'
+                    while read_byte(input)?.is_some_and(|b| b != b'~') {}
+                    match code {
+                        b'1' | b'7' => Key::Home,
+                        b'4' | b'8' => Key::End,
+                        b'5' => Key::PageUp,
+                        b'6' => Key::PageDown,
+                        _ => return Ok(None),
+                    }
+                }
+                _ => return Ok(None),
+            },
+            _ => Key::Escape,
+        },
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        // Raw mode turns off Ctrl-C's signal, so it quits like 'q'
+        0x03 => Key::Char('q'),
+        byte if byte < 0x80 => Key::Char(byte as char),
+        byte => {
+            // The rest of a UTF-8 sequence, as many bytes as the leading byte says
+            let len = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..len {
+                bytes.extend(read_byte(input)?);
+            }
+            match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+                Some(ch) => Key::Char(ch),
+                None => return Ok(None),
+            }
+        }
+    };
+    Ok(Some(key))
+}
+
+// What is on screen: the words passing the filter, the selected one and the first
+// one shown
+struct View<'a> {
+    board: &'a Board,
+    words: &'a [FoundWord],
+    filter: String,
+    editing: bool,
+    shown: Vec<usize>,
+    selected: usize,
+    top: usize,
+    list_rows: usize,
+}
+
+impl<'a> View<'a> {
+    fn new(board: &'a Board, words: &'a [FoundWord]) -> Self {
+        let mut view = View {
+            board,
+            words,
+            filter: String::new(),
+            editing: false,
+            shown: Vec::new(),
+            selected: 0,
+            top: 0,
+            list_rows: 1,
+        };
+        view.refilter();
+        view
+    }
+
+    fn refilter(&mut self) {
+        let filter = WordFilter::new().contains(&self.filter);
+        self.shown = (0..self.words.len()).filter(|&i| filter.matches(&self.words[i].word)).collect();
+        self.selected = 0;
+        self.top = 0;
+    }
+
+    // Fits the list into `rows` lines, keeping the selected word in sight
+    fn scroll(&mut self, rows: usize) {
+        self.list_rows = rows;
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + rows {
+            self.top = self.selected + 1 - rows;
+        }
+    }
+
+    // Applies a key press; false means quit
+    fn handle(&mut self, key: Key) -> bool {
+        if self.editing {
+            match key {
+                Key::Enter | Key::Escape => self.editing = false,
+                Key::Backspace => {
+                    self.filter.pop();
+                    self.refilter();
+                }
+                Key::Char(ch) if ch.is_alphabetic() => {
+                    self.filter.extend(ch.to_uppercase());
+                    self.refilter();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        let last = self.shown.len().saturating_sub(1);
+        match key {
+            Key::Char('q') => return false,
+            Key::Char('/') => self.editing = true,
+            Key::Escape if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.refilter();
+            }
+            Key::Up | Key::Char('k') => self.selected = self.selected.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.selected = (self.selected + 1).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(self.list_rows),
+            Key::PageDown => self.selected = (self.selected + self.list_rows).min(last),
+            Key::Home | Key::Char('g') => self.selected = 0,
+            Key::End | Key::Char('G') => self.selected = last,
+            _ => {}
+        }
+        true
+    }
+
+    fn current(&self) -> Option<&'a FoundWord> {
+        self.shown.get(self.selected).map(|&i| &self.words[i])
+    }
+
+    // The whole screen, lines ended with "\r\n" as raw mode does not add the return
+    fn render(&self) -> String {
+        let mut screen = String::new();
+        let total: u32 = self.words.iter().map(|f| score(&f.word)).sum();
+        screen += &format!("{} words, {} points", self.words.len(), total);
+        if !self.filter.is_empty() {
+            screen += &format!("; {} contain {}", self.shown.len(), self.filter);
+        }
+        screen += "\r\n\r\n";
+        self.render_board(&mut screen);
+        screen += "\r\n";
+
+        for (row, &i) in self.shown.iter().enumerate().skip(self.top).take(self.list_rows) {
+            let found = &self.words[i];
+            let mark = if found.uses_wildcard() { "*" } else { "" };
+            let line = format!(" {:<20} {:>2}", format!("{}{}", found.word, mark), score(&found.word));
+            if row == self.selected {
+                // Reverse video
+                screen += &format!("\x1b[7m{}\x1b[0m\r\n", line);
+            } else {
+                screen += &format!("{}\r\n", line);
+            }
+        }
+        for _ in self.shown.len().saturating_sub(self.top).min(self.list_rows)..self.list_rows {
+            screen += "\r\n";
+        }
+
+        if self.editing {
+            screen += &format!("Filter: {}_  (Enter to keep, Backspace to erase)", self.filter);
+        } else {
+            screen += "up/down: select  PgUp/PgDn: page  /: filter  Esc: clear filter  q: quit";
+        }
+        screen
+    }
+
+    // Each cell is its tile and, on the selected word's path, its step number. The
+    // first cell is green and the rest yellow
+    fn render_board(&self, screen: &mut String) {
+        let found = self.current();
+        let path = found.map_or(&[][..], |f| &f.path[..]);
+        for r in 0..self.board.rows() {
+            for c in 0..self.board.cols() {
+                let tile = if self.board.is_blocked(r, c) {
+                    "#".to_string()
+                } else {
+                    // A wildcard shows the letter it stood for in this word
+                    let stood_for = found.and_then(|f| f.wildcards.iter().find(|(cell, _)| *cell == (r, c)));
+                    let tile = stood_for.map_or_else(|| self.board.tile(r, c).to_string(), |(_, ch)| ch.to_string());
+                    let mut chars = tile.chars();
+                    chars.next().map_or(String::new(), |first| first.to_string() + &chars.as_str().to_lowercase())
+                };
+                let cell = match path.iter().position(|&cell| cell == (r, c)) {
+                    Some(0) => format!("\x1b[30;42m {:<2}{:>2} \x1b[0m", tile, 1),
+                    Some(step) => format!("\x1b[30;43m {:<2}{:>2} \x1b[0m", tile, step + 1),
+                    None => format!(" {:<2}   ", tile),
+                };
+                *screen += &cell;
+            }
+            *screen += "\r\n";
+        }
+    }
+}
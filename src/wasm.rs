@@ -0,0 +1,135 @@
+//! Entry points for a WebAssembly build, callable from JavaScript without any
+//! binding generator. Strings cross the boundary as UTF-8 bytes in the module's
+//! memory:
+//!
+//! ```js
+//! const bytes = (s) => new TextEncoder().encode(s);
+//! function put(s) {
+//!     const b = bytes(s);
+//!     const ptr = exports.alloc(b.length);
+//!     new Uint8Array(exports.memory.buffer, ptr, b.length).set(b);
+//!     return [ptr, b.length];
+//! }
+//! const packed = exports.solve_board(...put("srpseuimeahwwdzr"), ...put(dictText));
+//! const [ptr, len] = [Number(packed >> 32n), Number(packed & 0xffffffffn)];
+//! const json = new TextDecoder().decode(new Uint8Array(exports.memory.buffer, ptr, len));
+//! exports.dealloc(ptr, len);
+//! ```
+//!
+//! Inputs passed to `solve_board` are freed by it; the returned string must be freed
+//! with `dealloc`.
+
+use crate::board::{Board, ParseOptions};
+use crate::dictionary::{Dictionary, LengthFilter};
+use crate::error::BoggleError;
+use crate::json::Json;
+use crate::output::to_json;
+use crate::solver::BoggleSolver;
+
+/// Solves a board (`srpseuimeahwwdzr`, `srps/euim/eahw/wdzr`, or one row per line, as
+/// `Board::parse` reads it) against a newline-separated word list, returning the same
+/// JSON as `to_json`.
+pub fn solve_board(board: &str, dict: &str) -> Result<String, BoggleError> {
+    let board = Board::parse(board, &ParseOptions::default())?;
+    let dict = Dictionary::from_reader(dict.as_bytes(), "dictionary", LengthFilter::default())?;
+    let solver = BoggleSolver::with_dictionary(board, dict)?;
+    Ok(to_json(solver.board(), &solver.solve()))
+}
+
+/// Reserves `len` bytes of module memory for the caller to write a string into.
+#[unsafe(no_mangle)]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    // `with_capacity` allocates exactly `len` bytes
+    let mut buf = Vec::<u8>::with_capacity(len);
+    let ptr = buf.as_mut_ptr();
+    std::mem::forget(buf);
+    ptr
+}
+
+/// Frees memory from `alloc` or a string returned by `solve_board`.
+///
+/// # Safety
+///
+/// `ptr` and `len` must describe exactly one block handed out by this module and not
+/// yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(unsafe { Vec::from_raw_parts(ptr, 0, len) });
+}
+
+/// Exported as `solve_board`. Solves the board and dictionary strings at the given
+/// locations; the result is a JSON string packed as `pointer << 32 | length` (wasm32
+/// pointers are 32 bits). On failure the JSON is `{"error": "..."}`.
+///
+/// # Safety
+///
+/// Both inputs must be blocks from `alloc` of exactly the given lengths, filled with
+/// UTF-8 text. They are freed before this returns.
+#[unsafe(export_name = "solve_board")]
+pub unsafe extern "C" fn solve_board_raw(
+    board_ptr: *mut u8,
+    board_len: usize,
+    dict_ptr: *mut u8,
+    dict_len: usize,
+) -> u64 {
+    let board = unsafe { take_string(board_ptr, board_len) };
+    let dict = unsafe { take_string(dict_ptr, dict_len) };
+    let json = match (board, dict) {
+        (Ok(board), Ok(dict)) => solve_board(&board, &dict).unwrap_or_else(|e| error_json(&e.to_string())),
+        _ => error_json("input is not valid UTF-8"),
+    };
+
+    // A boxed slice has no spare capacity, so `dealloc` can rebuild it from the length
+    let bytes = json.into_bytes().into_boxed_slice();
+    let len = bytes.len();
+    let ptr = Box::into_raw(bytes) as *mut u8;
+    ((ptr as usize as u64) << 32) | len as u64
+}
+
+// Takes ownership of an `alloc` block holding `len` bytes of text
+unsafe fn take_string(ptr: *mut u8, len: usize) -> Result<String, std::string::FromUtf8Error> {
+    String::from_utf8(unsafe { Vec::from_raw_parts(ptr, len, len) })
+}
+
+fn error_json(message: &str) -> String {
+    Json::object(vec![("error", Json::from(message))]).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: &str = "cat\nace\nquest\nrote\ndog\n";
+
+    fn words(json: &str) -> Vec<String> {
+        let json = Json::parse(json).unwrap();
+        let words = json.get("words").and_then(Json::as_array).unwrap();
+        words.iter().map(|w| w.get("word").and_then(Json::as_str).unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn solves_a_board_string_against_word_list_text() {
+        let json = solve_board("cat/qer/sto", WORDS).unwrap();
+        assert_eq!(words(&json), ["QUEST", "ROTE", "ACE", "CAT"]);
+        assert_eq!(Json::parse(&json).unwrap().get("score").and_then(Json::as_int), Some(5));
+        assert_eq!(solve_board("catqersto", WORDS).unwrap(), json);
+        assert!(solve_board("cat/qe", WORDS).is_err());
+    }
+
+    // The packed pointer only fits on a 32-bit target, so the raw export runs there alone
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn the_export_answers_through_module_memory() {
+        let put = |s: &str| {
+            let ptr = alloc(s.len());
+            unsafe { std::ptr::copy_nonoverlapping(s.as_ptr(), ptr, s.len()) };
+            (ptr, s.len())
+        };
+        let ((board, board_len), (dict, dict_len)) = (put("cat/qer/sto"), put(WORDS));
+        let packed = unsafe { solve_board_raw(board, board_len, dict, dict_len) };
+        let (ptr, len) = ((packed >> 32) as usize as *mut u8, (packed & 0xffff_ffff) as usize);
+        let json = String::from_utf8(unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec()).unwrap();
+        unsafe { dealloc(ptr, len) };
+        assert_eq!(words(&json), ["QUEST", "ROTE", "ACE", "CAT"]);
+    }
+}
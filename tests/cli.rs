@@ -0,0 +1,324 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const BOARD: [&str; 3] = ["cat", "qer", "sto"];
+const WORDS: &str = "cat\ncater\nace\nquest\nsquat\nrote\n";
+
+// A file of this test's own under the temp directory, so tests running at once never share one
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("boggle_cli_{}_{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_boggle_solver"))
+        .args(args)
+        // Nothing from the environment picks a dictionary or reads a config
+        .env_remove("BOGGLE_DICT")
+        .env("BOGGLE_CONFIG", std::env::temp_dir().join("boggle_cli_no_config.toml"))
+        .env("NO_COLOR", "1")
+        .current_dir(std::env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+// Runs against the small word list with the board after `args`
+fn solve(name: &str, args: &[&str]) -> Output {
+    let dict = temp_file(&format!("{}.txt", name), WORDS);
+    let dict = dict.to_str().unwrap();
+    let args: Vec<&str> = ["--dict", dict].iter().chain(args).chain(&BOARD).copied().collect();
+    run(&args, "")
+}
+
+fn code(output: &Output) -> i32 {
+    output.status.code().expect("killed by a signal")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn exits_0_on_success() {
+    let output = solve("success", &[]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("Total words found: 6\nTotal score: 9\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn an_excluded_word_never_shows() {
+    // QUEST is in both word lists, in different cases, and is excluded in a third
+    let dict = temp_file("exclude_words.txt", WORDS);
+    let more = temp_file("exclude_more.txt", "QUEST\ntoes\n");
+    let excluded = temp_file("exclude_bad.txt", "Quest\n");
+    let (dict, more, excluded) = (dict.to_str().unwrap(), more.to_str().unwrap(), excluded.to_str().unwrap());
+    let modes = [
+        &[][..],
+        &["--all"],
+        &["--paths"],
+        &["--by-score", "--top", "all"],
+        &["--live"],
+        &["--json"],
+        &["--format", "ndjson"],
+        &["--format", "csv"],
+        &["--format", "markdown"],
+    ];
+    for mode in modes {
+        let base = ["--dict", dict, "--dict", more, "--exclude", excluded];
+        let args: Vec<&str> = base.iter().chain(mode).chain(&BOARD).copied().collect();
+        let output = run(&args, "");
+        assert_eq!(code(&output), 0, "{:?}: {}", mode, stderr(&output));
+        let text = stdout(&output);
+        assert!(text.contains("TOES"), "{:?}: {}", mode, text);
+        assert!(!text.to_lowercase().contains("quest"), "{:?}: {}", mode, text);
+    }
+    let args = ["--dict", dict, "--dict", more, "--exclude", excluded, "cat", "qer", "sto"];
+    assert!(stdout(&run(&args, "")).starts_with("Total words found: 6\n"));
+}
+
+#[test]
+fn ndjson_is_one_object_per_line_ending_in_a_summary() {
+    let output = solve("ndjson", &["--format", "ndjson"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let text = stdout(&output);
+    let lines: Vec<&str> = text.lines().collect();
+    let (summary, words) = lines.split_last().unwrap();
+    assert_eq!(words.len(), 6, "{}", text);
+    for line in words {
+        assert!(line.starts_with("{\"type\":\"word\",\"word\":\"") && line.ends_with('}'), "{}", line);
+    }
+    let board = "{\"type\":\"summary\",\"board\":[[\"C\",\"A\",\"T\"],[\"QU\",\"E\",\"R\"],[\"S\",\"T\",\"O\"]],";
+    assert_eq!(*summary, format!("{}\"count\":6,\"score\":9}}", board));
+}
+
+#[test]
+fn required_letters_and_cells_shrink_the_results() {
+    let cases = [
+        (
+            &["--require-letter", "q"][..],
+            "Total words found: 2 (of 6 before filtering)\nTotal score: 4 (of 9 before filtering)\n",
+        ),
+        // Through the middle E
+        (
+            &["--require-cell", "1,1"],
+            "Total words found: 4 (of 6 before filtering)\nTotal score: 6 (of 9 before filtering)\n",
+        ),
+        (
+            &["--require-letter", "T", "--require-cell", "1,1"],
+            "Total words found: 3 (of 6 before filtering)\nTotal score: 5 (of 9 before filtering)\n",
+        ),
+    ];
+    for (args, totals) in cases {
+        let output = solve("require", args);
+        assert_eq!(code(&output), 0, "{:?}: {}", args, stderr(&output));
+        assert!(stdout(&output).starts_with(totals), "{:?}: {}", args, stdout(&output));
+    }
+    let output = solve("require", &["--require-letter", "q", "--require-cell", "1,1", "--all"]);
+    assert_eq!(stdout(&output), "QUEST\n");
+
+    let output = solve("require_off", &["--require-cell", "3,0"]);
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("cell 3,0 is off the 3 x 3 board"), "{}", stderr(&output));
+}
+
+#[test]
+fn coverage_flags_a_word_on_every_tile() {
+    let dict = temp_file("coverage.txt", "abcdefghijklmnoqu\nabcd\n");
+    let output = run(&["--dict", dict.to_str().unwrap(), "--coverage", "abcd", "hgfe", "ijkl", "qonm"], "");
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let expected =
+        "\nMost tiles covered:\n  16  ABCDEFGHIJKLMNOQU\n   4  ABCD\nCovering all 16 tiles: [\"ABCDEFGHIJKLMNOQU\"]\n";
+    assert!(stdout(&output).ends_with(expected), "{}", stdout(&output));
+
+    let output = solve("coverage_none", &["--coverage"]);
+    assert!(stdout(&output).ends_with("No word covers all 9 tiles\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn top_lists_no_more_words_than_the_board_has() {
+    let dict = temp_file("top.txt", "cat\nace\nquest\nrote\n");
+    let dict = dict.to_str().unwrap();
+    let output = run(&["--dict", dict, "--top", "10", "cat", "qer", "sto"], "");
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let longest = "\nLongest 4 words: [\"QUEST\", \"ROTE\", \"ACE\", \"CAT\"]\n";
+    assert!(stdout(&output).contains(longest), "{}", stdout(&output));
+
+    let output = run(&["--dict", dict, "--top", "2", "cat", "qer", "sto"], "");
+    assert!(stdout(&output).contains("\nLongest 2 words: [\"QUEST\", \"ROTE\"]\n"), "{}", stdout(&output));
+    let output = run(&["--dict", dict, "--top", "0", "cat", "qer", "sto"], "");
+    assert!(!stdout(&output).contains("Longest"), "{}", stdout(&output));
+}
+
+#[test]
+fn no_diagonals_is_documented_and_drops_diagonal_words() {
+    let output = run(&["--help"], "");
+    assert!(stderr(&output).contains("  --no-diagonals "), "{}", stderr(&output));
+
+    // SET only runs corner to corner
+    let dict = temp_file("no_diagonals.txt", "set\ncat\n");
+    let dict = dict.to_str().unwrap();
+    let output = run(&["--dict", dict, "cat", "qer", "sto"], "");
+    assert!(stdout(&output).starts_with("Total words found: 2\n"), "{}", stdout(&output));
+    let output = run(&["--dict", dict, "--no-diagonals", "cat", "qer", "sto"], "");
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("Total words found: 1\n"), "{}", stdout(&output));
+    assert!(!stdout(&output).contains("SET"), "{}", stdout(&output));
+}
+
+#[test]
+fn exits_2_on_bad_usage() {
+    for args in [&["--no-such-flag"][..], &["--top"], &["--min-len", "abc"], &["--sort", "sideways"]] {
+        let output = solve("usage", args);
+        assert_eq!(code(&output), 2, "{:?}: {}", args, stderr(&output));
+    }
+    let output = run(&["--min-len"], "");
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("--min-len"), "{}", stderr(&output));
+}
+
+#[test]
+fn exits_3_on_a_bad_board() {
+    let dict = temp_file("bad_board.txt", WORDS);
+    let output = run(&["--dict", dict.to_str().unwrap(), "ab1", "cde"], "");
+    assert_eq!(code(&output), 3);
+    assert!(stderr(&output).contains("row 1, column 3: '1' is not a letter"), "{}", stderr(&output));
+
+    let output = run(&["--dict", dict.to_str().unwrap(), "ab", "cde"], "");
+    assert_eq!(code(&output), 3);
+    assert!(stderr(&output).contains("row 2 has 3 tiles, expected 2"), "{}", stderr(&output));
+}
+
+// A pipe can be read only once, so sniffing the file for an FST must not lose its start
+#[cfg(unix)]
+#[test]
+fn reads_a_dictionary_piped_in() {
+    let args: Vec<&str> = ["--dict", "/dev/stdin"].into_iter().chain(BOARD).collect();
+    let output = run(&args, WORDS);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("Total words found: 6\nTotal score: 9\n"), "{}", stdout(&output));
+}
+
+#[test]
+fn exits_4_on_an_unreadable_file() {
+    let dict = temp_file("unreadable.txt", WORDS);
+    let missing = std::env::temp_dir().join("boggle_cli_no_such_board.txt");
+    let output = run(&["--dict", dict.to_str().unwrap(), "--board", missing.to_str().unwrap()], "");
+    assert_eq!(code(&output), 4);
+    assert!(stderr(&output).contains("cannot read board"), "{}", stderr(&output));
+}
+
+#[test]
+fn exits_5_on_a_missing_dictionary() {
+    let missing = std::env::temp_dir().join("boggle_cli_no_such_dict.txt");
+    let args: Vec<&str> = ["--dict", missing.to_str().unwrap()].into_iter().chain(BOARD).collect();
+    let output = run(&args, "");
+    assert_eq!(code(&output), 5);
+    assert!(stderr(&output).contains("cannot read dictionary"), "{}", stderr(&output));
+}
+
+// With the embedded word list there is always a dictionary to fall back on
+#[cfg(not(feature = "embedded-dict"))]
+#[test]
+fn exits_5_when_no_dictionary_is_found() {
+    let output = run(&BOARD, "");
+    assert_eq!(code(&output), 5);
+    assert!(stderr(&output).contains("no dictionary found"), "{}", stderr(&output));
+}
+
+#[test]
+fn exits_5_on_a_dictionary_over_the_cap() {
+    let output = solve("over_cap", &["--max-dict-words", "2"]);
+    assert_eq!(code(&output), 5);
+    assert!(stderr(&output).contains("dictionary has more than 2 words"), "{}", stderr(&output));
+    assert_eq!(code(&solve("under_cap", &["--max-dict-words", "6"])), 0);
+}
+
+#[test]
+fn exits_6_on_invalid_judged_words() {
+    let judged = temp_file("judged.txt", "cat\ndog\n");
+    let output = solve("judge", &["--judge", judged.to_str().unwrap()]);
+    assert_eq!(code(&output), 6);
+    assert!(stdout(&output).contains("DOG   not in the dictionary"), "{}", stdout(&output));
+
+    let judged = temp_file("judged_ok.txt", "cat\nquest\n");
+    assert_eq!(code(&solve("judge_ok", &["--judge", judged.to_str().unwrap()])), 0);
+}
+
+#[test]
+fn exits_7_below_the_requirement() {
+    let output = solve("require", &["--require-words", "10"]);
+    assert_eq!(code(&output), 7);
+    assert!(stderr(&output).contains("Only 6 words on this board; --require-words asks for 10"), "{}", stderr(&output));
+    assert_eq!(code(&solve("require_score", &["--require-score", "10"])), 7);
+    assert_eq!(code(&solve("require_met", &["--require-words", "6", "--require-score", "9"])), 0);
+}
+
+#[test]
+fn repl_answers_commands_piped_in() {
+    let dict = temp_file("repl.txt", WORDS);
+    let input = "board cat qer sto\nsolve\ncheck quest\ncheck tot\ntop 2\nquit\nsolve\n";
+    let output = run(&["repl", "--dict", dict.to_str().unwrap()], input);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "C  A  T\n\
+         Qu E  R\n\
+         S  T  O\n\
+         \n\
+         Total words found: 6\n\
+         Total score: 9\n\
+         Longest 6 words: [\"CATER\", \"QUEST\", \"SQUAT\", \"ROTE\", \"ACE\", \"CAT\"]\n\
+         QUEST: (1,0) -> (1,1) -> (2,0) -> (2,1)\n\
+         TOT: not playable (not in the dictionary and not on the board)\n\
+         CATER (2 points)\n\
+         QUEST (2 points)\n"
+    );
+}
+
+#[test]
+fn repl_reports_a_bad_board_and_carries_on() {
+    let dict = temp_file("repl_bad.txt", WORDS);
+    let input = "board ab1 cde\nboard cat qer sto\ncheck cat\n";
+    let output = run(&["repl", "--dict", dict.to_str().unwrap()], input);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(stdout(&output).ends_with("CAT: (0,0) -> (0,1) -> (0,2)\n"), "{}", stdout(&output));
+    assert!(stderr(&output).contains("'1' is not a letter"), "{}", stderr(&output));
+}
+
+#[test]
+fn a_second_run_is_answered_from_the_result_cache() {
+    let dir = std::env::temp_dir().join(format!("boggle_cli_{}_cache", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let cached = |name: &str| solve(name, &["--cache-dir", dir.to_str().unwrap(), "--stats"]);
+
+    let first = cached("cache_words.txt");
+    assert_eq!(code(&first), 0, "{}", stderr(&first));
+    assert!(stderr(&first).contains("Result cache:      miss"), "{}", stderr(&first));
+    let second = cached("cache_words.txt");
+    assert!(stderr(&second).contains("Result cache:      hit"), "{}", stderr(&second));
+    assert_eq!(stdout(&second), stdout(&first));
+
+    // A damaged entry is a miss, and the solve writes a good one in its place
+    let entries: Vec<PathBuf> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(entries.len(), 1, "{:?}", entries);
+    fs::write(&entries[0], "{\"version\": 1, \"key\": tru").unwrap();
+    let damaged = cached("cache_words.txt");
+    assert_eq!(code(&damaged), 0, "{}", stderr(&damaged));
+    assert!(stderr(&damaged).contains("Result cache:      miss"), "{}", stderr(&damaged));
+    assert_eq!(stdout(&damaged), stdout(&first));
+    let repaired = cached("cache_words.txt");
+    assert!(stderr(&repaired).contains("Result cache:      hit"), "{}", stderr(&repaired));
+    fs::remove_dir_all(&dir).unwrap();
+}